@@ -1,20 +1,18 @@
-#![feature(never_type)]
-
 use std::{env, process};
 
 use spinach::tokio;
-use spinach::tokio::net::TcpStream;
 
-use spinach::comp::{CompExt};
+use spinach::comp::{shutdown_signal, CompExt, Supervision};
 use spinach::lattice::set_union::SetUnionRepr;
-use spinach::op::{OpExt, ReadOp, TcpOp, TcpServerOp};
+use spinach::op::{OpExt, ReadOp, TcpClientOp, TcpServerOp};
+use spinach::tcp_client::TcpClient;
 use spinach::tcp_server::TcpServer;
 use spinach::tag;
 
 type WireLatRepr = SetUnionRepr<tag::SINGLE, String>;
 
 /// Run the server portion of the program.
-async fn server(url: &str) -> Result<!, String> {
+async fn server(url: &str) -> Result<(), String> {
 
     let server = TcpServer::bind(url).await.map_err(|e| e.to_string())?;
 
@@ -22,49 +20,44 @@ async fn server(url: &str) -> Result<!, String> {
     TcpServerOp::<WireLatRepr>::new(server.clone())
         .debug("server")
         .comp_tcp_server(server)
-        .run()
+        // Client connections failing shouldn't stop the server.
+        .supervise(Supervision::Restart(usize::MAX))
+        .run_until(shutdown_signal())
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| e.to_string())
 }
 
 /// Run the client portion of the program.
-async fn client<R: tokio::io::AsyncRead + std::marker::Unpin>(url: &str, input_read: R) -> Result<!, String> {
+async fn client<R: tokio::io::AsyncRead + std::marker::Unpin>(url: &str, input_read: R) -> Result<(), String> {
 
-    let (read, write) = TcpStream::connect(url).await.map_err(|e| e.to_string())?
-        .into_split();
+    let tcp_client = TcpClient::connect(url).await.map_err(|e| e.to_string())?;
 
-    let read_comp = TcpOp::<WireLatRepr>::new(read)
+    let read_comp = TcpClientOp::<WireLatRepr>::new(tcp_client.clone())
         .comp_debug("read");
 
     let write_comp = ReadOp::new(input_read)
-        .comp_tcp(write);
-
-    let result = tokio::join!(
-        async {
-            read_comp.run().await.map_err(|_| format!("Read failed."))
-        },
-        async {
-            write_comp.run().await.map_err(|e| e.to_string())
-        },
-    );
+        .comp_tcp_client(tcp_client);
 
-    Err(format!("Read error: {:?}, Write error: {:?}",
-        result.0.unwrap_err(), result.1.unwrap_err()))
+    read_comp
+        .join(write_comp)
+        .run()
+        .await
+        .map_err(|e| format!("{:?}", e))
 }
 
 /// Entry point of the application.
 #[tokio::main(flavor = "current_thread")]
-async fn main() -> Result<!, String> {
+async fn main() -> Result<(), String> {
     // Begin by parsing the arguments. We are either a server or a client, and
     // we need an address and potentially a sleep duration.
     let args: Vec<_> = env::args().collect();
 
     match &*args {
-        [_, mode, url]             if mode == "server" => server(url).await?,
-        [_, mode, url]             if mode == "client" => client(url, tokio::io::stdin()).await?,
+        [_, mode, url]             if mode == "server" => server(url).await,
+        [_, mode, url]             if mode == "client" => client(url, tokio::io::stdin()).await,
         [_, mode, url, input_file] if mode == "client" => {
             match tokio::fs::File::open(input_file).await {
-                Ok(file) => client(url, file).await?,
+                Ok(file) => client(url, file).await,
                 Err(err) => {
                     eprintln!("Failed to open input_file: \"{}\", error: {}", input_file, err);
                     process::exit(2);
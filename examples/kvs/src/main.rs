@@ -1,5 +1,4 @@
 #![feature(core_intrinsics)]
-#![feature(never_type)]
 
 use std::{env, process};
 use std::net::SocketAddr;
@@ -7,12 +6,11 @@ use std::net::SocketAddr;
 use serde::{Deserialize, Serialize};
 
 use spinach::tokio;
-use spinach::tokio::net::TcpStream;
+use spinach::tracing;
 
 use spinach::collections::Single;
-use spinach::comp::{CompExt};
-use spinach::func::binary::{HashPartitioned, TableProduct};
-use spinach::func::unary::{Morphism};
+use spinach::comp::{shutdown_signal, CompExt, Supervision};
+use spinach::func::unary::{Morphism, ParseClosure};
 use spinach::hide::{Hide, Qualifier};
 use spinach::lattice::LatticeRepr;
 use spinach::lattice::map_union::MapUnionRepr;
@@ -20,8 +18,9 @@ use spinach::lattice::set_union::SetUnionRepr;
 use spinach::lattice::dom_pair::DomPairRepr;
 use spinach::lattice::ord::MaxRepr;
 use spinach::lattice::pair::PairRepr;
-use spinach::op::{BinaryOp, OpExt, ReadOp, TcpOp, TcpServerOp};
+use spinach::op::{KeyRange, OpExt, ReadOp, TcpClientOp, TcpServerOp};
 use spinach::tag;
+use spinach::tcp_client::TcpClient;
 use spinach::tcp_server::TcpServer;
 
 type ValueLatRepr = DomPairRepr<MaxRepr<usize>, MaxRepr<String>>;
@@ -31,6 +30,8 @@ type ValueLatRepr = DomPairRepr<MaxRepr<usize>, MaxRepr<String>>;
 pub enum KvsOperation {
     Read(String),
     Write(String, <ValueLatRepr as LatticeRepr>::Repr),
+    /// Read all keys from the first (inclusive) to the second (exclusive).
+    Range(String, String),
 }
 
 type RequestLatRepr = SetUnionRepr<tag::SINGLE, KvsOperation>;
@@ -40,143 +41,163 @@ pub struct Switch;
 impl Morphism for Switch {
     type InLatRepr  = SetUnionRepr<tag::VEC, (SocketAddr, KvsOperation)>;
     type OutLatRepr = PairRepr<
-        MapUnionRepr<tag::VEC, String, SetUnionRepr<tag::VEC, SocketAddr>>,
-        MapUnionRepr<tag::VEC, String, ValueLatRepr>,
+        SetUnionRepr<tag::VEC, (SocketAddr, String)>,
+        PairRepr<
+            MapUnionRepr<tag::VEC, String, ValueLatRepr>,
+            SetUnionRepr<tag::VEC, (SocketAddr, KeyRange<String>)>,
+        >,
     >;
 
     fn call<Y: Qualifier>(&self, item: Hide<Y, Self::InLatRepr>) -> Hide<Y, Self::OutLatRepr> {
-        let (reads, writes) = item.switch::<tag::VEC, _>(|(_addr, operation)| {
-            match operation {
-                KvsOperation::Read(_) => true,
-                KvsOperation::Write(_, _) => false,
-            }
+        let (reads, rest) = item.switch::<tag::VEC, _>(|(_addr, operation)| {
+            matches!(operation, KvsOperation::Read(_))
+        });
+        let (writes, ranges) = rest.switch::<tag::VEC, _>(|(_addr, operation)| {
+            matches!(operation, KvsOperation::Write(_, _))
         });
 
         let reads = reads
             .map::<_, tag::VEC, _>(|(addr, operation)| {
                 match operation {
-                    KvsOperation::Read(key) => Single((key, Single(addr))),
-                    KvsOperation::Write(_, _) => panic!(),
+                    KvsOperation::Read(key) => (addr, key),
+                    _ => panic!(),
                 }
-            })
-            .fold::<MapUnionRepr<tag::VEC, String, SetUnionRepr<tag::VEC, SocketAddr>>, MapUnionRepr<tag::SINGLE, String, SetUnionRepr<tag::SINGLE, SocketAddr>>>();
+            });
 
         let writes = writes
             .map::<_, tag::VEC, _>(|(_addr, operation)| {
                     match operation {
-                        KvsOperation::Read(_) => panic!(),
                         KvsOperation::Write(key, val) => Single((key, val)),
+                        _ => panic!(),
                     }
             })
             .fold::<MapUnionRepr<tag::VEC, String, ValueLatRepr>, MapUnionRepr<tag::SINGLE, String, ValueLatRepr>>();
 
-        Hide::zip(reads, writes)
+        let ranges = ranges
+            .map::<_, tag::VEC, _>(|(addr, operation)| {
+                match operation {
+                    KvsOperation::Range(start, end) => (addr, KeyRange::new(start..end)),
+                    _ => panic!(),
+                }
+            });
+
+        Hide::zip(reads, Hide::zip(writes, ranges))
     }
 }
 
-/// Run the server portion of the program.
-async fn server(url: &str) -> Result<!, String> {
+/// Run the server portion of the program. Writes are persisted in DATA_DIR, if given.
+async fn server(url: &str, data_dir: Option<&str>) -> Result<(), String> {
 
     let server = TcpServer::bind(url).await.map_err(|e| e.to_string())?;
-    let (op_reads, op_writes) = TcpServerOp::<RequestLatRepr>::new(server.clone())
+    let (op_reads, op_rest) = TcpServerOp::<RequestLatRepr>::new(server.clone())
         // .debug("ingress")
+        .trace("ingress")
         .morphism_closure(|item| item.flatten_keyed::<tag::VEC>())
         .morphism(Switch)
         // .debug("split")
         .switch();
+    let (op_writes, op_ranges) = op_rest.switch();
 
-    type ReadsLatRepr = MapUnionRepr<tag::HASH_MAP, String, SetUnionRepr<tag::HASH_SET, SocketAddr>>;
     let op_reads = op_reads
         // .debug("read")
-        .lattice_default::<ReadsLatRepr>();
+        .trace("reads");
 
-    type WritesLatRepr = MapUnionRepr<tag::HASH_MAP, String, ValueLatRepr>;
+    // Ordered, for range reads.
+    type WritesLatRepr = MapUnionRepr<tag::BTREE_MAP, String, ValueLatRepr>;
     let op_writes = op_writes
         // .debug("write")
         .lattice_default::<WritesLatRepr>();
-
-    let binary_func = HashPartitioned::<String, _>::new(
-        TableProduct::<_, _, _, MapUnionRepr<tag::VEC, _, _>>::new());
-
-    let comp = BinaryOp::new(op_reads, op_writes, binary_func)
-        .morphism_closure(|item| item.transpose::<tag::VEC, tag::VEC>())
+    let op_writes = match data_dir {
+        Some(data_dir) => op_writes.persist(data_dir).map_err(|e| e.to_string())?,
+        None => op_writes,
+    };
+    let [op_writes_reads, op_writes_ranges] = op_writes.trace("writes").fixed_split();
+
+    // Reads stay subscribed, responding again whenever their key is written.
+    let read_comp = op_reads
+        .lookup(op_writes_reads)
+        .trace("responses")
+        .comp_tcp_server::<ResponseLatRepr, _>(server.clone());
+
+    let range_comp = op_ranges
+        .range_query(op_writes_ranges)
+        .trace("range_responses")
         .comp_tcp_server::<ResponseLatRepr, _>(server);
 
+    let comp = read_comp.join(range_comp);
+
+    // Stop on ctrl-c or SIGTERM, after flushing any in-flight responses.
     comp
-        .run()
+        // Client connections failing shouldn't stop the server.
+        .supervise(Supervision::Restart(usize::MAX))
+        .run_until(shutdown_signal())
         .await
-        .map_err(|e| format!("TcpComp error: {:?}", e))?;
+        .map_err(|e| format!("TcpComp error: {:?}", e))
 }
 
 
-pub struct ParseKvsOperation;
-impl Morphism for ParseKvsOperation {
-    type InLatRepr  = SetUnionRepr<tag::SINGLE, String>;
-    type OutLatRepr = SetUnionRepr<tag::OPTION, KvsOperation>;
-    fn call<Y: Qualifier>(&self, item: Hide<Y, Self::InLatRepr>) -> Hide<Y, Self::OutLatRepr> {
-        item.filter_map_one(|input| {
-            match ron::de::from_str::<KvsOperation>(&*input) {
-                Ok(operation) => {
-                    Some(operation)
-                },
-                Err(err) => {
-                    eprintln!("Failed to parse operation, error: {}", err);
-                    None
-                }
-            }
-        })
-    }
-}
-
 /// Run the client portion of the program.
-async fn client<R: tokio::io::AsyncRead + std::marker::Unpin>(url: &str, input_read: R) -> Result<!, String> {
+async fn client<R: tokio::io::AsyncRead + std::marker::Unpin>(url: &str, input_read: R) -> Result<(), String> {
 
-    let (read, write) = TcpStream::connect(url).await.map_err(|e| e.to_string())?
-        .into_split();
+    let tcp_client = TcpClient::connect(url).await.map_err(|e| e.to_string())?;
 
-    let read_comp = TcpOp::<ResponseLatRepr>::new(read)
+    let read_comp = TcpClientOp::<ResponseLatRepr>::new(tcp_client.clone())
         .comp_null();
         // .comp_debug("read");
 
-    let write_comp = ReadOp::new(input_read)
-        .morphism(ParseKvsOperation)
+    let (op_operations, op_errors) = ReadOp::new(input_read)
+        .morphism(ParseClosure::new(|input| ron::de::from_str::<KvsOperation>(input)))
+        .switch();
+
+    let error_comp = op_errors
+        .debottom()
+        .comp_debug("parse error");
+
+    let write_comp = op_operations
         .debottom()
-        .comp_tcp::<RequestLatRepr>(write);
-
-    #[allow(unreachable_code)]
-    let result = tokio::try_join!(
-        async {
-            read_comp.run().await.map_err(|_| format!("Read failed."))
-        },
-        async {
-            let err = write_comp.run().await.map_err(|e| e.to_string());
-            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
-            err
-        },
-    );
-
-    result?;
-    unreachable!();
-
-    // Err(format!("Read error: {:?}, Write error: {:?}",
-    //     result.0.unwrap_err(), result.1.unwrap_err()))
+        .comp_tcp_client::<RequestLatRepr>(tcp_client);
+
+    let writes = async {
+        error_comp
+            .join(write_comp)
+            .run()
+            .await
+            .map_err(|e| format!("{:?}", e))?;
+        // Wait for the remaining responses.
+        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+        Ok(())
+    };
+
+    tokio::select! {
+        result = read_comp.run() => result.map_err(|_| format!("Read failed.")),
+        result = writes => result,
+    }
 }
 
 
 
 /// Entry point of the application.
 #[tokio::main(flavor = "current_thread")]
-async fn main() -> Result<!, String> {
+async fn main() -> Result<(), String> {
     // Begin by parsing the arguments. We are either a server or a client, and
-    // we need an address and potentially a sleep duration.
+    // we need an address and potentially a data directory or input file.
     let args: Vec<_> = env::args().collect();
 
+    // Log the spans of each delta through the pipeline, with timings.
+    if env::var_os("KVS_TRACE").is_some() {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
+            .init();
+    }
+
     match &*args {
-        [_, mode, url]             if mode == "server" => server(url).await?,
-        [_, mode, url]             if mode == "client" => client(url, tokio::io::stdin()).await?,
+        [_, mode, url]             if mode == "server" => server(url, None).await,
+        [_, mode, url, data_dir]   if mode == "server" => server(url, Some(data_dir)).await,
+        [_, mode, url]             if mode == "client" => client(url, tokio::io::stdin()).await,
         [_, mode, url, input_file] if mode == "client" => {
             match tokio::fs::File::open(input_file).await {
-                Ok(file) => client(url, file).await?,
+                Ok(file) => client(url, file).await,
                 Err(err) => {
                     eprintln!("Failed to open input_file: \"{}\", error: {}", input_file, err);
                     process::exit(2);
@@ -184,7 +205,7 @@ async fn main() -> Result<!, String> {
             }
         }
         _ => {
-            eprintln!("Usage:\n{0} server <url>\n  or\n{0} client <url> [input_file]", args[0]);
+            eprintln!("Usage:\n{0} server <url> [data_dir]\n  or\n{0} client <url> [input_file]", args[0]);
             process::exit(1);
         }
     }
@@ -0,0 +1,117 @@
+//! Tracks live heap memory while merging a kvs-shaped workload into the
+//! server's read and write lattices, and checks that set compares and merges
+//! of already-present values do not allocate.
+//!
+//! Run with `cargo bench --bench merge_memory`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicIsize, AtomicUsize, Ordering};
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use spinach::collections::Single;
+use spinach::lattice::{Compare, Merge};
+use spinach::lattice::dom_pair::DomPairRepr;
+use spinach::lattice::map_union::MapUnionRepr;
+use spinach::lattice::ord::MaxRepr;
+use spinach::lattice::set_union::SetUnionRepr;
+use spinach::tag;
+
+struct Counting;
+
+static LIVE_BYTES: AtomicIsize = AtomicIsize::new(0);
+static ALLOCS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        LIVE_BYTES.fetch_add(layout.size() as isize, Ordering::Relaxed);
+        ALLOCS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE_BYTES.fetch_sub(layout.size() as isize, Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+type ValueLatRepr = DomPairRepr<MaxRepr<usize>, MaxRepr<String>>;
+type ReadsLatRepr = MapUnionRepr<tag::HASH_MAP, String, SetUnionRepr<tag::HASH_SET, SocketAddr>>;
+type ReadsDeltaRepr = MapUnionRepr<tag::SINGLE, String, SetUnionRepr<tag::SINGLE, SocketAddr>>;
+type WritesLatRepr = MapUnionRepr<tag::HASH_MAP, String, ValueLatRepr>;
+type WritesDeltaRepr = MapUnionRepr<tag::SINGLE, String, ValueLatRepr>;
+
+const KEYS: u64 = 10_000;
+const CLIENTS: u16 = 8;
+const ROUNDS: usize = 20;
+const OPS_PER_ROUND: usize = 50_000;
+
+fn live_bytes() -> isize {
+    LIVE_BYTES.load(Ordering::Relaxed)
+}
+
+fn allocs() -> usize {
+    ALLOCS.load(Ordering::Relaxed)
+}
+
+fn main() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut reads = <ReadsLatRepr as spinach::lattice::LatticeRepr>::Repr::default();
+    let mut writes = <WritesLatRepr as spinach::lattice::LatticeRepr>::Repr::default();
+
+    // Fill in every key and reader up front, so the state stops growing.
+    let key = |i: u64| format!("{:016x}", i);
+    let addr = |i: u16| SocketAddr::from(([127, 0, 0, 1], 8000 + i));
+    let mut version = 0;
+    for i in 0..KEYS {
+        for client in 0..CLIENTS {
+            <ReadsLatRepr as Merge<ReadsDeltaRepr>>::merge(&mut reads, Single((key(i), Single(addr(client)))));
+        }
+        version += 1;
+        <WritesLatRepr as Merge<WritesDeltaRepr>>::merge(&mut writes, Single((key(i), (version, format!("{:040x}", 0)))));
+    }
+    println!("filled:   {:10} live bytes, {:10} allocations", live_bytes(), allocs());
+
+    let mut round_live = Vec::new();
+    for round in 0..ROUNDS {
+        for _ in 0..OPS_PER_ROUND {
+            // Skew towards low keys, like the zipf-distributed workload.
+            let i = (rng.gen::<f64>().powi(4) * KEYS as f64) as u64;
+            if rng.gen_bool(0.75) {
+                <ReadsLatRepr as Merge<ReadsDeltaRepr>>::merge(&mut reads, Single((key(i), Single(addr(rng.gen_range(0..CLIENTS))))));
+            }
+            else {
+                version += 1;
+                let value = format!("{:040x}", rng.gen::<u128>());
+                <WritesLatRepr as Merge<WritesDeltaRepr>>::merge(&mut writes, Single((key(i), (version, value))));
+            }
+        }
+        round_live.push(live_bytes());
+        println!("round {:2}: {:10} live bytes, {:10} allocations", round, live_bytes(), allocs());
+    }
+
+    // Merges into the filled state must not grow live memory.
+    let first = round_live[0];
+    let last = round_live[ROUNDS - 1];
+    assert!(last <= first, "Live memory grew: {} -> {}.", first, last);
+
+    // Set compares and merges of already-present values must not allocate.
+    type SetRepr = SetUnionRepr<tag::HASH_SET, u64>;
+    type SingleRepr = SetUnionRepr<tag::SINGLE, u64>;
+    let set: HashSet<u64> = (0..KEYS).collect();
+    let other = set.clone();
+    let mut set = set;
+    let before = allocs();
+    for i in 0..KEYS {
+        assert!(!<SetRepr as Merge<SingleRepr>>::merge(&mut set, Single(i)));
+        assert_eq!(Some(std::cmp::Ordering::Equal), <SetRepr as Compare<SetRepr>>::compare(&set, &other));
+    }
+    assert_eq!(before, allocs(), "Set merges or compares allocated.");
+    println!("set merges and compares: 0 allocations");
+}
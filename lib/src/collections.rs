@@ -1,17 +1,20 @@
 use std::array::IntoIter;
+use std::cmp::Ordering;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::hash::Hash;
+use std::iter::FromIterator;
+
+use smallvec::SmallVec;
 
 fn bool_to_option<'a>(value: bool) -> Option<&'a ()> {
     if value { Some(&()) } else { None }
 }
-fn bool_to_option_mut<'a>(value: bool) -> Option<&'a mut ()> {
-    if value { Some(Box::leak(Box::new(()))) } else { None }
-}
 
 pub trait Collection<K, V> {
     fn get(&self, key: &K) -> Option<&V>;
-    fn get_mut(&mut self, key: &K) -> Option<&mut V>;
+    fn contains(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
     fn len(&self) -> usize;
     fn is_empty(&self) -> bool {
         0 == self.len()
@@ -24,13 +27,16 @@ pub trait Collection<K, V> {
     fn entries(&self) -> Self::Entries<'_>;
 }
 
+/// Collections whose values can be modified in place, i.e. maps. Sets have
+/// nothing to modify, so only implement `Collection`.
+pub trait CollectionMut<K, V>: Collection<K, V> {
+    fn get_mut(&mut self, key: &K) -> Option<&mut V>;
+}
+
 impl<K: 'static + Eq + Hash> Collection<K, ()> for HashSet<K> {
     fn get(&self, key: &K) -> Option<&()> {
         bool_to_option(self.contains(key))
     }
-    fn get_mut(&mut self, key: &K) -> Option<&mut ()> {
-        bool_to_option_mut(self.contains(key))
-    }
     fn len(&self) -> usize {
         self.len()
     }
@@ -50,9 +56,6 @@ impl<K: 'static + Eq + Ord> Collection<K, ()> for BTreeSet<K> {
     fn get(&self, key: &K) -> Option<&()> {
         bool_to_option(self.contains(key))
     }
-    fn get_mut(&mut self, key: &K) -> Option<&mut ()> {
-        bool_to_option_mut(self.contains(key))
-    }
     fn len(&self) -> usize {
         self.len()
     }
@@ -70,10 +73,7 @@ impl<K: 'static + Eq + Ord> Collection<K, ()> for BTreeSet<K> {
 
 impl<K: 'static + Eq> Collection<K, ()> for Vec<K> {
     fn get(&self, key: &K) -> Option<&()> {
-        bool_to_option(self.contains(key))
-    }
-    fn get_mut(&mut self, key: &K) -> Option<&mut ()> {
-        bool_to_option_mut(self.contains(key))
+        bool_to_option(self.as_slice().contains(key))
     }
     fn len(&self) -> usize {
         self.len()
@@ -94,9 +94,6 @@ impl<K: 'static + Eq> Collection<K, ()> for Option<K> {
     fn get(&self, key: &K) -> Option<&()> {
         bool_to_option(Some(key) == self.as_ref())
     }
-    fn get_mut(&mut self, key: &K) -> Option<&mut ()> {
-        bool_to_option_mut(Some(key) == self.as_ref())
-    }
     fn len(&self) -> usize {
         self.is_some().into()
     }
@@ -116,9 +113,6 @@ impl<K: 'static + Eq> Collection<K, ()> for Single<K> {
     fn get(&self, key: &K) -> Option<&()> {
         bool_to_option(key == &self.0)
     }
-    fn get_mut(&mut self, key: &K) -> Option<&mut ()> {
-        bool_to_option_mut(key == &self.0)
-    }
     fn len(&self) -> usize {
         1
     }
@@ -138,9 +132,6 @@ impl<K: 'static + Eq, const N: usize> Collection<K, ()> for Array<K, N> {
     fn get(&self, key: &K) -> Option<&()> {
         bool_to_option(self.0.contains(key))
     }
-    fn get_mut(&mut self, key: &K) -> Option<&mut ()> {
-        bool_to_option_mut(self.0.contains(key))
-    }
     fn len(&self) -> usize {
         N
     }
@@ -163,12 +154,6 @@ impl<K: 'static + Eq, const N: usize> Collection<K, ()> for MaskedArray<K, N> {
                 .any(|(mask, item)| *mask && item == key)
             )
     }
-    fn get_mut(&mut self, key: &K) -> Option<&mut ()> {
-        bool_to_option_mut(self.mask.iter()
-                .zip(self.vals.iter())
-                .any(|(mask, item)| *mask && item == key)
-            )
-    }
     fn len(&self) -> usize {
         self.mask.iter().filter(|mask| **mask).count()
     }
@@ -187,6 +172,64 @@ impl<K: 'static + Eq, const N: usize> Collection<K, ()> for MaskedArray<K, N> {
     }
 }
 
+impl<K: 'static + Eq + Ord> Collection<K, ()> for SortedVec<K> {
+    fn get(&self, key: &K) -> Option<&()> {
+        bool_to_option(self.0.binary_search(key).is_ok())
+    }
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    type Keys<'s> = std::slice::Iter<'s, K>;
+    fn keys(&self) -> Self::Keys<'_> {
+        self.0.iter()
+    }
+
+    type Entries<'s> = impl Iterator<Item = (&'s K, &'s ())>;
+    fn entries(&self) -> Self::Entries<'_> {
+        self.keys().map(|k| (k, &()))
+    }
+}
+
+impl<K: 'static + Eq, const N: usize> Collection<K, ()> for SmallVecSet<K, N> {
+    fn get(&self, key: &K) -> Option<&()> {
+        bool_to_option(self.0.contains(key))
+    }
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    type Keys<'s> = std::slice::Iter<'s, K>;
+    fn keys(&self) -> Self::Keys<'_> {
+        self.0.iter()
+    }
+
+    type Entries<'s> = impl Iterator<Item = (&'s K, &'s ())>;
+    fn entries(&self) -> Self::Entries<'_> {
+        self.keys().map(|k| (k, &()))
+    }
+}
+
+impl<K: 'static + Eq + Hash + Clone> Collection<K, ()> for im::HashSet<K> {
+    fn get(&self, key: &K) -> Option<&()> {
+        bool_to_option(self.contains(key))
+    }
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    type Keys<'s> = impl Iterator<Item = &'s K>;
+    fn keys(&self) -> Self::Keys<'_> {
+        self.iter()
+    }
+
+    type Entries<'s> = impl Iterator<Item = (&'s K, &'s ())>;
+    fn entries(&self) -> Self::Entries<'_> {
+        self.iter().map(|k| (k, &()))
+    }
+}
+
+
 
 
 
@@ -194,9 +237,31 @@ impl<K: 'static + Eq + Hash, V: 'static> Collection<K, V> for HashMap<K, V> {
     fn get(&self, key: &K) -> Option<&V> {
         self.get(key)
     }
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    type Keys<'s> = impl Iterator<Item = &'s K>;
+    fn keys(&self) -> Self::Keys<'_> {
+        self.keys()
+    }
+
+    type Entries<'s> = impl Iterator<Item = (&'s K, &'s V)>;
+    fn entries(&self) -> Self::Entries<'_> {
+        self.iter()
+    }
+}
+
+impl<K: 'static + Eq + Hash, V: 'static> CollectionMut<K, V> for HashMap<K, V> {
     fn get_mut(&mut self, key: &K) -> Option<&mut V> {
         self.get_mut(key)
     }
+}
+
+impl<K: 'static + Eq + Ord, V: 'static> Collection<K, V> for BTreeMap<K, V> {
+    fn get(&self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
     fn len(&self) -> usize {
         self.len()
     }
@@ -212,13 +277,41 @@ impl<K: 'static + Eq + Hash, V: 'static> Collection<K, V> for HashMap<K, V> {
     }
 }
 
-impl<K: 'static + Eq + Ord, V: 'static> Collection<K, V> for BTreeMap<K, V> {
+impl<K: 'static + Eq + Ord, V: 'static> CollectionMut<K, V> for BTreeMap<K, V> {
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.get_mut(key)
+    }
+}
+
+impl<K: 'static + Eq + Hash + Clone, V: 'static + Clone> Collection<K, V> for im::HashMap<K, V> {
     fn get(&self, key: &K) -> Option<&V> {
         self.get(key)
     }
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    type Keys<'s> = impl Iterator<Item = &'s K>;
+    fn keys(&self) -> Self::Keys<'_> {
+        self.keys()
+    }
+
+    type Entries<'s> = impl Iterator<Item = (&'s K, &'s V)>;
+    fn entries(&self) -> Self::Entries<'_> {
+        self.iter()
+    }
+}
+
+impl<K: 'static + Eq + Hash + Clone, V: 'static + Clone> CollectionMut<K, V> for im::HashMap<K, V> {
     fn get_mut(&mut self, key: &K) -> Option<&mut V> {
         self.get_mut(key)
     }
+}
+
+impl<K: 'static + Eq + Ord + Clone, V: 'static + Clone> Collection<K, V> for im::OrdMap<K, V> {
+    fn get(&self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
     fn len(&self) -> usize {
         self.len()
     }
@@ -234,17 +327,18 @@ impl<K: 'static + Eq + Ord, V: 'static> Collection<K, V> for BTreeMap<K, V> {
     }
 }
 
+impl<K: 'static + Eq + Ord + Clone, V: 'static + Clone> CollectionMut<K, V> for im::OrdMap<K, V> {
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.get_mut(key)
+    }
+}
+
 impl<K: 'static + Eq, V: 'static> Collection<K, V> for Vec<(K, V)> {
     fn get(&self, key: &K) -> Option<&V> {
         self.iter()
             .find(|(k, _)| k == key)
             .map(|(_, val)| val)
     }
-    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
-        self.iter_mut()
-            .find(|(k, _)| k == key)
-            .map(|(_, val)| val)
-    }
     fn len(&self) -> usize {
         self.len()
     }
@@ -262,14 +356,17 @@ impl<K: 'static + Eq, V: 'static> Collection<K, V> for Vec<(K, V)> {
     }
 }
 
-impl<K: 'static + Eq, V: 'static, const N: usize> Collection<K, V> for Array<(K, V), N> {
-    fn get(&self, key: &K) -> Option<&V> {
-        self.0.iter()
+impl<K: 'static + Eq, V: 'static> CollectionMut<K, V> for Vec<(K, V)> {
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.iter_mut()
             .find(|(k, _)| k == key)
             .map(|(_, val)| val)
     }
-    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
-        self.0.iter_mut()
+}
+
+impl<K: 'static + Eq, V: 'static, const N: usize> Collection<K, V> for Array<(K, V), N> {
+    fn get(&self, key: &K) -> Option<&V> {
+        self.0.iter()
             .find(|(k, _)| k == key)
             .map(|(_, val)| val)
     }
@@ -290,6 +387,14 @@ impl<K: 'static + Eq, V: 'static, const N: usize> Collection<K, V> for Array<(K,
     }
 }
 
+impl<K: 'static + Eq, V: 'static, const N: usize> CollectionMut<K, V> for Array<(K, V), N> {
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.0.iter_mut()
+            .find(|(k, _)| k == key)
+            .map(|(_, val)| val)
+    }
+}
+
 impl<K: 'static + Eq, V: 'static, const N: usize> Collection<K, V> for MaskedArray<(K, V), N> {
     fn get(&self, key: &K) -> Option<&V> {
         self.mask.iter()
@@ -297,12 +402,6 @@ impl<K: 'static + Eq, V: 'static, const N: usize> Collection<K, V> for MaskedArr
             .find(|(mask, (k, _))| **mask && k == key)
             .map(|(_, (_, val))| val)
     }
-    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
-        self.mask.iter()
-            .zip(self.vals.iter_mut())
-            .find(|(mask, (k, _))| **mask && k == key)
-            .map(|(_, (_, val))| val)
-    }
     fn len(&self) -> usize {
         self.mask.iter().filter(|mask| **mask).count()
     }
@@ -324,6 +423,78 @@ impl<K: 'static + Eq, V: 'static, const N: usize> Collection<K, V> for MaskedArr
     }
 }
 
+impl<K: 'static + Eq, V: 'static, const N: usize> CollectionMut<K, V> for MaskedArray<(K, V), N> {
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.mask.iter()
+            .zip(self.vals.iter_mut())
+            .find(|(mask, (k, _))| **mask && k == key)
+            .map(|(_, (_, val))| val)
+    }
+}
+
+
+impl<K: 'static + Eq + Ord, V: 'static> Collection<K, V> for SortedVecMap<K, V> {
+    fn get(&self, key: &K) -> Option<&V> {
+        self.0.binary_search_by(|(k, _)| k.cmp(key))
+            .ok()
+            .map(|i| &self.0[i].1)
+    }
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    type Keys<'s> = impl Iterator<Item = &'s K>;
+    fn keys(&self) -> Self::Keys<'_> {
+        self.0.iter()
+            .map(|(k, _)| k)
+    }
+
+    type Entries<'s> = impl Iterator<Item = (&'s K, &'s V)>;
+    fn entries(&self) -> Self::Entries<'_> {
+        self.0.iter()
+            .map(|(k, v)| (k, v))
+    }
+}
+
+impl<K: 'static + Eq + Ord, V: 'static> CollectionMut<K, V> for SortedVecMap<K, V> {
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.0.binary_search_by(|(k, _)| k.cmp(key))
+            .ok()
+            .map(move |i| &mut self.0[i].1)
+    }
+}
+
+impl<K: 'static + Eq, V: 'static, const N: usize> Collection<K, V> for SmallVecMap<K, V, N> {
+    fn get(&self, key: &K) -> Option<&V> {
+        self.0.iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, val)| val)
+    }
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    type Keys<'s> = impl Iterator<Item = &'s K>;
+    fn keys(&self) -> Self::Keys<'_> {
+        self.0.iter()
+            .map(|(k, _)| k)
+    }
+
+    type Entries<'s> = impl Iterator<Item = (&'s K, &'s V)>;
+    fn entries(&self) -> Self::Entries<'_> {
+        self.0.iter()
+            .map(|(k, v)| (k, v))
+    }
+}
+
+impl<K: 'static + Eq, V: 'static, const N: usize> CollectionMut<K, V> for SmallVecMap<K, V, N> {
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.0.iter_mut()
+            .find(|(k, _)| k == key)
+            .map(|(_, val)| val)
+    }
+}
+
 
 #[repr(transparent)]
 #[derive(Debug, Clone, Copy)]
@@ -376,4 +547,267 @@ impl<T, const N: usize> IntoIterator for MaskedArray<T, N> {
             .filter(|(mask, _)| *mask)
             .map(|(_, val)| val)
     }
-}
\ No newline at end of file
+}
+
+/// Merge two sorted runs. On equal keys only B's element is kept.
+fn merge_sorted<T>(a: Vec<T>, b: Vec<T>, cmp: impl Fn(&T, &T) -> Ordering) -> Vec<T> {
+    let mut out = Vec::with_capacity(a.len() + b.len());
+    let mut a = a.into_iter().peekable();
+    let mut b = b.into_iter().peekable();
+    loop {
+        let ord = match (a.peek(), b.peek()) {
+            (Some(x), Some(y)) => cmp(x, y),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => break,
+        };
+        match ord {
+            Ordering::Less => out.extend(a.next()),
+            Ordering::Greater => out.extend(b.next()),
+            Ordering::Equal => {
+                a.next();
+                out.extend(b.next());
+            }
+        }
+    }
+    out
+}
+
+/// A set stored as a sorted, deduplicated `Vec`. Lookups are binary searches,
+/// and extending sorts the new items then merges them in a single pass.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SortedVec<T>(Vec<T>);
+impl<T> SortedVec<T> {
+    pub fn as_slice(&self) -> &[T] {
+        &self.0
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.0
+    }
+}
+impl<T> Default for SortedVec<T> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+impl<T> IntoIterator for SortedVec<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+impl<T: Ord> FromIterator<T> for SortedVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut vec: Vec<T> = iter.into_iter().collect();
+        vec.sort();
+        vec.dedup();
+        Self(vec)
+    }
+}
+impl<T: Ord> Extend<T> for SortedVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let new = Self::from_iter(iter).0;
+        match (self.0.last(), new.first()) {
+            (_, None) => {}
+            (Some(last), Some(first)) if last < first => self.0.extend(new),
+            _ => self.0 = merge_sorted(std::mem::take(&mut self.0), new, T::cmp),
+        }
+    }
+}
+
+impl<T: serde::Serialize> serde::Serialize for SortedVec<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+impl<'de, T: Ord + serde::Deserialize<'de>> serde::Deserialize<'de> for SortedVec<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<T>::deserialize(deserializer).map(Self::from_iter)
+    }
+}
+
+/// A set stored as a deduplicated `SmallVec`, inline up to N items. Lookups are
+/// linear scans, so this is for small sets.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SmallVecSet<T, const N: usize>(SmallVec<[T; N]>);
+impl<T, const N: usize> SmallVecSet<T, N> {
+    pub fn as_slice(&self) -> &[T] {
+        &self.0
+    }
+
+    /// If the items no longer fit inline and have moved to the heap.
+    pub fn spilled(&self) -> bool {
+        self.0.spilled()
+    }
+
+    pub fn into_inner(self) -> SmallVec<[T; N]> {
+        self.0
+    }
+}
+impl<T, const N: usize> Default for SmallVecSet<T, N> {
+    fn default() -> Self {
+        Self(SmallVec::new())
+    }
+}
+impl<T, const N: usize> IntoIterator for SmallVecSet<T, N> {
+    type Item = T;
+    type IntoIter = smallvec::IntoIter<[T; N]>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+impl<T: Eq, const N: usize> FromIterator<T> for SmallVecSet<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = Self::default();
+        set.extend(iter);
+        set
+    }
+}
+impl<T: Eq, const N: usize> Extend<T> for SmallVecSet<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            if !self.0.contains(&item) {
+                self.0.push(item);
+            }
+        }
+    }
+}
+
+impl<T: serde::Serialize, const N: usize> serde::Serialize for SmallVecSet<T, N> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+impl<'de, T: Eq + serde::Deserialize<'de>, const N: usize> serde::Deserialize<'de> for SmallVecSet<T, N> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<T>::deserialize(deserializer).map(Self::from_iter)
+    }
+}
+
+/// A map stored as a `Vec` of entries sorted by key, see `SortedVec`. When a
+/// key is inserted more than once the last value is kept.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SortedVecMap<K, V>(Vec<(K, V)>);
+impl<K, V> SortedVecMap<K, V> {
+    pub fn as_slice(&self) -> &[(K, V)] {
+        &self.0
+    }
+
+    pub fn into_vec(self) -> Vec<(K, V)> {
+        self.0
+    }
+}
+impl<K, V> Default for SortedVecMap<K, V> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+impl<K, V> IntoIterator for SortedVecMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = std::vec::IntoIter<(K, V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+impl<K: Ord, V> FromIterator<(K, V)> for SortedVecMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut vec: Vec<(K, V)> = iter.into_iter().collect();
+        // Reverse so the stable sort puts the last value for each key first.
+        vec.reverse();
+        vec.sort_by(|(a, _), (b, _)| a.cmp(b));
+        vec.dedup_by(|(a, _), (b, _)| a == b);
+        Self(vec)
+    }
+}
+impl<K: Ord, V> Extend<(K, V)> for SortedVecMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        let new = Self::from_iter(iter).0;
+        match (self.0.last(), new.first()) {
+            (_, None) => {}
+            (Some((last, _)), Some((first, _))) if last < first => self.0.extend(new),
+            _ => self.0 = merge_sorted(std::mem::take(&mut self.0), new, |(a, _), (b, _)| a.cmp(b)),
+        }
+    }
+}
+
+impl<K: serde::Serialize, V: serde::Serialize> serde::Serialize for SortedVecMap<K, V> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+impl<'de, K: Ord + serde::Deserialize<'de>, V: serde::Deserialize<'de>> serde::Deserialize<'de> for SortedVecMap<K, V> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<(K, V)>::deserialize(deserializer).map(Self::from_iter)
+    }
+}
+
+/// A map stored as a `SmallVec` of entries with distinct keys, inline up to N
+/// entries, see `SmallVecSet`. When collected from entries with repeated keys
+/// the last value is kept. Merging a delta into a `SMALL_VEC` map instead
+/// merges the values of repeated keys.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SmallVecMap<K, V, const N: usize>(SmallVec<[(K, V); N]>);
+impl<K, V, const N: usize> SmallVecMap<K, V, N> {
+    pub fn as_slice(&self) -> &[(K, V)] {
+        &self.0
+    }
+
+    /// If the entries no longer fit inline and have moved to the heap.
+    pub fn spilled(&self) -> bool {
+        self.0.spilled()
+    }
+
+    pub fn into_inner(self) -> SmallVec<[(K, V); N]> {
+        self.0
+    }
+}
+impl<K: Eq, V, const N: usize> SmallVecMap<K, V, N> {
+    /// Insert VAL for KEY, returning the value it replaces.
+    pub fn insert(&mut self, key: K, val: V) -> Option<V> {
+        match self.0.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, old)) => Some(std::mem::replace(old, val)),
+            None => {
+                self.0.push((key, val));
+                None
+            }
+        }
+    }
+}
+impl<K, V, const N: usize> Default for SmallVecMap<K, V, N> {
+    fn default() -> Self {
+        Self(SmallVec::new())
+    }
+}
+impl<K, V, const N: usize> IntoIterator for SmallVecMap<K, V, N> {
+    type Item = (K, V);
+    type IntoIter = smallvec::IntoIter<[(K, V); N]>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+impl<K: Eq, V, const N: usize> FromIterator<(K, V)> for SmallVecMap<K, V, N> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::default();
+        for (key, val) in iter {
+            map.insert(key, val);
+        }
+        map
+    }
+}
+
+impl<K: serde::Serialize, V: serde::Serialize, const N: usize> serde::Serialize for SmallVecMap<K, V, N> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+impl<'de, K: Eq + serde::Deserialize<'de>, V: serde::Deserialize<'de>, const N: usize> serde::Deserialize<'de> for SmallVecMap<K, V, N> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<(K, V)>::deserialize(deserializer).map(Self::from_iter)
+    }
+}
@@ -1,25 +1,84 @@
+use std::cell::Cell;
 use std::future::Future;
 use std::task::{Context, Poll};
 use std::pin::Pin;
 
 use crate::op::Op;
 
-pub struct CompRunFuture<'s, C: Comp + ?Sized> {
+use super::{SupervisedComp, Supervision};
+
+pub use tokio_util::sync::CancellationToken;
+
+thread_local! {
+    static SHUTTING_DOWN: Cell<bool> = Cell::new(false);
+}
+
+/// If the comp currently being polled is shutting down, see `CompExt::run_until`.
+///
+/// Comps should then treat their input running dry (`Poll::Pending`) as the
+/// end of the stream: finish and flush any in-flight output, then complete.
+pub fn is_shutting_down() -> bool {
+    SHUTTING_DOWN.with(|flag| flag.get())
+}
+
+/// Resolves on ctrl-c, or on SIGTERM on unix. For use with `CompExt::run_until`.
+pub async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = sigterm.recv() => {}
+                }
+                return;
+            }
+            Err(err) => tracing::warn!(%err, "failed to listen for SIGTERM"),
+        }
+    }
+    if let Err(err) = tokio::signal::ctrl_c().await {
+        tracing::warn!(%err, "failed to listen for ctrl-c");
+        std::future::pending::<()>().await;
+    }
+}
+
+/// The outcome of a successful `Comp::tick`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompStatus {
+    /// The comp may have more work, tick it again.
+    Continue,
+    /// The comp's input has ended and its output has been flushed.
+    Complete,
+}
+
+pub struct CompRunFuture<'s, C: Comp + ?Sized, S: Future<Output = ()>> {
     comp: &'s C,
     future: Pin<Box<C::TickFuture<'s>>>,
+    shutdown: Pin<Box<S>>,
+    shutting_down: bool,
 }
 
-impl<'s, C: Comp + ?Sized> Future for CompRunFuture<'s, C> {
-    type Output = Result<!, C::Error>;
+impl<'s, C: Comp + ?Sized, S: Future<Output = ()>> Future for CompRunFuture<'s, C, S> {
+    type Output = Result<(), C::Error>;
 
     fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
-        match self.future.as_mut().poll(ctx) {
-            Poll::Pending => Poll::Pending,
-            Poll::Ready(Err(err)) => Poll::Ready(Err(err)),
-            Poll::Ready(Ok(_)) => {
-                let this = self.get_mut();
-                this.future = Box::pin(this.comp.tick());
-                Pin::new(this).poll(ctx)
+        let this = &mut *self;
+        if !this.shutting_down {
+            this.shutting_down = this.shutdown.as_mut().poll(ctx).is_ready();
+        }
+        loop {
+            let prev = SHUTTING_DOWN.with(|flag| flag.replace(this.shutting_down));
+            let result = this.future.as_mut().poll(ctx);
+            SHUTTING_DOWN.with(|flag| flag.set(prev));
+
+            match result {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                Poll::Ready(Ok(CompStatus::Complete)) => return Poll::Ready(Ok(())),
+                Poll::Ready(Ok(CompStatus::Continue)) => {
+                    this.future = Box::pin(this.comp.tick());
+                }
             }
         }
     }
@@ -28,17 +87,43 @@ impl<'s, C: Comp + ?Sized> Future for CompRunFuture<'s, C> {
 pub trait Comp {
     type Error: std::fmt::Debug;
 
-    type TickFuture<'s>: Future<Output = Result<(), Self::Error>>;
+    type TickFuture<'s>: Future<Output = Result<CompStatus, Self::Error>>;
     fn tick(&self) -> Self::TickFuture<'_>;
 }
 
 pub trait CompExt: Comp {
-    fn run(&self) -> CompRunFuture<'_, Self> {
+    /// Run until the comp completes or errors.
+    fn run(&self) -> CompRunFuture<'_, Self, std::future::Pending<()>> {
+        self.run_until(std::future::pending())
+    }
+
+    /// Run until the comp completes or errors, or SHUTDOWN resolves. After
+    /// SHUTDOWN the comp drains the deltas already available, flushes its
+    /// output, and completes. E.g. `comp.run_until(token.cancelled())`.
+    fn run_until<S: Future<Output = ()>>(&self, shutdown: S) -> CompRunFuture<'_, Self, S> {
         CompRunFuture {
             comp: self,
             future: Box::pin(self.tick()),
+            shutdown: Box::pin(shutdown),
+            shutting_down: false,
         }
     }
+
+    /// Run this comp and OTHER together as one comp, see `Comp for (A, B)`.
+    fn join<C: Comp>(self, other: C) -> (Self, C)
+    where
+        Self: Sized,
+    {
+        (self, other)
+    }
+
+    /// Handle this comp's errors according to POLICY.
+    fn supervise(self, policy: Supervision) -> SupervisedComp<Self>
+    where
+        Self: Sized,
+    {
+        SupervisedComp::new(self, policy)
+    }
 }
 impl<C: Comp> CompExt for C {}
 
@@ -49,4 +134,3 @@ pub trait CompConnector<O: Op> {
     #[must_use]
     fn connect(&self, op: O) -> Self::Comp;
 }
-
@@ -1,16 +1,18 @@
 use std::fmt::Debug;
 use std::future::Future;
 
+use crate::error::{ErrorHandle, SpinachError};
 use crate::lattice::LatticeRepr;
 use crate::op::OpDelta;
 
-use super::{Comp, Next};
+use super::{Comp, CompStatus, Next};
 
 pub struct DebugComp<O: OpDelta>
 where
     <O::LatRepr as LatticeRepr>::Repr: Debug,
 {
     op: O,
+    errors: ErrorHandle,
     tag: &'static str,
 }
 
@@ -19,7 +21,7 @@ where
     <O::LatRepr as LatticeRepr>::Repr: Debug,
 {
     pub fn new(op: O, tag: &'static str) -> Self {
-        Self { op, tag }
+        Self { op, errors: Default::default(), tag }
     }
 }
 
@@ -27,17 +29,17 @@ impl<O: OpDelta> Comp for DebugComp<O>
 where
     <O::LatRepr as LatticeRepr>::Repr: Debug,
 {
-    type Error = ();
+    type Error = SpinachError;
 
-    type TickFuture<'s> = impl Future<Output = Result<(), Self::Error>>;
+    type TickFuture<'s> = impl Future<Output = Result<CompStatus, Self::Error>>;
     fn tick(&self) -> Self::TickFuture<'_> {
         async move {
-            if let Some(hide) = (Next { op: &self.op }).await {
+            if let Some(hide) = (Next { op: &self.op, errors: &self.errors }).await? {
                 println!("{}: {:?}", self.tag, hide.into_reveal());
-                Ok(())
+                Ok(CompStatus::Continue)
             }
             else {
-                Err(())
+                Ok(CompStatus::Complete)
             }
         }
     }
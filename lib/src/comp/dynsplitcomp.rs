@@ -4,12 +4,17 @@ use std::iter::IntoIterator;
 
 use futures::future;
 
+use crate::error::{ErrorHandle, SpinachError};
 use crate::op::{OpDelta, OpValue, Splitter, SplitOp};
 use crate::lattice::LatticeRepr;
 use crate::lattice::set_union::SetUnion;
 
-use super::{Comp, CompConnector, Next};
+use super::{Comp, CompConnector, CompExt, CompStatus, Next, SupervisedComp, Supervision};
 
+/// Connects each comp connector received from PIPE_OP to a new split of the
+/// splitter and runs them all concurrently. Each split is supervised with
+/// `Supervision::Ignore`: one which fails is logged and dropped, like one
+/// which completes, without ending the others.
 pub struct DynSplitComp<O: OpValue + OpDelta, P, C>
 where
     P: OpDelta,
@@ -19,8 +24,9 @@ where
 {
     splitter: Splitter<O>,
     pipe_op: P,
+    errors: ErrorHandle,
 
-    splits: RefCell<Vec<C::Comp>>,
+    splits: RefCell<Vec<SupervisedComp<C::Comp>>>,
 }
 
 impl<O: OpValue + OpDelta, P, C> DynSplitComp<O, P, C>
@@ -34,6 +40,7 @@ where
         Self {
             splitter,
             pipe_op,
+            errors: Default::default(),
 
             splits: Default::default(),
         }
@@ -47,36 +54,26 @@ where
     <P::LatRepr as LatticeRepr>::Repr: IntoIterator<Item = C>,
     C: CompConnector<SplitOp<O>>,
 {
-    type Error = <C::Comp as Comp>::Error;
+    type Error = SpinachError;
 
-    type TickFuture<'s> = impl Future<Output = Result<(), Self::Error>>;
+    type TickFuture<'s> = impl Future<Output = Result<CompStatus, Self::Error>>;
     fn tick(&self) -> Self::TickFuture<'_> {
         async move {
             // Join up any new splits.
-            while let Some(hide_connectors) = (Next { op: &self.pipe_op }).await {
+            while let Some(hide_connectors) = (Next { op: &self.pipe_op, errors: &self.errors }).await? {
                 for connector in hide_connectors.into_reveal() {
-                    let new_split = connector.connect(self.splitter.add_split());
+                    let new_split = connector.connect(self.splitter.add_split())
+                        .supervise(Supervision::Ignore);
                     self.splits.borrow_mut().push(new_split);
                 }
             }
 
-            // Run all the ticks, remove any erroring comps.
+            // Run all the ticks, remove any completed (or failed and logged) comps.
             let tick_results = future::join_all(self.splits.borrow().iter().map(|comp| comp.tick())).await;
-            {
-                let mut splits = self.splits.borrow_mut();
-                let mut index = 0;
-                for tick_result in tick_results {
-                    match tick_result {
-                        Err(_) => {
-                            splits.remove(index);
-                        }
-                        Ok(_) => {
-                            index += 1;
-                        }
-                    }
-                }
-            }
-            Ok(())
+            let mut tick_results = tick_results.into_iter();
+            self.splits.borrow_mut()
+                .retain(|_| matches!(tick_results.next(), Some(Ok(CompStatus::Continue))));
+            Ok(CompStatus::Continue)
         }
     }
 }
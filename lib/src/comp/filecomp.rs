@@ -0,0 +1,53 @@
+use std::cell::RefCell;
+use std::future::Future;
+
+use bytes::BytesMut;
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+
+use crate::error::{ErrorHandle, SpinachError};
+use crate::format::FileEncode;
+use crate::op::OpDelta;
+
+use super::{Comp, CompStatus, Next};
+
+/// Writes each delta to a file in format F.
+pub struct FileComp<O: OpDelta, F: FileEncode<O::LatRepr>> {
+    op: O,
+    errors: ErrorHandle,
+    file: RefCell<File>,
+    _phantom: std::marker::PhantomData<F>,
+}
+
+impl<O: OpDelta, F: FileEncode<O::LatRepr>> FileComp<O, F> {
+    pub fn new(op: O, file: File) -> Self {
+        Self {
+            op,
+            errors: Default::default(),
+            file: RefCell::new(file),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<O: OpDelta, F: FileEncode<O::LatRepr>> Comp for FileComp<O, F> {
+    type Error = SpinachError;
+
+    type TickFuture<'s> = impl Future<Output = Result<CompStatus, Self::Error>>;
+    fn tick(&self) -> Self::TickFuture<'_> {
+        async move {
+            let mut file = self.file.borrow_mut();
+            if let Some(hide) = (Next { op: &self.op, errors: &self.errors }).await? {
+                let mut buf = BytesMut::new();
+                F::encode(hide.reveal_ref(), &mut buf)?;
+                file.write_all(&*buf).await?;
+                file.flush().await?;
+                Ok(CompStatus::Continue)
+            }
+            else {
+                file.flush().await?;
+                Ok(CompStatus::Complete)
+            }
+        }
+    }
+}
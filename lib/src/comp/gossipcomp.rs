@@ -0,0 +1,107 @@
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::task::Poll;
+use std::time::Duration;
+
+use futures::future;
+use rand::seq::index;
+use serde::ser::Serialize;
+use tokio::time::{Interval, MissedTickBehavior};
+
+use crate::error::{ErrorHandle, SpinachError};
+use crate::lattice::{LatticeRepr, Split};
+use crate::op::{OpDelta, OpValue};
+use crate::tcp_client::TcpClient;
+use crate::tcp_server::serde::serialize_frames;
+
+use super::{is_shutting_down, poll_next, Comp, CompStatus};
+
+/// Anti-entropy replication. Drains the op's deltas and, every period, sends
+/// the op's full value to a random subset of FANOUT peers. Peers receive the
+/// value with a `GossipOp`. FANOUT is capped at the number of peers. Values
+/// too large for one frame are split and sent over several.
+///
+/// Because values only grow and merges are idempotent, lost, duplicated, or
+/// stale gossip is harmless and replicas converge eventually.
+pub struct GossipComp<O: OpDelta + OpValue>
+where
+    O::LatRepr: Any + Split,
+    <O::LatRepr as LatticeRepr>::Repr: Serialize,
+{
+    op: O,
+    errors: ErrorHandle,
+    peers: Vec<TcpClient>,
+    period: Duration,
+    fanout: usize,
+    interval: RefCell<Interval>,
+    closed: Cell<bool>,
+}
+
+impl<O: OpDelta + OpValue> GossipComp<O>
+where
+    O::LatRepr: Any + Split,
+    <O::LatRepr as LatticeRepr>::Repr: Serialize,
+{
+    pub fn new(op: O, peers: Vec<TcpClient>, period: Duration, fanout: usize) -> Self {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Self {
+            op,
+            errors: Default::default(),
+            fanout: std::cmp::min(fanout, peers.len()),
+            peers,
+            period,
+            interval: RefCell::new(interval),
+            closed: Cell::new(false),
+        }
+    }
+}
+
+impl<O: OpDelta + OpValue> Comp for GossipComp<O>
+where
+    O::LatRepr: Any + Split,
+    <O::LatRepr as LatticeRepr>::Repr: Serialize,
+{
+    type Error = SpinachError;
+
+    type TickFuture<'s> = impl Future<Output = Result<CompStatus, Self::Error>>;
+    fn tick(&self) -> Self::TickFuture<'_> {
+        async move {
+            // Accumulate deltas until the next round.
+            future::poll_fn(|ctx| -> Poll<Result<(), SpinachError>> {
+                while !self.closed.get() {
+                    match poll_next(&self.op, &self.errors, ctx)? {
+                        Poll::Ready(Some(_delta)) => {},
+                        Poll::Ready(None) => self.closed.set(true),
+                        Poll::Pending => break,
+                    }
+                }
+                // Send a final round before shutting down.
+                if is_shutting_down() {
+                    return Poll::Ready(Ok(()));
+                }
+                self.interval.borrow_mut().poll_tick(ctx).map(|_| Ok(()))
+            }).await?;
+
+            let frames = serialize_frames::<O::LatRepr>(self.op.get_value().into_reveal())?;
+
+            let targets = index::sample(&mut rand::thread_rng(), self.peers.len(), self.fanout);
+            // Unreachable peers are skipped this round, they will be retried in later rounds.
+            future::join_all(targets.into_iter().map(|i| {
+                let (peer, frames) = (&self.peers[i], &frames);
+                tokio::time::timeout(self.period, async move {
+                    for frame in frames.iter() {
+                        peer.write(frame.clone()).await?;
+                    }
+                    Ok::<_, std::io::Error>(())
+                })
+            })).await;
+
+            if is_shutting_down() {
+                return Ok(CompStatus::Complete);
+            }
+            Ok(CompStatus::Continue)
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use std::fmt::Debug;
+use std::future::Future;
+
+use futures::future::{self, TryFutureExt};
+
+use super::{Comp, CompStatus};
+
+/// The first error from a joined comp, with the index of the comp which failed.
+#[derive(Debug)]
+pub struct JoinError<E> {
+    pub index: usize,
+    pub error: E,
+}
+
+/// Tick COMP until it completes or errors.
+async fn run_member<C: Comp>(comp: &C, index: usize) -> Result<(), JoinError<C::Error>> {
+    loop {
+        match comp.tick().await {
+            Ok(CompStatus::Continue) => {}
+            Ok(CompStatus::Complete) => return Ok(()),
+            Err(error) => return Err(JoinError { index, error }),
+        }
+    }
+}
+
+fn boxed<E: Debug + 'static>(err: JoinError<E>) -> JoinError<Box<dyn Debug>> {
+    JoinError {
+        index: err.index,
+        error: Box::new(err.error),
+    }
+}
+
+/// Runs all the comps concurrently. Completes once every comp has completed,
+/// fails as soon as any comp errors. Failing drops the other comps' in-flight
+/// ticks, so a delta another comp had taken from its op but not yet finished
+/// handling (e.g. sending) is lost. Use `CompExt::supervise` to handle errors
+/// of individual comps differently.
+impl<C: Comp> Comp for Vec<C> {
+    type Error = JoinError<C::Error>;
+
+    type TickFuture<'s> = impl Future<Output = Result<CompStatus, Self::Error>>;
+    fn tick(&self) -> Self::TickFuture<'_> {
+        async move {
+            future::try_join_all(self.iter().enumerate()
+                .map(|(index, comp)| run_member(comp, index)))
+                .await?;
+            Ok(CompStatus::Complete)
+        }
+    }
+}
+
+macro_rules! tuple_comp {
+    ( $( $name:ident $index:tt ),* ) => {
+        /// Runs all the comps concurrently, like `Vec<C>`.
+        impl<$( $name: Comp ),*> Comp for ( $( $name, )* )
+        where
+            $( $name::Error: 'static ),*
+        {
+            type Error = JoinError<Box<dyn Debug>>;
+
+            type TickFuture<'s> = impl Future<Output = Result<CompStatus, Self::Error>>;
+            fn tick(&self) -> Self::TickFuture<'_> {
+                async move {
+                    futures::try_join!( $( run_member(&self.$index, $index).map_err(boxed) ),* )?;
+                    Ok(CompStatus::Complete)
+                }
+            }
+        }
+    };
+}
+
+tuple_comp!(A 0, B 1);
+tuple_comp!(A 0, B 1, C 2);
+tuple_comp!(A 0, B 1, C 2, D 3);
@@ -0,0 +1,235 @@
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::hash::Hash;
+use std::task::Poll;
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures::future;
+use serde::ser::Serialize;
+use tokio::time::{Interval, MissedTickBehavior};
+
+use crate::collections::Collection;
+use crate::error::{ErrorHandle, SpinachError};
+use crate::lattice::LatticeRepr;
+use crate::lattice::map_union::{MapTag, MapUnionRepr};
+use crate::merkle::{MerkleMessage, MerkleRoute, MerkleSync, MerkleTree};
+use crate::op::{OpDelta, OpValue};
+
+use super::{is_shutting_down, poll_next, Comp, CompStatus};
+
+/// Merkle-tree anti-entropy over `MapUnionRepr` state. Every period sends the
+/// root digest of the op's value to each peer, then walks down the branches
+/// which differ, so only the differing leaf buckets' entries are exchanged.
+/// Peers' entries are received by the `MerkleSyncOp` sharing the same
+/// `MerkleSync`.
+///
+/// The tree is built lazily, when first needed, then each delta updates only
+/// the buckets of its keys. Keys and values must hash the same on every
+/// replica.
+pub struct MerkleSyncComp<O: OpDelta + OpValue, Tag, K, Lr: LatticeRepr>
+where
+    Tag: MapTag<K, Lr::Repr>,
+    MapUnionRepr<Tag, K, Lr>: LatticeRepr,
+    O: OpDelta<LatRepr = MapUnionRepr<Tag, K, Lr>>,
+    <O::LatRepr as LatticeRepr>::Repr: Collection<K, Lr::Repr>,
+    K: Clone + Eq + Hash + Serialize,
+    Lr::Repr: Hash + Serialize,
+{
+    op: O,
+    errors: ErrorHandle,
+    sync: MerkleSync<K, Lr>,
+    period: Duration,
+    interval: RefCell<Interval>,
+    tree: RefCell<Option<MerkleTree>>,
+    /// The `MerkleTree::entry_hash` of each key's value in the tree.
+    entry_hashes: RefCell<HashMap<K, u64>>,
+    closed: Cell<bool>,
+    _phantom: std::marker::PhantomData<Tag>,
+}
+
+impl<O: OpDelta + OpValue, Tag, K, Lr: LatticeRepr> MerkleSyncComp<O, Tag, K, Lr>
+where
+    Tag: MapTag<K, Lr::Repr>,
+    MapUnionRepr<Tag, K, Lr>: LatticeRepr,
+    O: OpDelta<LatRepr = MapUnionRepr<Tag, K, Lr>>,
+    <O::LatRepr as LatticeRepr>::Repr: Collection<K, Lr::Repr>,
+    K: Clone + Eq + Hash + Serialize,
+    Lr::Repr: Hash + Serialize,
+{
+    pub fn new(op: O, sync: MerkleSync<K, Lr>, period: Duration) -> Self {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Self {
+            op,
+            errors: Default::default(),
+            sync,
+            period,
+            interval: RefCell::new(interval),
+            tree: RefCell::new(None),
+            entry_hashes: Default::default(),
+            closed: Cell::new(false),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    fn entries(&self, buckets: &[u64]) -> Vec<(K, Lr::Repr)> {
+        let depth = self.sync.depth();
+        let buckets: HashSet<u64> = buckets.iter().copied().collect();
+        self.op.with_value(|value| {
+            value.reveal_ref().entries()
+                .filter(|(key, _)| buckets.contains(&MerkleTree::bucket(depth, *key)))
+                .map(|(key, val)| (key.clone(), val.clone()))
+                .collect()
+        })
+    }
+
+    fn build_tree(&self) -> MerkleTree {
+        self.op.with_value(|value| {
+            let entries = value.reveal_ref().entries();
+            let mut entry_hashes = self.entry_hashes.borrow_mut();
+            entry_hashes.clear();
+            entry_hashes.extend(entries.map(|(key, val)| (key.clone(), MerkleTree::entry_hash(key, val))));
+            MerkleTree::new(self.sync.depth(), value.reveal_ref().entries())
+        })
+    }
+
+    /// Update the tree, if built, for the keys in DELTA.
+    fn update_tree(&self, delta: &<O::LatRepr as LatticeRepr>::Repr) {
+        let mut tree = self.tree.borrow_mut();
+        let tree = match &mut *tree {
+            Some(tree) => tree,
+            None => return,
+        };
+        let mut entry_hashes = self.entry_hashes.borrow_mut();
+        self.op.with_value(|value| {
+            for key in delta.keys() {
+                // The value may already include later deltas, those updates will be no-ops.
+                if let Some(val) = value.reveal_ref().get(key) {
+                    let new = MerkleTree::entry_hash(key, val);
+                    let old = entry_hashes.insert(key.clone(), new).unwrap_or(0);
+                    tree.update(MerkleTree::bucket(tree.depth(), key), old, new);
+                }
+            }
+        });
+    }
+
+    /// Responses to MSG, given our current TREE.
+    fn respond(&self, tree: &MerkleTree, msg: MerkleMessage<K, Lr::Repr>) -> Vec<MerkleMessage<K, Lr::Repr>> {
+        match msg {
+            MerkleMessage::Digest { depth, level, nodes } => {
+                if depth != tree.depth() || level > depth {
+                    tracing::warn!(depth, level, expected_depth = tree.depth(), "mismatched Merkle digest");
+                    return vec![];
+                }
+                let diff = tree.diff(level, &nodes);
+                if diff.is_empty() {
+                    vec![]
+                }
+                else if level < depth {
+                    vec![MerkleMessage::Digest { depth, level: level + 1, nodes: tree.children(level, &diff) }]
+                }
+                else {
+                    // Differing leaves: send ours and ask for theirs.
+                    let entries = self.entries(&diff);
+                    let pull = MerkleMessage::Pull { buckets: diff };
+                    if entries.is_empty() {
+                        vec![pull]
+                    }
+                    else {
+                        vec![MerkleMessage::Push { entries }, pull]
+                    }
+                }
+            }
+            MerkleMessage::Pull { buckets } => {
+                let entries = self.entries(&buckets);
+                if entries.is_empty() {
+                    vec![]
+                }
+                else {
+                    vec![MerkleMessage::Push { entries }]
+                }
+            }
+            MerkleMessage::Push { .. } => vec![], // Handled by `MerkleSyncOp`.
+        }
+    }
+
+    async fn send(&self, route: MerkleRoute, msg: MerkleMessage<K, Lr::Repr>) -> Result<(), SpinachError> {
+        for bytes in msg.encode()? {
+            // Failed sends are dropped, the next round will retry.
+            let _ = self.write(route, bytes).await;
+        }
+        Ok(())
+    }
+
+    async fn write(&self, route: MerkleRoute, bytes: Bytes) -> tokio::io::Result<()> {
+        match route {
+            MerkleRoute::Server(addr) => self.sync.tcp_server().write(addr, bytes).await,
+            MerkleRoute::Peer(i) => {
+                tokio::time::timeout(self.period, self.sync.peers()[i].write(bytes)).await?
+            }
+        }
+    }
+}
+
+impl<O: OpDelta + OpValue, Tag, K, Lr: LatticeRepr> Comp for MerkleSyncComp<O, Tag, K, Lr>
+where
+    Tag: MapTag<K, Lr::Repr>,
+    MapUnionRepr<Tag, K, Lr>: LatticeRepr,
+    O: OpDelta<LatRepr = MapUnionRepr<Tag, K, Lr>>,
+    <O::LatRepr as LatticeRepr>::Repr: Collection<K, Lr::Repr>,
+    K: Clone + Eq + Hash + Serialize,
+    Lr::Repr: Hash + Serialize,
+{
+    type Error = SpinachError;
+
+    type TickFuture<'s> = impl Future<Output = Result<CompStatus, Self::Error>>;
+    fn tick(&self) -> Self::TickFuture<'_> {
+        async move {
+            // Accumulate deltas until a message needs answering or the next round starts.
+            let round = future::poll_fn(|ctx| -> Poll<Result<bool, SpinachError>> {
+                while !self.closed.get() {
+                    match poll_next(&self.op, &self.errors, ctx)? {
+                        Poll::Ready(Some(delta)) => self.update_tree(delta.reveal_ref()),
+                        Poll::Ready(None) => self.closed.set(true),
+                        Poll::Pending => break,
+                    }
+                }
+                if self.sync.has_inbox() || is_shutting_down() {
+                    return Poll::Ready(Ok(false));
+                }
+                self.interval.borrow_mut().poll_tick(ctx).map(|_| Ok(true))
+            }).await?;
+
+            let mut outbox = Vec::new();
+            {
+                let mut tree = self.tree.borrow_mut();
+                let tree = tree.get_or_insert_with(|| self.build_tree());
+                if round {
+                    for i in 0..self.sync.peers().len() {
+                        outbox.push((MerkleRoute::Peer(i), MerkleMessage::Digest {
+                            depth: tree.depth(),
+                            level: 0,
+                            nodes: vec![(0, tree.root())],
+                        }));
+                    }
+                }
+                while let Some((route, msg)) = self.sync.pop_inbox() {
+                    for response in self.respond(tree, msg) {
+                        outbox.push((route, response));
+                    }
+                }
+            }
+
+            for (route, msg) in outbox {
+                self.send(route, msg).await?;
+            }
+            // Answer any pending messages before shutting down.
+            if is_shutting_down() && !self.sync.has_inbox() {
+                return Ok(CompStatus::Complete);
+            }
+            Ok(CompStatus::Continue)
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use std::cell::RefCell;
+use std::future::Future;
+use std::task::Poll;
+use std::time::Duration;
+
+use futures::future;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::time::{Interval, MissedTickBehavior};
+
+use crate::error::SpinachError;
+use crate::metrics::Metrics;
+
+use super::{is_shutting_down, Comp, CompStatus};
+
+/// Writes the rendered metrics to a writer every period, and once more on
+/// shutdown. Never completes otherwise, so join it with the dataflow's comps.
+pub struct MetricsComp<W: AsyncWrite + Unpin> {
+    metrics: Metrics,
+    writer: RefCell<W>,
+    interval: RefCell<Interval>,
+}
+
+impl<W: AsyncWrite + Unpin> MetricsComp<W> {
+    pub fn new(metrics: Metrics, writer: W, period: Duration) -> Self {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Self {
+            metrics,
+            writer: RefCell::new(writer),
+            interval: RefCell::new(interval),
+        }
+    }
+}
+
+impl<W: AsyncWrite + Unpin> Comp for MetricsComp<W> {
+    type Error = SpinachError;
+
+    type TickFuture<'s> = impl Future<Output = Result<CompStatus, Self::Error>>;
+    fn tick(&self) -> Self::TickFuture<'_> {
+        async move {
+            future::poll_fn(|ctx| {
+                if is_shutting_down() {
+                    return Poll::Ready(());
+                }
+                self.interval.borrow_mut().poll_tick(ctx).map(|_| ())
+            }).await;
+
+            let mut writer = self.writer.borrow_mut();
+            writer.write_all(self.metrics.render().as_bytes()).await?;
+            writer.flush().await?;
+
+            if is_shutting_down() {
+                return Ok(CompStatus::Complete);
+            }
+            Ok(CompStatus::Continue)
+        }
+    }
+}
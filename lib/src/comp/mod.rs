@@ -2,18 +2,39 @@ use std::future::Future;
 use std::task::{Context, Poll};
 use std::pin::Pin;
 
+use crate::error::{ErrorHandle, SpinachError};
 use crate::op::OpDelta;
 use crate::hide::{Hide, Delta};
 
+/// Poll OP for its next delta, or for any error reported by its sources into ERRORS.
+fn poll_next<O: OpDelta>(op: &O, errors: &ErrorHandle, ctx: &mut Context<'_>) -> Poll<Result<Option<Hide<Delta, O::LatRepr>>, SpinachError>> {
+    if let Some(err) = errors.take() {
+        return Poll::Ready(Err(err));
+    }
+    match errors.scope(|| op.poll_delta(ctx)) {
+        Poll::Ready(Some(hide)) => Poll::Ready(Ok(Some(hide))),
+        polled => match errors.take() {
+            Some(err) => Poll::Ready(Err(err)),
+            None => match polled {
+                // Input has run dry, end the stream.
+                Poll::Pending if is_shutting_down() => Poll::Ready(Ok(None)),
+                Poll::Pending => Poll::Pending,
+                Poll::Ready(_) => Poll::Ready(Ok(None)),
+            }
+        }
+    }
+}
+
 struct Next<'s, O: OpDelta> {
     op: &'s O,
+    errors: &'s ErrorHandle,
 }
 
 impl<O: OpDelta> Future for Next<'_, O> {
-    type Output = Option<Hide<Delta, O::LatRepr>>;
+    type Output = Result<Option<Hide<Delta, O::LatRepr>>, SpinachError>;
 
     fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
-        self.op.poll_delta(ctx)
+        poll_next(self.op, self.errors, ctx)
     }
 }
 
@@ -26,11 +47,60 @@ pub use nullcomp::*;
 mod debugcomp;
 pub use debugcomp::*;
 
+mod metricscomp;
+pub use metricscomp::*;
+
+mod filecomp;
+pub use filecomp::*;
+
 mod tcpcomp;
 pub use tcpcomp::*;
 
 mod tcpservercomp;
 pub use tcpservercomp::*;
 
+mod tcpbroadcastcomp;
+pub use tcpbroadcastcomp::*;
+
+mod tcpmulticastcomp;
+pub use tcpmulticastcomp::*;
+
+mod tcpclientcomp;
+pub use tcpclientcomp::*;
+
+mod tcpclientresendcomp;
+pub use tcpclientresendcomp::*;
+
+mod simcomp;
+pub use simcomp::*;
+
+mod simservercomp;
+pub use simservercomp::*;
+
+#[cfg(unix)]
+mod unixcomp;
+#[cfg(unix)]
+pub use unixcomp::*;
+
+#[cfg(unix)]
+mod unixservercomp;
+#[cfg(unix)]
+pub use unixservercomp::*;
+
+mod udpcomp;
+pub use udpcomp::*;
+
+mod gossipcomp;
+pub use gossipcomp::*;
+
+mod merklesynccomp;
+pub use merklesynccomp::*;
+
+mod joincomp;
+pub use joincomp::*;
+
+mod supervisedcomp;
+pub use supervisedcomp::*;
+
 mod dynsplitcomp;
 pub use dynsplitcomp::*;
@@ -1,30 +1,32 @@
 use std::future::Future;
 
+use crate::error::{ErrorHandle, SpinachError};
 use crate::op::OpDelta;
 
-use super::{Comp, Next};
+use super::{Comp, CompStatus, Next};
 
 pub struct NullComp<O: OpDelta> {
     op: O,
+    errors: ErrorHandle,
 }
 
 impl<O: OpDelta> NullComp<O> {
     pub fn new(op: O) -> Self {
-        Self { op }
+        Self { op, errors: Default::default() }
     }
 }
 
 impl<O: OpDelta> Comp for NullComp<O> {
-    type Error = ();
+    type Error = SpinachError;
 
-    type TickFuture<'s> = impl Future<Output = Result<(), Self::Error>>;
+    type TickFuture<'s> = impl Future<Output = Result<CompStatus, Self::Error>>;
     fn tick(&self) -> Self::TickFuture<'_> {
         async move {
-            if let Some(_hide) = (Next { op: &self.op }).await {
-                Ok(())
+            if let Some(_hide) = (Next { op: &self.op, errors: &self.errors }).await? {
+                Ok(CompStatus::Continue)
             }
             else {
-                Err(())
+                Ok(CompStatus::Complete)
             }
         }
     }
@@ -0,0 +1,64 @@
+use std::any::Any;
+use std::future::Future;
+use std::net::SocketAddr;
+
+use serde::ser::Serialize;
+
+use crate::error::{ErrorHandle, SpinachError};
+use crate::lattice::LatticeRepr;
+use crate::op::OpDelta;
+use crate::sim_net::SimServer;
+use crate::tcp_server::serde::serialize;
+
+use super::{Comp, CompStatus, Next};
+
+/// Sends each delta from a `SimServer` to every peer, like a `TcpComp`.
+pub struct SimComp<O: OpDelta>
+where
+    O::LatRepr: Any,
+    <O::LatRepr as LatticeRepr>::Repr: Serialize,
+{
+    op: O,
+    errors: ErrorHandle,
+    sim_server: SimServer,
+    peers: Vec<SocketAddr>,
+}
+
+impl<O: OpDelta> SimComp<O>
+where
+    O::LatRepr: Any,
+    <O::LatRepr as LatticeRepr>::Repr: Serialize,
+{
+    pub fn new(op: O, sim_server: SimServer, peers: Vec<SocketAddr>) -> Self {
+        Self {
+            op,
+            errors: Default::default(),
+            sim_server,
+            peers,
+        }
+    }
+}
+
+impl<O: OpDelta> Comp for SimComp<O>
+where
+    O::LatRepr: Any,
+    <O::LatRepr as LatticeRepr>::Repr: Serialize,
+{
+    type Error = SpinachError;
+
+    type TickFuture<'s> = impl Future<Output = Result<CompStatus, Self::Error>>;
+    fn tick(&self) -> Self::TickFuture<'_> {
+        async move {
+            if let Some(hide) = (Next { op: &self.op, errors: &self.errors }).await? {
+                let bytes = serialize::<O::LatRepr>(hide.reveal_ref())?.freeze();
+                for &peer in self.peers.iter() {
+                    self.sim_server.write(peer, bytes.clone()).await?;
+                }
+                Ok(CompStatus::Continue)
+            }
+            else {
+                Ok(CompStatus::Complete)
+            }
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use std::any::Any;
+use std::future::Future;
+use std::net::SocketAddr;
+
+use serde::ser::Serialize;
+
+use crate::error::{ErrorHandle, SpinachError};
+use crate::lattice::{LatticeRepr};
+use crate::lattice::map_union::{MapTag, MapUnionRepr};
+use crate::op::OpDelta;
+use crate::sim_net::SimServer;
+use crate::tcp_server::serde::serialize;
+
+use super::{Comp, CompStatus, Next};
+
+/// Sends each delta to the node at its key, like a `TcpServerComp`.
+pub struct SimServerComp<O: OpDelta, Tag, Lr: Any + LatticeRepr>
+where
+    Tag: MapTag<SocketAddr, Lr::Repr>,
+    MapUnionRepr<Tag, SocketAddr, Lr>: LatticeRepr,
+    O: OpDelta<LatRepr = MapUnionRepr<Tag, SocketAddr, Lr>>,
+    <O::LatRepr as LatticeRepr>::Repr: IntoIterator<Item = (SocketAddr, Lr::Repr)>,
+    Lr::Repr: Serialize,
+{
+    op: O,
+    errors: ErrorHandle,
+    sim_server: SimServer,
+    _phantom: std::marker::PhantomData<(Tag, Lr)>,
+}
+
+impl<O: OpDelta, Tag, Lr: Any + LatticeRepr> SimServerComp<O, Tag, Lr>
+where
+    Tag: MapTag<SocketAddr, Lr::Repr>,
+    MapUnionRepr<Tag, SocketAddr, Lr>: LatticeRepr,
+    O: OpDelta<LatRepr = MapUnionRepr<Tag, SocketAddr, Lr>>,
+    <O::LatRepr as LatticeRepr>::Repr: IntoIterator<Item = (SocketAddr, Lr::Repr)>,
+    Lr::Repr: Serialize,
+{
+    pub fn new(op: O, sim_server: SimServer) -> Self {
+        Self {
+            op,
+            errors: Default::default(),
+            sim_server,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<O: OpDelta, Tag, Lr: Any + LatticeRepr> Comp for SimServerComp<O, Tag, Lr>
+where
+    Tag: MapTag<SocketAddr, Lr::Repr>,
+    MapUnionRepr<Tag, SocketAddr, Lr>: LatticeRepr,
+    O: OpDelta<LatRepr = MapUnionRepr<Tag, SocketAddr, Lr>>,
+    <O::LatRepr as LatticeRepr>::Repr: IntoIterator<Item = (SocketAddr, Lr::Repr)>,
+    Lr::Repr: Serialize,
+{
+    type Error = SpinachError;
+
+    type TickFuture<'s> = impl Future<Output = Result<CompStatus, Self::Error>>;
+    fn tick(&self) -> Self::TickFuture<'_> {
+        async move {
+            if let Some(hide) = (Next { op: &self.op, errors: &self.errors }).await? {
+                for (addr, repr) in hide.into_reveal().into_iter() {
+                    let bytes = serialize::<Lr>(&repr)?.freeze();
+                    self.sim_server.write(addr, bytes).await?;
+                }
+                Ok(CompStatus::Continue)
+            }
+            else {
+                Ok(CompStatus::Complete)
+            }
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use std::cell::Cell;
+use std::future::Future;
+
+use super::{Comp, CompStatus};
+
+/// What a `SupervisedComp` does when its comp errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Supervision {
+    /// Return the error, ending the comp.
+    FailFast,
+    /// Log the error and keep ticking the comp, up to the given number of
+    /// times. After that the error is returned. Use `Restart(usize::MAX)` to
+    /// never stop, e.g. for a server whose connections may fail.
+    Restart(usize),
+    /// Log the error and complete, ending just this comp.
+    Ignore,
+}
+
+pub struct SupervisedComp<C: Comp> {
+    comp: C,
+    policy: Supervision,
+    restarts: Cell<usize>,
+}
+
+impl<C: Comp> SupervisedComp<C> {
+    pub fn new(comp: C, policy: Supervision) -> Self {
+        Self {
+            comp,
+            policy,
+            restarts: Cell::new(0),
+        }
+    }
+}
+
+impl<C: Comp> Comp for SupervisedComp<C> {
+    type Error = C::Error;
+
+    type TickFuture<'s> = impl Future<Output = Result<CompStatus, Self::Error>>;
+    fn tick(&self) -> Self::TickFuture<'_> {
+        async move {
+            match self.comp.tick().await {
+                Err(err) => match self.policy {
+                    Supervision::Restart(max) if self.restarts.get() < max => {
+                        self.restarts.set(self.restarts.get() + 1);
+                        tracing::warn!(restart = self.restarts.get(), max, ?err, "comp failed, restarting");
+                        Ok(CompStatus::Continue)
+                    }
+                    Supervision::Ignore => {
+                        tracing::warn!(?err, "comp failed, ignoring");
+                        Ok(CompStatus::Complete)
+                    }
+                    _ => Err(err),
+                }
+                status => status,
+            }
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use std::any::Any;
+use std::future::Future;
+
+use serde::ser::Serialize;
+
+use crate::error::{ErrorHandle, SpinachError};
+use crate::lattice::LatticeRepr;
+use crate::op::OpDelta;
+use crate::tcp_server::TcpServer;
+use crate::tcp_server::serde::serialize;
+
+use super::{Comp, CompStatus, Next};
+
+/// Sends each delta to every peer currently connected to the `TcpServer`.
+/// Each delta is serialized only once.
+pub struct TcpBroadcastComp<O: OpDelta>
+where
+    O::LatRepr: Any,
+    <O::LatRepr as LatticeRepr>::Repr: Serialize,
+{
+    op: O,
+    errors: ErrorHandle,
+    tcp_server: TcpServer,
+}
+
+impl<O: OpDelta> TcpBroadcastComp<O>
+where
+    O::LatRepr: Any,
+    <O::LatRepr as LatticeRepr>::Repr: Serialize,
+{
+    pub fn new(op: O, tcp_server: TcpServer) -> Self {
+        Self { op, errors: Default::default(), tcp_server }
+    }
+}
+
+impl<O: OpDelta> Comp for TcpBroadcastComp<O>
+where
+    O::LatRepr: Any,
+    <O::LatRepr as LatticeRepr>::Repr: Serialize,
+{
+    type Error = SpinachError;
+
+    type TickFuture<'s> = impl Future<Output = Result<CompStatus, Self::Error>>;
+    fn tick(&self) -> Self::TickFuture<'_> {
+        async move {
+            if let Some(hide) = (Next { op: &self.op, errors: &self.errors }).await? {
+                let bytes = serialize::<O::LatRepr>(hide.reveal_ref())?.freeze();
+                self.tcp_server.broadcast(bytes).await;
+                Ok(CompStatus::Continue)
+            }
+            else {
+                Ok(CompStatus::Complete)
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use std::any::Any;
+use std::future::Future;
+use std::io::{Error, ErrorKind};
+
+use serde::ser::Serialize;
+
+use crate::error::{ErrorHandle, SpinachError};
+use crate::lattice::LatticeRepr;
+use crate::op::OpDelta;
+use crate::tcp_client::TcpClient;
+use crate::tcp_server::serde::serialize;
+
+use super::{Comp, CompStatus, Next};
+
+/// How many times a delta is written before giving up.
+const MAX_WRITE_ATTEMPTS: usize = 10;
+
+/// If ERR means the connection was lost, so the write may succeed after
+/// reconnecting.
+fn is_connection_lost(err: &Error) -> bool {
+    matches!(err.kind(),
+        ErrorKind::BrokenPipe | ErrorKind::ConnectionReset | ErrorKind::ConnectionAborted
+        | ErrorKind::NotConnected | ErrorKind::UnexpectedEof | ErrorKind::WriteZero)
+}
+
+/// Sends each delta once over a reconnecting `TcpClient`. A delta whose write
+/// fails because the connection was lost is retried once the client
+/// reconnects, up to `MAX_WRITE_ATTEMPTS` times. Other write errors end the
+/// comp. Deltas the server had received before the connection was lost are
+/// not resent. To resend the full value on every reconnect, use
+/// `TcpClientResendComp`.
+pub struct TcpClientComp<O: OpDelta>
+where
+    O::LatRepr: Any,
+    <O::LatRepr as LatticeRepr>::Repr: Serialize,
+{
+    op: O,
+    errors: ErrorHandle,
+    tcp_client: TcpClient,
+}
+
+impl<O: OpDelta> TcpClientComp<O>
+where
+    O::LatRepr: Any,
+    <O::LatRepr as LatticeRepr>::Repr: Serialize,
+{
+    pub fn new(op: O, tcp_client: TcpClient) -> Self {
+        Self { op, errors: Default::default(), tcp_client }
+    }
+}
+
+impl<O: OpDelta> Comp for TcpClientComp<O>
+where
+    O::LatRepr: Any,
+    <O::LatRepr as LatticeRepr>::Repr: Serialize,
+{
+    type Error = SpinachError;
+
+    type TickFuture<'s> = impl Future<Output = Result<CompStatus, Self::Error>>;
+    fn tick(&self) -> Self::TickFuture<'_> {
+        async move {
+            if let Some(hide) = (Next { op: &self.op, errors: &self.errors }).await? {
+                let bytes = serialize::<O::LatRepr>(hide.reveal_ref())?.freeze();
+                // On failure the client drops the connection, the write waits for the reconnect.
+                let mut attempts = 1;
+                while let Err(err) = self.tcp_client.write(bytes.clone()).await {
+                    if !is_connection_lost(&err) || MAX_WRITE_ATTEMPTS <= attempts {
+                        return Err(err.into());
+                    }
+                    attempts += 1;
+                }
+                Ok(CompStatus::Continue)
+            }
+            else {
+                Ok(CompStatus::Complete)
+            }
+        }
+    }
+}
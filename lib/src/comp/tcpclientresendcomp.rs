@@ -0,0 +1,102 @@
+use std::any::Any;
+use std::cell::Cell;
+use std::future::Future;
+use std::task::Poll;
+
+use futures::future;
+use serde::ser::Serialize;
+
+use crate::error::{ErrorHandle, SpinachError};
+use crate::lattice::{LatticeRepr, Split};
+use crate::op::{OpDelta, OpValue};
+use crate::tcp_client::TcpClient;
+use crate::tcp_server::serde::{serialize, serialize_frames};
+
+use super::{poll_next, Comp, CompStatus};
+
+/// Sends deltas over a reconnecting `TcpClient`, for replicating state.
+///
+/// Whenever the client (re)connects, the full value of the op is sent instead
+/// of the delta. Since the value subsumes every delta, including any lost
+/// with the previous connection, no writes are lost. Values too large for one
+/// frame are split and sent over several.
+pub struct TcpClientResendComp<O: OpDelta + OpValue>
+where
+    O::LatRepr: Any + Split,
+    <O::LatRepr as LatticeRepr>::Repr: Serialize,
+{
+    op: O,
+    errors: ErrorHandle,
+    tcp_client: TcpClient,
+    generation: Cell<usize>,
+}
+
+impl<O: OpDelta + OpValue> TcpClientResendComp<O>
+where
+    O::LatRepr: Any + Split,
+    <O::LatRepr as LatticeRepr>::Repr: Serialize,
+{
+    pub fn new(op: O, tcp_client: TcpClient) -> Self {
+        Self {
+            op,
+            errors: Default::default(),
+            tcp_client,
+            generation: Cell::new(0),
+        }
+    }
+}
+
+impl<O: OpDelta + OpValue> Comp for TcpClientResendComp<O>
+where
+    O::LatRepr: Any + Split,
+    <O::LatRepr as LatticeRepr>::Repr: Serialize,
+{
+    type Error = SpinachError;
+
+    type TickFuture<'s> = impl Future<Output = Result<CompStatus, Self::Error>>;
+    fn tick(&self) -> Self::TickFuture<'_> {
+        async move {
+            // Wait for either a delta or a reconnection, `Some(None)` signals a reconnection.
+            let next = future::poll_fn(|ctx| {
+                if let Poll::Ready(generation) = self.tcp_client.poll_connected(ctx) {
+                    if generation != self.generation.get() {
+                        return Poll::Ready(Ok(Some(None)));
+                    }
+                }
+                poll_next(&self.op, &self.errors, ctx).map_ok(|opt| opt.map(Some))
+            }).await?;
+
+            let mut bytes_delta = match next {
+                Some(delta_opt) => delta_opt
+                    .map(|hide| serialize::<O::LatRepr>(hide.reveal_ref()))
+                    .transpose()?
+                    .map(|bytes| bytes.freeze()),
+                None => return Ok(CompStatus::Complete),
+            };
+
+            'write: loop {
+                let generation = self.tcp_client.connected().await;
+                let frames = if generation == self.generation.get() {
+                    match bytes_delta.take() {
+                        Some(bytes) => vec![ bytes ],
+                        None => return Ok(CompStatus::Continue),
+                    }
+                }
+                else {
+                    // New connection, send the full value.
+                    bytes_delta = None;
+                    serialize_frames::<O::LatRepr>(self.op.get_value().into_reveal())?
+                };
+
+                for frame in frames {
+                    if self.tcp_client.write(frame).await.is_err() {
+                        // Connection lost, retry (resending the value) once reconnected.
+                        continue 'write;
+                    }
+                }
+                self.generation.set(generation);
+                return Ok(CompStatus::Continue);
+            }
+        }
+    }
+}
@@ -2,17 +2,19 @@ use std::any::Any;
 use std::cell::RefCell;
 use std::future::Future;
 
-use bincode::{Error, ErrorKind};
 use futures::sink::SinkExt;
 use serde::ser::Serialize;
 use tokio::net::tcp::OwnedWriteHalf;
 use tokio_util::codec::{FramedWrite, LengthDelimitedCodec};
 
+use crate::error::{ErrorHandle, SpinachError};
 use crate::lattice::LatticeRepr;
+use crate::merkle::MAX_FRAME_SIZE;
+use crate::metrics::{Counter, Metrics};
 use crate::op::OpDelta;
 use crate::tcp_server::serde::serialize;
 
-use super::{Comp, Next};
+use super::{Comp, CompStatus, Next};
 
 pub struct TcpComp<O: OpDelta>
 where
@@ -20,7 +22,9 @@ where
     <O::LatRepr as LatticeRepr>::Repr: Serialize,
 {
     op: O,
+    errors: ErrorHandle,
     framed_write: RefCell<FramedWrite<OwnedWriteHalf, LengthDelimitedCodec>>,
+    bytes_sent: Counter,
 }
 
 impl<O: OpDelta> TcpComp<O>
@@ -31,12 +35,20 @@ where
     pub fn new(op: O, tcp_write: OwnedWriteHalf) -> Self {
         let framed_write = LengthDelimitedCodec::builder()
             .length_field_length(2)
+            .max_frame_length(MAX_FRAME_SIZE)
             .new_write(tcp_write);
         Self {
             op,
+            errors: Default::default(),
             framed_write: RefCell::new(framed_write),
+            bytes_sent: Default::default(),
         }
     }
+
+    /// Count bytes written as `<name>_bytes_sent_total`.
+    pub fn register_metrics(&self, metrics: &Metrics, name: &str) {
+        metrics.register(format!("{}_bytes_sent_total", name), self.bytes_sent.clone());
+    }
 }
 
 impl<O: OpDelta> Comp for TcpComp<O>
@@ -44,20 +56,22 @@ where
     O::LatRepr: Any,
     <O::LatRepr as LatticeRepr>::Repr: Serialize,
 {
-    type Error = Error;
+    type Error = SpinachError;
 
-    type TickFuture<'s> = impl Future<Output = Result<(), Self::Error>>;
+    type TickFuture<'s> = impl Future<Output = Result<CompStatus, Self::Error>>;
     fn tick(&self) -> Self::TickFuture<'_> {
         async move {
             let mut framed_write_mut = self.framed_write.borrow_mut();
-            if let Some(hide) = (Next { op: &self.op }).await {
-                let bytes = serialize::<O::LatRepr>(hide.into_reveal())?.freeze();
+            if let Some(hide) = (Next { op: &self.op, errors: &self.errors }).await? {
+                let bytes = serialize::<O::LatRepr>(hide.reveal_ref())?.freeze();
+                let len = bytes.len();
                 framed_write_mut.send(bytes).await?;
-                Ok(())
+                self.bytes_sent.add(len as u64);
+                Ok(CompStatus::Continue)
             }
             else {
                 // framed_write_mut.shutdown().await?;
-                Err(Box::new(ErrorKind::Custom("End of stream.".to_owned())))
+                Ok(CompStatus::Complete)
             }
         }
     }
@@ -0,0 +1,76 @@
+use std::any::Any;
+use std::future::Future;
+use std::net::SocketAddr;
+
+use serde::ser::Serialize;
+
+use crate::error::{ErrorHandle, SpinachError};
+use crate::lattice::LatticeRepr;
+use crate::lattice::pair::PairRepr;
+use crate::lattice::set_union::{SetTag, SetUnionRepr};
+use crate::op::OpDelta;
+use crate::tcp_server::TcpServer;
+use crate::tcp_server::serde::serialize;
+
+use super::{Comp, CompStatus, Next};
+
+/// Sends the second half of each `(addrs, payload)` delta to every address in
+/// the first half. The payload is serialized only once per delta. Addresses
+/// which are not connected are skipped rather than failing the comp.
+pub struct TcpMulticastComp<O: OpDelta, Tag, Lr: Any + LatticeRepr>
+where
+    Tag: SetTag<SocketAddr>,
+    SetUnionRepr<Tag, SocketAddr>: LatticeRepr,
+    <SetUnionRepr<Tag, SocketAddr> as LatticeRepr>::Repr: IntoIterator<Item = SocketAddr>,
+    O: OpDelta<LatRepr = PairRepr<SetUnionRepr<Tag, SocketAddr>, Lr>>,
+    Lr::Repr: Serialize,
+{
+    op: O,
+    errors: ErrorHandle,
+    tcp_server: TcpServer,
+    _phantom: std::marker::PhantomData<(Tag, Lr)>,
+}
+
+impl<O: OpDelta, Tag, Lr: Any + LatticeRepr> TcpMulticastComp<O, Tag, Lr>
+where
+    Tag: SetTag<SocketAddr>,
+    SetUnionRepr<Tag, SocketAddr>: LatticeRepr,
+    <SetUnionRepr<Tag, SocketAddr> as LatticeRepr>::Repr: IntoIterator<Item = SocketAddr>,
+    O: OpDelta<LatRepr = PairRepr<SetUnionRepr<Tag, SocketAddr>, Lr>>,
+    Lr::Repr: Serialize,
+{
+    pub fn new(op: O, tcp_server: TcpServer) -> Self {
+        Self {
+            op,
+            errors: Default::default(),
+            tcp_server,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<O: OpDelta, Tag, Lr: Any + LatticeRepr> Comp for TcpMulticastComp<O, Tag, Lr>
+where
+    Tag: SetTag<SocketAddr>,
+    SetUnionRepr<Tag, SocketAddr>: LatticeRepr,
+    <SetUnionRepr<Tag, SocketAddr> as LatticeRepr>::Repr: IntoIterator<Item = SocketAddr>,
+    O: OpDelta<LatRepr = PairRepr<SetUnionRepr<Tag, SocketAddr>, Lr>>,
+    Lr::Repr: Serialize,
+{
+    type Error = SpinachError;
+
+    type TickFuture<'s> = impl Future<Output = Result<CompStatus, Self::Error>>;
+    fn tick(&self) -> Self::TickFuture<'_> {
+        async move {
+            if let Some(hide) = (Next { op: &self.op, errors: &self.errors }).await? {
+                let (addrs, repr) = hide.into_reveal();
+                let bytes = serialize::<Lr>(&repr)?.freeze();
+                self.tcp_server.multicast(addrs, bytes).await;
+                Ok(CompStatus::Continue)
+            }
+            else {
+                Ok(CompStatus::Complete)
+            }
+        }
+    }
+}
@@ -2,16 +2,16 @@ use std::any::Any;
 use std::future::Future;
 use std::net::SocketAddr;
 
-use bincode::{Error, ErrorKind};
 use serde::ser::Serialize;
 
+use crate::error::{ErrorHandle, SpinachError};
 use crate::lattice::{LatticeRepr};
 use crate::lattice::map_union::{MapTag, MapUnionRepr};
 use crate::op::OpDelta;
 use crate::tcp_server::TcpServer;
 use crate::tcp_server::serde::serialize;
 
-use super::{Comp, Next};
+use super::{Comp, CompStatus, Next};
 
 pub struct TcpServerComp<O: OpDelta, Tag, Lr: Any + LatticeRepr>
 where
@@ -22,8 +22,8 @@ where
     Lr::Repr: Serialize,
 {
     op: O,
+    errors: ErrorHandle,
     tcp_server: TcpServer,
-    msgs: std::cell::Cell<usize>,
     _phantom: std::marker::PhantomData<(Tag, Lr)>,
 }
 
@@ -38,8 +38,8 @@ where
     pub fn new(op: O, tcp_server: TcpServer) -> Self {
         Self {
             op,
+            errors: Default::default(),
             tcp_server,
-            msgs: Default::default(),
             _phantom: std::marker::PhantomData,
         }
     }
@@ -53,29 +53,20 @@ where
     <O::LatRepr as LatticeRepr>::Repr: IntoIterator<Item = (SocketAddr, Lr::Repr)>,
     Lr::Repr: Serialize,
 {
-    type Error = Error;
+    type Error = SpinachError;
 
-    type TickFuture<'s> = impl Future<Output = Result<(), Self::Error>>;
+    type TickFuture<'s> = impl Future<Output = Result<CompStatus, Self::Error>>;
     fn tick(&self) -> Self::TickFuture<'_> {
         async move {
-            if let Some(hide) = (Next { op: &self.op }).await {
+            if let Some(hide) = (Next { op: &self.op, errors: &self.errors }).await? {
                 for (addr, repr) in hide.into_reveal().into_iter() {
-                    let bytes = serialize::<Lr>(repr)?.freeze();
+                    let bytes = serialize::<Lr>(&repr)?.freeze();
                     self.tcp_server.write(addr, bytes).await?;
-
-                    {
-                        let msgs = self.msgs.get() + 1;
-                        if 1 == msgs || 0 == msgs % 5000 {
-                            let time = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis();
-                            println!("{} MESSAGES SENT: {}", time, msgs);
-                        }
-                        self.msgs.set(msgs);
-                    }
                 }
-                Ok(())
+                Ok(CompStatus::Continue)
             }
             else {
-                Err(Box::new(ErrorKind::Custom("End of stream.".to_owned())))
+                Ok(CompStatus::Complete)
             }
         }
     }
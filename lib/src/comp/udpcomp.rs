@@ -0,0 +1,90 @@
+use std::any::Any;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use bincode::ErrorKind;
+use serde::ser::Serialize;
+use tokio::net::UdpSocket;
+
+use crate::error::{ErrorHandle, SpinachError};
+use crate::lattice::{LatticeRepr, Split};
+use crate::op::{OpDelta, MAX_DATAGRAM_SIZE};
+use crate::tcp_server::serde::serialize;
+
+use super::{Comp, CompStatus, Next};
+
+/// Sends each delta to every peer as datagrams. Deltas which serialize larger
+/// than the maximum datagram size are split (via `Split`) into smaller deltas.
+pub struct UdpComp<O: OpDelta>
+where
+    O::LatRepr: Any + Split,
+    <O::LatRepr as LatticeRepr>::Repr: Serialize,
+{
+    op: O,
+    errors: ErrorHandle,
+    socket: Arc<UdpSocket>,
+    peers: Vec<SocketAddr>,
+    max_datagram_size: usize,
+}
+
+impl<O: OpDelta> UdpComp<O>
+where
+    O::LatRepr: Any + Split,
+    <O::LatRepr as LatticeRepr>::Repr: Serialize,
+{
+    pub fn new(op: O, socket: Arc<UdpSocket>, peers: Vec<SocketAddr>) -> Self {
+        Self::new_with_size(op, socket, peers, MAX_DATAGRAM_SIZE)
+    }
+
+    pub fn new_with_size(op: O, socket: Arc<UdpSocket>, peers: Vec<SocketAddr>, max_datagram_size: usize) -> Self {
+        Self {
+            op,
+            errors: Default::default(),
+            socket,
+            peers,
+            max_datagram_size,
+        }
+    }
+}
+
+impl<O: OpDelta> Comp for UdpComp<O>
+where
+    O::LatRepr: Any + Split,
+    <O::LatRepr as LatticeRepr>::Repr: Serialize,
+{
+    type Error = SpinachError;
+
+    type TickFuture<'s> = impl Future<Output = Result<CompStatus, Self::Error>>;
+    fn tick(&self) -> Self::TickFuture<'_> {
+        async move {
+            if let Some(hide) = (Next { op: &self.op, errors: &self.errors }).await? {
+                let mut reprs = vec![ hide.into_reveal() ];
+                while let Some(repr) = reprs.pop() {
+                    let bytes = serialize::<O::LatRepr>(&repr)?;
+                    if bytes.len() <= self.max_datagram_size {
+                        for peer in self.peers.iter() {
+                            self.socket.send_to(&*bytes, peer).await?;
+                        }
+                    }
+                    else {
+                        match <O::LatRepr as Split>::split(repr) {
+                            Ok((a, b)) => {
+                                reprs.push(b);
+                                reprs.push(a);
+                            }
+                            Err(_) => {
+                                return Err(Box::new(ErrorKind::Custom(format!(
+                                    "Delta too large for a datagram: {} bytes.", bytes.len()))).into());
+                            }
+                        }
+                    }
+                }
+                Ok(CompStatus::Continue)
+            }
+            else {
+                Ok(CompStatus::Complete)
+            }
+        }
+    }
+}
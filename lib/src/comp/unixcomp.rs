@@ -0,0 +1,67 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::future::Future;
+
+use futures::sink::SinkExt;
+use serde::ser::Serialize;
+use tokio::net::unix::OwnedWriteHalf;
+use tokio_util::codec::{FramedWrite, LengthDelimitedCodec};
+
+use crate::error::{ErrorHandle, SpinachError};
+use crate::lattice::LatticeRepr;
+use crate::merkle::MAX_FRAME_SIZE;
+use crate::op::OpDelta;
+use crate::tcp_server::serde::serialize;
+
+use super::{Comp, CompStatus, Next};
+
+pub struct UnixComp<O: OpDelta>
+where
+    O::LatRepr: Any,
+    <O::LatRepr as LatticeRepr>::Repr: Serialize,
+{
+    op: O,
+    errors: ErrorHandle,
+    framed_write: RefCell<FramedWrite<OwnedWriteHalf, LengthDelimitedCodec>>,
+}
+
+impl<O: OpDelta> UnixComp<O>
+where
+    O::LatRepr: Any,
+    <O::LatRepr as LatticeRepr>::Repr: Serialize,
+{
+    pub fn new(op: O, unix_write: OwnedWriteHalf) -> Self {
+        let framed_write = LengthDelimitedCodec::builder()
+            .length_field_length(2)
+            .max_frame_length(MAX_FRAME_SIZE)
+            .new_write(unix_write);
+        Self {
+            op,
+            errors: Default::default(),
+            framed_write: RefCell::new(framed_write),
+        }
+    }
+}
+
+impl<O: OpDelta> Comp for UnixComp<O>
+where
+    O::LatRepr: Any,
+    <O::LatRepr as LatticeRepr>::Repr: Serialize,
+{
+    type Error = SpinachError;
+
+    type TickFuture<'s> = impl Future<Output = Result<CompStatus, Self::Error>>;
+    fn tick(&self) -> Self::TickFuture<'_> {
+        async move {
+            let mut framed_write_mut = self.framed_write.borrow_mut();
+            if let Some(hide) = (Next { op: &self.op, errors: &self.errors }).await? {
+                let bytes = serialize::<O::LatRepr>(hide.reveal_ref())?.freeze();
+                framed_write_mut.send(bytes).await?;
+                Ok(CompStatus::Continue)
+            }
+            else {
+                Ok(CompStatus::Complete)
+            }
+        }
+    }
+}
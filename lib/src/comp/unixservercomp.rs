@@ -0,0 +1,72 @@
+use std::any::Any;
+use std::future::Future;
+
+use serde::ser::Serialize;
+
+use crate::error::{ErrorHandle, SpinachError};
+use crate::lattice::{LatticeRepr};
+use crate::lattice::map_union::{MapTag, MapUnionRepr};
+use crate::op::OpDelta;
+use crate::tcp_server::serde::serialize;
+use crate::unix_server::{UnixPeer, UnixServer};
+
+use super::{Comp, CompStatus, Next};
+
+pub struct UnixServerComp<O: OpDelta, Tag, Lr: Any + LatticeRepr>
+where
+    Tag: MapTag<UnixPeer, Lr::Repr>,
+    MapUnionRepr<Tag, UnixPeer, Lr>: LatticeRepr,
+    O: OpDelta<LatRepr = MapUnionRepr<Tag, UnixPeer, Lr>>,
+    <O::LatRepr as LatticeRepr>::Repr: IntoIterator<Item = (UnixPeer, Lr::Repr)>,
+    Lr::Repr: Serialize,
+{
+    op: O,
+    errors: ErrorHandle,
+    unix_server: UnixServer,
+    _phantom: std::marker::PhantomData<(Tag, Lr)>,
+}
+
+impl<O: OpDelta, Tag, Lr: Any + LatticeRepr> UnixServerComp<O, Tag, Lr>
+where
+    Tag: MapTag<UnixPeer, Lr::Repr>,
+    MapUnionRepr<Tag, UnixPeer, Lr>: LatticeRepr,
+    O: OpDelta<LatRepr = MapUnionRepr<Tag, UnixPeer, Lr>>,
+    <O::LatRepr as LatticeRepr>::Repr: IntoIterator<Item = (UnixPeer, Lr::Repr)>,
+    Lr::Repr: Serialize,
+{
+    pub fn new(op: O, unix_server: UnixServer) -> Self {
+        Self {
+            op,
+            errors: Default::default(),
+            unix_server,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<O: OpDelta, Tag, Lr: Any + LatticeRepr> Comp for UnixServerComp<O, Tag, Lr>
+where
+    Tag: MapTag<UnixPeer, Lr::Repr>,
+    MapUnionRepr<Tag, UnixPeer, Lr>: LatticeRepr,
+    O: OpDelta<LatRepr = MapUnionRepr<Tag, UnixPeer, Lr>>,
+    <O::LatRepr as LatticeRepr>::Repr: IntoIterator<Item = (UnixPeer, Lr::Repr)>,
+    Lr::Repr: Serialize,
+{
+    type Error = SpinachError;
+
+    type TickFuture<'s> = impl Future<Output = Result<CompStatus, Self::Error>>;
+    fn tick(&self) -> Self::TickFuture<'_> {
+        async move {
+            if let Some(hide) = (Next { op: &self.op, errors: &self.errors }).await? {
+                for (peer, repr) in hide.into_reveal().into_iter() {
+                    let bytes = serialize::<Lr>(&repr)?.freeze();
+                    self.unix_server.write(peer, bytes).await?;
+                }
+                Ok(CompStatus::Continue)
+            }
+            else {
+                Ok(CompStatus::Complete)
+            }
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Errors from ops and comps.
+//!
+//! Comps return their failures as `SpinachError`. Ops can't return errors from
+//! `poll_delta`, so sources instead `report` them to the `ErrorHandle` of the
+//! comp polling the op, which returns them once the op has nothing more ready.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::rc::Rc;
+
+#[derive(Debug)]
+pub enum SpinachError {
+    /// Local I/O failure, e.g. reading a file or writing a socket.
+    Io(io::Error),
+    /// Failure on a single connection, e.g. of a `TcpServer`.
+    Connection {
+        peer: String,
+        error: io::Error,
+    },
+    /// Failure to serialize or deserialize a delta.
+    Codec(Box<dyn std::error::Error>),
+}
+
+impl Display for SpinachError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "I/O error: {}", err),
+            Self::Connection { peer, error } => write!(f, "Connection error on {}: {}", peer, error),
+            Self::Codec(err) => write!(f, "Codec error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for SpinachError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Connection { error, .. } => Some(error),
+            Self::Codec(err) => Some(&**err),
+        }
+    }
+}
+
+impl From<io::Error> for SpinachError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<bincode::Error> for SpinachError {
+    fn from(err: bincode::Error) -> Self {
+        Self::Codec(err)
+    }
+}
+
+impl From<serde_json::Error> for SpinachError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Codec(Box::new(err))
+    }
+}
+
+thread_local! {
+    /// The errors of the comp currently polling its op, see `ErrorHandle::scope`.
+    static CURRENT: RefCell<Option<Rc<RefCell<VecDeque<SpinachError>>>>> = Default::default();
+}
+
+/// Collects the errors `report`ed by a comp's ops. Each comp owns its own
+/// handle, so errors are returned by the comp whose op reported them.
+#[derive(Default)]
+pub struct ErrorHandle {
+    errors: Rc<RefCell<VecDeque<SpinachError>>>,
+}
+
+impl ErrorHandle {
+    /// Run F, e.g. polling the comp's op, collecting the errors reported within it.
+    pub fn scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        struct Restore(Option<Rc<RefCell<VecDeque<SpinachError>>>>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CURRENT.with(|current| *current.borrow_mut() = self.0.take());
+            }
+        }
+
+        let _restore = Restore(CURRENT.with(|current| current.replace(Some(self.errors.clone()))));
+        f()
+    }
+
+    /// Take the oldest reported error, if any.
+    pub fn take(&self) -> Option<SpinachError> {
+        self.errors.borrow_mut().pop_front()
+    }
+}
+
+/// Report an error from within `OpDelta::poll_delta`. It will be returned by
+/// the comp polling the op. Errors reported while no comp is polling, e.g.
+/// when polling an op directly, are logged and dropped.
+pub fn report(err: impl Into<SpinachError>) {
+    let err = err.into();
+    CURRENT.with(|current| match &*current.borrow() {
+        Some(errors) => errors.borrow_mut().push_back(err),
+        None => tracing::warn!(%err, "error reported outside of a comp"),
+    });
+}
@@ -0,0 +1,131 @@
+//! File formats for `FileOp` and `FileComp`.
+
+use std::io::{Error, ErrorKind, Result};
+
+use bytes::{Buf, BufMut, BytesMut};
+use serde::de::DeserializeOwned;
+use serde::ser::Serialize;
+
+use crate::collections::{Collection, Single};
+use crate::lattice::LatticeRepr;
+use crate::lattice::set_union::SetUnionRepr;
+use crate::tag;
+
+pub trait FileEncode<Lr: LatticeRepr> {
+    /// Append REPR to BUF.
+    fn encode(repr: &Lr::Repr, buf: &mut BytesMut) -> Result<()>;
+}
+
+pub trait FileDecode<Lr: LatticeRepr> {
+    /// Remove and return the next item from BUF if it holds a complete one.
+    /// EOF is set when no more bytes will be read.
+    fn decode(buf: &mut BytesMut, eof: bool) -> Result<Option<Lr::Repr>>;
+}
+
+fn take_line(buf: &mut BytesMut, eof: bool) -> Option<BytesMut> {
+    match buf.iter().position(|&b| b'\n' == b) {
+        Some(i) => {
+            let mut line = buf.split_to(i + 1);
+            line.truncate(i);
+            if line.ends_with(b"\r") {
+                line.truncate(i - 1);
+            }
+            Some(line)
+        }
+        None if eof && !buf.is_empty() => Some(buf.split()),
+        None => None,
+    }
+}
+
+/// One string per line. Each element of a delta is written as its own line,
+/// and each line is read as a `SetUnionRepr<SINGLE, String>` delta.
+pub enum Lines {}
+
+impl<Lr: LatticeRepr> FileEncode<Lr> for Lines
+where
+    Lr::Repr: Collection<String, ()>,
+{
+    fn encode(repr: &Lr::Repr, buf: &mut BytesMut) -> Result<()> {
+        for line in repr.keys() {
+            buf.put_slice(line.as_bytes());
+            buf.put_u8(b'\n');
+        }
+        Ok(())
+    }
+}
+
+impl FileDecode<SetUnionRepr<tag::SINGLE, String>> for Lines {
+    fn decode(buf: &mut BytesMut, eof: bool) -> Result<Option<Single<String>>> {
+        take_line(buf, eof)
+            .map(|line| String::from_utf8(line.to_vec())
+                .map(Single)
+                .map_err(|err| Error::new(ErrorKind::InvalidData, err)))
+            .transpose()
+    }
+}
+
+/// One JSON-serialized delta per line.
+pub enum JsonLines {}
+
+impl<Lr: LatticeRepr> FileEncode<Lr> for JsonLines
+where
+    Lr::Repr: Serialize,
+{
+    fn encode(repr: &Lr::Repr, buf: &mut BytesMut) -> Result<()> {
+        let mut writer = buf.writer();
+        serde_json::to_writer(&mut writer, repr)?;
+        writer.into_inner().put_u8(b'\n');
+        Ok(())
+    }
+}
+
+impl<Lr: LatticeRepr> FileDecode<Lr> for JsonLines
+where
+    Lr::Repr: DeserializeOwned,
+{
+    fn decode(buf: &mut BytesMut, eof: bool) -> Result<Option<Lr::Repr>> {
+        loop {
+            match take_line(buf, eof) {
+                // Skip blank lines.
+                Some(line) if line.iter().all(u8::is_ascii_whitespace) => continue,
+                Some(line) => return Ok(Some(serde_json::from_slice(&*line)?)),
+                None => return Ok(None),
+            }
+        }
+    }
+}
+
+/// Bincode-serialized deltas, each prefixed by its length as a big-endian `u32`.
+pub enum Bincode {}
+
+impl<Lr: LatticeRepr> FileEncode<Lr> for Bincode
+where
+    Lr::Repr: Serialize,
+{
+    fn encode(repr: &Lr::Repr, buf: &mut BytesMut) -> Result<()> {
+        let bytes = bincode::serialize(repr)
+            .map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+        buf.put_u32(bytes.len() as u32);
+        buf.put_slice(&*bytes);
+        Ok(())
+    }
+}
+
+impl<Lr: LatticeRepr> FileDecode<Lr> for Bincode
+where
+    Lr::Repr: DeserializeOwned,
+{
+    fn decode(buf: &mut BytesMut, eof: bool) -> Result<Option<Lr::Repr>> {
+        if buf.len() < 4 || buf.len() < 4 + (&buf[..4]).get_u32() as usize {
+            if eof && !buf.is_empty() {
+                return Err(Error::new(ErrorKind::UnexpectedEof, "Truncated bincode item."));
+            }
+            return Ok(None);
+        }
+        let len = buf.get_u32() as usize;
+        let item = buf.split_to(len);
+        bincode::deserialize(&*item)
+            .map(Some)
+            .map_err(|err| Error::new(ErrorKind::InvalidData, err))
+    }
+}
@@ -0,0 +1,116 @@
+//! The connections of a stream server, shared by `TcpServer` and `UnixServer`.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::task::{Poll, Context};
+
+use bytes::{Bytes, BytesMut};
+use futures::sink::SinkExt;
+use tokio::io::{AsyncRead, AsyncWrite, Result};
+use tokio_util::codec::{Framed, LengthDelimitedCodec};
+use tokio_stream::Stream;
+
+use crate::error::{report, SpinachError};
+use crate::merkle::MAX_FRAME_SIZE;
+
+/// Length-delimited connections, keyed by peer.
+pub(crate) struct FramedStreams<P, S> {
+    streams: Mutex<HashMap<P, Framed<S, LengthDelimitedCodec>>>,
+}
+
+impl<P, S> Default for FramedStreams<P, S> {
+    fn default() -> Self {
+        Self {
+            streams: Default::default(),
+        }
+    }
+}
+
+impl<P: Copy + Eq + Hash + Display, S: AsyncRead + AsyncWrite + Unpin> FramedStreams<P, S> {
+    pub fn insert(&self, peer: P, stream: S) {
+        let framed_stream = LengthDelimitedCodec::builder()
+            .length_field_length(2)
+            .max_frame_length(MAX_FRAME_SIZE)
+            .new_framed(stream);
+
+        let mut streams = self.streams.lock().expect("Poisoned");
+        streams.insert(peer, framed_stream);
+    }
+
+    pub async fn write(&self, peer: P, item: Bytes) -> Result<()> {
+        let mut streams = self.streams.lock().expect("Poisoned");
+        match streams.get_mut(&peer) {
+            Some(stream) => stream.send(item).await,
+            None => Err(std::io::Error::new(
+                std::io::ErrorKind::NotConnected,
+                format!("Peer not found: {}.", peer)))
+        }
+    }
+
+    /// Write ITEM to each of PEERS, skipping peers which are not connected and
+    /// dropping those which fail to receive the write. Returns the number of
+    /// successful writes.
+    pub async fn multicast(&self, peers: impl IntoIterator<Item = P>, item: Bytes) -> usize {
+        let mut streams = self.streams.lock().expect("Poisoned");
+        let mut sent = 0;
+        for peer in peers {
+            let stream = match streams.get_mut(&peer) {
+                Some(stream) => stream,
+                None => {
+                    tracing::debug!(%peer, "multicast target not connected");
+                    continue;
+                }
+            };
+            match stream.send(item.clone()).await {
+                Ok(()) => sent += 1,
+                Err(err) => {
+                    tracing::debug!(%peer, %err, "multicast target dropped");
+                    streams.remove(&peer);
+                }
+            }
+        }
+        sent
+    }
+
+    /// Write ITEM to every connected peer, see `multicast`.
+    pub async fn broadcast(&self, item: Bytes) -> usize {
+        let peers: Vec<P> = self.streams.lock().expect("Poisoned").keys().copied().collect();
+        self.multicast(peers, item).await
+    }
+
+    /// Poll for a frame from any connection. Broken connections are dropped
+    /// and reported, see `error::report`.
+    pub fn poll_read(&self, ctx: &mut Context<'_>) -> Poll<Option<(P, BytesMut)>> {
+        let mut item = None;
+        {
+            let mut streams = self.streams.lock().expect("Poisoned");
+
+            streams.retain(|peer, stream| {
+                if item.is_some() { // "break"
+                    return true;
+                }
+
+                match Pin::new(stream).poll_next(ctx) {
+                    Poll::Ready(Some(Ok(bytes))) => {
+                        item.replace((*peer, bytes));
+                        true
+                    }
+                    Poll::Ready(Some(Err(error))) => {
+                        report(SpinachError::Connection { peer: peer.to_string(), error });
+                        false
+                    }
+                    Poll::Ready(None) => false,
+                    Poll::Pending => true,
+                }
+            });
+        }
+
+        match item {
+            Some((peer, bytes)) => Poll::Ready(Some((peer, bytes))),
+            None => Poll::Pending,
+        }
+    }
+}
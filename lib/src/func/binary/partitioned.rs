@@ -43,4 +43,44 @@ impl<K: Eq + Hash + Clone, F: BinaryMorphism> BinaryMorphism for HashPartitioned
             .collect();
         Hide::new(out)
     }
-}
\ No newline at end of file
+}
+
+/// Like `HashPartitioned`, but keeps keys in order.
+pub struct BTreePartitioned<K: Ord + Clone, F: BinaryMorphism> {
+    func: F,
+    _phantom: std::marker::PhantomData<K>,
+}
+
+impl<K: Ord + Clone, F: BinaryMorphism> BTreePartitioned<K, F> {
+    pub fn new(func: F) -> Self {
+        Self {
+            func,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<K: Ord + Clone, F: BinaryMorphism> BinaryMorphism for BTreePartitioned<K, F> {
+    type InLatReprA = MapUnionRepr<tag::BTREE_MAP, K, F::InLatReprA>;
+    type InLatReprB = MapUnionRepr<tag::BTREE_MAP, K, F::InLatReprB>;
+    type OutLatRepr = MapUnionRepr<tag::BTREE_MAP, K, F::OutLatRepr>;
+
+    fn call<Y: Qualifier>(
+        &self,
+        item_a: Hide<Y, Self::InLatReprA>, item_b: Hide<Y, Self::InLatReprB>
+    )
+        -> Hide<Y, Self::OutLatRepr>
+    {
+        let item_a = item_a.into_reveal();
+        let item_b = item_b.into_reveal();
+
+        let out = item_a.into_iter()
+            .filter_map(|(k, val_a)| {
+                item_b.get(&k)
+                    .map(|val_b| self.func.call::<Y>(Hide::new(val_a), Hide::new(val_b.clone())))
+                    .map(|hide_out| (k, hide_out.into_reveal()))
+            })
+            .collect();
+        Hide::new(out)
+    }
+}
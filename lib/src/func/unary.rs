@@ -26,3 +26,15 @@ pub use closure::*;
 
 mod partitioned;
 pub use partitioned::*;
+
+mod parse;
+pub use parse::*;
+
+mod compose;
+pub use compose::*;
+
+mod pair;
+pub use pair::*;
+
+mod map_values;
+pub use map_values::*;
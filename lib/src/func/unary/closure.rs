@@ -1,7 +1,7 @@
-use crate::hide::{Hide, Qualifier, Delta};
+use crate::hide::{Hide, Qualifier, Delta, Value};
 use crate::lattice::LatticeRepr;
 
-use super::Morphism;
+use super::{Monotone, Morphism};
 
 pub struct ClosureMorphism<In: LatticeRepr, Out: LatticeRepr, F>
 where
@@ -33,3 +33,35 @@ where
         (self.func)(item.into_delta()).into_qualifier_reveal()
     }
 }
+
+/// A monotone function from a closure, which sees the full accumulated value.
+pub struct ClosureMonotone<In: LatticeRepr, Out: LatticeRepr, F>
+where
+    F: Fn(Hide<Value, In>) -> Hide<Value, Out>,
+{
+    func: F,
+    _phantom: std::marker::PhantomData<(In, Out)>,
+}
+
+impl<In: LatticeRepr, Out: LatticeRepr, F> ClosureMonotone<In, Out, F>
+where
+    F: Fn(Hide<Value, In>) -> Hide<Value, Out>,
+{
+    pub fn new(func: F) -> Self {
+        Self {
+            func,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<In: LatticeRepr, Out: LatticeRepr, F> Monotone for ClosureMonotone<In, Out, F>
+where
+    F: Fn(Hide<Value, In>) -> Hide<Value, Out>,
+{
+    type InLatRepr  = In;
+    type OutLatRepr = Out;
+    fn call(&self, item: Hide<Value, Self::InLatRepr>) -> Hide<Value, Self::OutLatRepr> {
+        (self.func)(item)
+    }
+}
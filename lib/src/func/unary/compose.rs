@@ -0,0 +1,74 @@
+use crate::hide::{Hide, Qualifier};
+use crate::lattice::{LatticeRepr, Convert};
+
+use super::Morphism;
+
+/// Applies F, then G, as a single morphism.
+pub struct Compose<F: Morphism, G: Morphism<InLatRepr = F::OutLatRepr>> {
+    f: F,
+    g: G,
+}
+
+impl<F: Morphism, G: Morphism<InLatRepr = F::OutLatRepr>> Compose<F, G> {
+    pub fn new(f: F, g: G) -> Self {
+        Self { f, g }
+    }
+}
+
+impl<F: Morphism, G: Morphism<InLatRepr = F::OutLatRepr>> Morphism for Compose<F, G> {
+    type InLatRepr  = F::InLatRepr;
+    type OutLatRepr = G::OutLatRepr;
+    fn call<Y: Qualifier>(&self, item: Hide<Y, Self::InLatRepr>) -> Hide<Y, Self::OutLatRepr> {
+        self.g.call(self.f.call(item))
+    }
+}
+
+/// Passes items through unchanged.
+pub struct Identity<Lr: LatticeRepr> {
+    _phantom: std::marker::PhantomData<Lr>,
+}
+
+impl<Lr: LatticeRepr> Identity<Lr> {
+    pub fn new() -> Self {
+        Self { _phantom: std::marker::PhantomData }
+    }
+}
+
+impl<Lr: LatticeRepr> Default for Identity<Lr> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Lr: LatticeRepr> Morphism for Identity<Lr> {
+    type InLatRepr  = Lr;
+    type OutLatRepr = Lr;
+    fn call<Y: Qualifier>(&self, item: Hide<Y, Self::InLatRepr>) -> Hide<Y, Self::OutLatRepr> {
+        item
+    }
+}
+
+/// Converts items to another representation of the same lattice.
+pub struct ConvertMorphism<In: LatticeRepr + Convert<Out>, Out: LatticeRepr<Lattice = In::Lattice>> {
+    _phantom: std::marker::PhantomData<(In, Out)>,
+}
+
+impl<In: LatticeRepr + Convert<Out>, Out: LatticeRepr<Lattice = In::Lattice>> ConvertMorphism<In, Out> {
+    pub fn new() -> Self {
+        Self { _phantom: std::marker::PhantomData }
+    }
+}
+
+impl<In: LatticeRepr + Convert<Out>, Out: LatticeRepr<Lattice = In::Lattice>> Default for ConvertMorphism<In, Out> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<In: LatticeRepr + Convert<Out>, Out: LatticeRepr<Lattice = In::Lattice>> Morphism for ConvertMorphism<In, Out> {
+    type InLatRepr  = In;
+    type OutLatRepr = Out;
+    fn call<Y: Qualifier>(&self, item: Hide<Y, Self::InLatRepr>) -> Hide<Y, Self::OutLatRepr> {
+        In::convert_hide(item)
+    }
+}
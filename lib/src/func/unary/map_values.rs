@@ -0,0 +1,50 @@
+use std::iter::FromIterator;
+
+use crate::hide::{Hide, Qualifier};
+use crate::lattice::LatticeRepr;
+use crate::lattice::map_union::{MapTag, MapUnionRepr};
+
+use super::Morphism;
+
+/// Applies F to each value of a map, keeping the keys.
+pub struct MapValues<Tag, K, F: Morphism>
+where
+    Tag: MapTag<K, <F::InLatRepr as LatticeRepr>::Repr> + MapTag<K, <F::OutLatRepr as LatticeRepr>::Repr>,
+    MapUnionRepr<Tag, K, F::InLatRepr>: LatticeRepr,
+    MapUnionRepr<Tag, K, F::OutLatRepr>: LatticeRepr,
+{
+    func: F,
+    _phantom: std::marker::PhantomData<(Tag, K)>,
+}
+
+impl<Tag, K, F: Morphism> MapValues<Tag, K, F>
+where
+    Tag: MapTag<K, <F::InLatRepr as LatticeRepr>::Repr> + MapTag<K, <F::OutLatRepr as LatticeRepr>::Repr>,
+    MapUnionRepr<Tag, K, F::InLatRepr>: LatticeRepr,
+    MapUnionRepr<Tag, K, F::OutLatRepr>: LatticeRepr,
+{
+    pub fn new(func: F) -> Self {
+        Self {
+            func,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<Tag, K, F: Morphism> Morphism for MapValues<Tag, K, F>
+where
+    Tag: MapTag<K, <F::InLatRepr as LatticeRepr>::Repr> + MapTag<K, <F::OutLatRepr as LatticeRepr>::Repr>,
+    MapUnionRepr<Tag, K, F::InLatRepr>: LatticeRepr,
+    MapUnionRepr<Tag, K, F::OutLatRepr>: LatticeRepr,
+    <MapUnionRepr<Tag, K, F::InLatRepr> as LatticeRepr>::Repr: IntoIterator<Item = (K, <F::InLatRepr as LatticeRepr>::Repr)>,
+    <MapUnionRepr<Tag, K, F::OutLatRepr> as LatticeRepr>::Repr: FromIterator<(K, <F::OutLatRepr as LatticeRepr>::Repr)>,
+{
+    type InLatRepr  = MapUnionRepr<Tag, K, F::InLatRepr>;
+    type OutLatRepr = MapUnionRepr<Tag, K, F::OutLatRepr>;
+    fn call<Y: Qualifier>(&self, item: Hide<Y, Self::InLatRepr>) -> Hide<Y, Self::OutLatRepr> {
+        let out = item.into_reveal().into_iter()
+            .map(|(k, val)| (k, self.func.call::<Y>(Hide::new(val)).into_reveal()))
+            .collect();
+        Hide::new(out)
+    }
+}
@@ -0,0 +1,76 @@
+use crate::hide::{Hide, Qualifier};
+use crate::lattice::LatticeRepr;
+use crate::lattice::pair::PairRepr;
+
+use super::Morphism;
+
+/// Applies F to the first and G to the second half of a pair.
+pub struct PairMap<F: Morphism, G: Morphism> {
+    f: F,
+    g: G,
+}
+
+impl<F: Morphism, G: Morphism> PairMap<F, G> {
+    pub fn new(f: F, g: G) -> Self {
+        Self { f, g }
+    }
+}
+
+impl<F: Morphism, G: Morphism> Morphism for PairMap<F, G> {
+    type InLatRepr  = PairRepr<F::InLatRepr, G::InLatRepr>;
+    type OutLatRepr = PairRepr<F::OutLatRepr, G::OutLatRepr>;
+    fn call<Y: Qualifier>(&self, item: Hide<Y, Self::InLatRepr>) -> Hide<Y, Self::OutLatRepr> {
+        let (a, b) = item.split();
+        Hide::zip(self.f.call(a), self.g.call(b))
+    }
+}
+
+/// Projects the first half of a pair.
+pub struct Fst<Ra: LatticeRepr, Rb: LatticeRepr> {
+    _phantom: std::marker::PhantomData<(Ra, Rb)>,
+}
+
+impl<Ra: LatticeRepr, Rb: LatticeRepr> Fst<Ra, Rb> {
+    pub fn new() -> Self {
+        Self { _phantom: std::marker::PhantomData }
+    }
+}
+
+impl<Ra: LatticeRepr, Rb: LatticeRepr> Default for Fst<Ra, Rb> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Ra: LatticeRepr, Rb: LatticeRepr> Morphism for Fst<Ra, Rb> {
+    type InLatRepr  = PairRepr<Ra, Rb>;
+    type OutLatRepr = Ra;
+    fn call<Y: Qualifier>(&self, item: Hide<Y, Self::InLatRepr>) -> Hide<Y, Self::OutLatRepr> {
+        item.split().0
+    }
+}
+
+/// Projects the second half of a pair.
+pub struct Snd<Ra: LatticeRepr, Rb: LatticeRepr> {
+    _phantom: std::marker::PhantomData<(Ra, Rb)>,
+}
+
+impl<Ra: LatticeRepr, Rb: LatticeRepr> Snd<Ra, Rb> {
+    pub fn new() -> Self {
+        Self { _phantom: std::marker::PhantomData }
+    }
+}
+
+impl<Ra: LatticeRepr, Rb: LatticeRepr> Default for Snd<Ra, Rb> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Ra: LatticeRepr, Rb: LatticeRepr> Morphism for Snd<Ra, Rb> {
+    type InLatRepr  = PairRepr<Ra, Rb>;
+    type OutLatRepr = Rb;
+    fn call<Y: Qualifier>(&self, item: Hide<Y, Self::InLatRepr>) -> Hide<Y, Self::OutLatRepr> {
+        item.split().1
+    }
+}
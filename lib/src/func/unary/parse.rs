@@ -0,0 +1,135 @@
+use std::fmt::Display;
+
+use serde::de::DeserializeOwned;
+
+use crate::hide::{Hide, Qualifier};
+use crate::lattice::pair::PairRepr;
+use crate::lattice::set_union::SetUnionRepr;
+use crate::tag;
+
+use super::Morphism;
+
+/// An input which failed to parse, and why.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ParseError {
+    pub input: String,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to parse {:?}: {}", self.input, self.message)
+    }
+}
+
+/// Output of the parse morphisms: the parsed item, or the error. Use `switch()`
+/// to split the two into separate ops.
+pub type ParsedLatRepr<T> = PairRepr<SetUnionRepr<tag::OPTION, T>, SetUnionRepr<tag::OPTION, ParseError>>;
+
+fn parsed<Y: Qualifier, T: Clone, E: Display>(input: String, result: Result<T, E>) -> Hide<Y, ParsedLatRepr<T>> {
+    match result {
+        Ok(item) => Hide::new((Some(item), None)),
+        Err(err) => Hide::new((None, Some(ParseError { input, message: err.to_string() }))),
+    }
+}
+
+/// Parses each line with a closure.
+pub struct ParseClosure<T: Clone, E: Display, F: Fn(&str) -> Result<T, E>> {
+    func: F,
+}
+
+impl<T: Clone, E: Display, F: Fn(&str) -> Result<T, E>> ParseClosure<T, E, F> {
+    pub fn new(func: F) -> Self {
+        Self { func }
+    }
+}
+
+impl<T: Clone, E: Display, F: Fn(&str) -> Result<T, E>> Morphism for ParseClosure<T, E, F> {
+    type InLatRepr  = SetUnionRepr<tag::SINGLE, String>;
+    type OutLatRepr = ParsedLatRepr<T>;
+    fn call<Y: Qualifier>(&self, item: Hide<Y, Self::InLatRepr>) -> Hide<Y, Self::OutLatRepr> {
+        let input = item.into_reveal().0;
+        let result = (self.func)(&*input);
+        parsed(input, result)
+    }
+}
+
+/// Parses each line as a JSON value.
+pub struct ParseJson<T: Clone + DeserializeOwned> {
+    _phantom: std::marker::PhantomData<T>,
+}
+
+impl<T: Clone + DeserializeOwned> ParseJson<T> {
+    pub fn new() -> Self {
+        Self {
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<T: Clone + DeserializeOwned> Default for ParseJson<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone + DeserializeOwned> Morphism for ParseJson<T> {
+    type InLatRepr  = SetUnionRepr<tag::SINGLE, String>;
+    type OutLatRepr = ParsedLatRepr<T>;
+    fn call<Y: Qualifier>(&self, item: Hide<Y, Self::InLatRepr>) -> Hide<Y, Self::OutLatRepr> {
+        let input = item.into_reveal().0;
+        let result = serde_json::from_str(&*input);
+        parsed(input, result)
+    }
+}
+
+/// Parses each line as a CSV row. Without headers, fields are deserialized by
+/// position, with headers (e.g. from the file's first line) fields are matched
+/// to struct fields by name.
+pub struct ParseCsv<T: Clone + DeserializeOwned> {
+    headers: Option<csv::StringRecord>,
+    _phantom: std::marker::PhantomData<T>,
+}
+
+impl<T: Clone + DeserializeOwned> ParseCsv<T> {
+    pub fn new() -> Self {
+        Self {
+            headers: None,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    pub fn new_with_headers<I: IntoIterator<Item = S>, S: AsRef<str>>(headers: I) -> Self {
+        Self {
+            headers: Some(headers.into_iter().collect()),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    fn parse(&self, input: &str) -> csv::Result<T> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(input.as_bytes());
+        let mut record = csv::StringRecord::new();
+        if !reader.read_record(&mut record)? {
+            return Err(csv::Error::from(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "Empty row.")));
+        }
+        record.deserialize(self.headers.as_ref())
+    }
+}
+
+impl<T: Clone + DeserializeOwned> Default for ParseCsv<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone + DeserializeOwned> Morphism for ParseCsv<T> {
+    type InLatRepr  = SetUnionRepr<tag::SINGLE, String>;
+    type OutLatRepr = ParsedLatRepr<T>;
+    fn call<Y: Qualifier>(&self, item: Hide<Y, Self::InLatRepr>) -> Hide<Y, Self::OutLatRepr> {
+        let input = item.into_reveal().0;
+        let result = self.parse(&*input);
+        parsed(input, result)
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::hash::Hash;
 
 use crate::hide::{Hide, Qualifier};
@@ -49,3 +50,102 @@ where
         Hide::new(out)
     }
 }
+
+/// Like `HashPartitioned`, but keeps keys in order.
+pub struct BTreePartitioned<Lr, K: Ord + Clone, F: Morphism>
+where
+    Lr: LatticeRepr<Lattice = MapUnion<K, <F::InLatRepr as LatticeRepr>::Lattice>>,
+    Lr::Repr: IntoIterator<Item = (K, <F::InLatRepr as LatticeRepr>::Repr)>,
+{
+    func: F,
+    _phantom: std::marker::PhantomData<(K, Lr)>,
+}
+
+impl<Lr, K: Ord + Clone, F: Morphism> BTreePartitioned<Lr, K, F>
+where
+    Lr: LatticeRepr<Lattice = MapUnion<K, <F::InLatRepr as LatticeRepr>::Lattice>>,
+    Lr::Repr: IntoIterator<Item = (K, <F::InLatRepr as LatticeRepr>::Repr)>,
+{
+    pub fn new(func: F) -> Self {
+        Self {
+            func,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<Lr, K: Ord + Clone, F: Morphism> Morphism for BTreePartitioned<Lr, K, F>
+where
+    Lr: LatticeRepr<Lattice = MapUnion<K, <F::InLatRepr as LatticeRepr>::Lattice>>,
+    Lr::Repr: IntoIterator<Item = (K, <F::InLatRepr as LatticeRepr>::Repr)>,
+{
+    type InLatRepr  = Lr;
+    type OutLatRepr = MapUnionRepr<tag::BTREE_MAP, K, F::OutLatRepr>;
+
+    fn call<Y: Qualifier>(&self, item: Hide<Y, Self::InLatRepr>) -> Hide<Y, Self::OutLatRepr> {
+        let item = item.into_reveal();
+
+        let out = item.into_iter()
+            .map(|(k, val)| {
+                let hide = self.func.call::<Y>(Hide::new(val));
+                (k, hide.into_reveal())
+            })
+            .collect();
+        Hide::new(out)
+    }
+}
+
+/// Groups keys into ranges split at BOUNDS, keyed by the range's index, for
+/// sharding an ordered keyspace. Range `i` holds the keys at or after
+/// `bounds[i - 1]` and before `bounds[i]`, so there are `bounds.len() + 1`.
+pub struct RangePartitioned<Lr, K: Ord + Clone, F: Morphism>
+where
+    Lr: LatticeRepr<Lattice = MapUnion<K, <F::InLatRepr as LatticeRepr>::Lattice>>,
+    Lr::Repr: IntoIterator<Item = (K, <F::InLatRepr as LatticeRepr>::Repr)>,
+{
+    bounds: Vec<K>,
+    func: F,
+    _phantom: std::marker::PhantomData<Lr>,
+}
+
+impl<Lr, K: Ord + Clone, F: Morphism> RangePartitioned<Lr, K, F>
+where
+    Lr: LatticeRepr<Lattice = MapUnion<K, <F::InLatRepr as LatticeRepr>::Lattice>>,
+    Lr::Repr: IntoIterator<Item = (K, <F::InLatRepr as LatticeRepr>::Repr)>,
+{
+    pub fn new(bounds: Vec<K>, func: F) -> Self {
+        assert!(bounds.windows(2).all(|w| w[0] < w[1]), "RangePartitioned bounds must be strictly increasing.");
+        Self {
+            bounds,
+            func,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Index of the range containing KEY.
+    pub fn partition(&self, key: &K) -> usize {
+        self.bounds.partition_point(|bound| bound <= key)
+    }
+}
+
+impl<Lr, K: Ord + Clone, F: Morphism> Morphism for RangePartitioned<Lr, K, F>
+where
+    Lr: LatticeRepr<Lattice = MapUnion<K, <F::InLatRepr as LatticeRepr>::Lattice>>,
+    Lr::Repr: IntoIterator<Item = (K, <F::InLatRepr as LatticeRepr>::Repr)>,
+{
+    type InLatRepr  = Lr;
+    type OutLatRepr = MapUnionRepr<tag::BTREE_MAP, usize, MapUnionRepr<tag::BTREE_MAP, K, F::OutLatRepr>>;
+
+    fn call<Y: Qualifier>(&self, item: Hide<Y, Self::InLatRepr>) -> Hide<Y, Self::OutLatRepr> {
+        let item = item.into_reveal();
+
+        let mut out: BTreeMap<usize, BTreeMap<K, _>> = BTreeMap::new();
+        for (k, val) in item.into_iter() {
+            let hide = self.func.call::<Y>(Hide::new(val));
+            out.entry(self.partition(&k))
+                .or_default()
+                .insert(k, hide.into_reveal());
+        }
+        Hide::new(out)
+    }
+}
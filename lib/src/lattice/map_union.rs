@@ -14,12 +14,31 @@ impl<K, L: Lattice> Lattice for MapUnion<K, L> {}
 pub trait MapTag<T, U>: tag::Tag2<T, U> {}
 impl<T, U> MapTag<T, U> for tag::HASH_MAP {}
 impl<T, U> MapTag<T, U> for tag::BTREE_MAP {}
+impl<T, U> MapTag<T, U> for tag::IM_HASH_MAP {}
+impl<T, U> MapTag<T, U> for tag::IM_BTREE_MAP {}
 impl<T, U> MapTag<T, U> for tag::VEC {}
+impl<T, U> MapTag<T, U> for tag::SORTED_VEC {}
+impl<T, U, const N: usize> MapTag<T, U> for tag::SMALL_VEC<N> {}
 impl<T, U> MapTag<T, U> for tag::SINGLE {}
 impl<T, U> MapTag<T, U> for tag::OPTION {}
 impl<T, U, const N: usize> MapTag<T, U> for tag::ARRAY<N> {}
 impl<T, U, const N: usize> MapTag<T, U> for tag::MASKED_ARRAY<N> {}
 
+/// Map tags merged by extending with a delta's new entries in one batch. A key
+/// repeated within the delta keeps only one of its values, unlike `SMALL_VEC`,
+/// which inserts entries one at a time.
+pub trait BatchMergeTag {}
+impl BatchMergeTag for tag::HASH_MAP {}
+impl BatchMergeTag for tag::BTREE_MAP {}
+impl BatchMergeTag for tag::IM_HASH_MAP {}
+impl BatchMergeTag for tag::IM_BTREE_MAP {}
+impl BatchMergeTag for tag::VEC {}
+impl BatchMergeTag for tag::SORTED_VEC {}
+impl BatchMergeTag for tag::SINGLE {}
+impl BatchMergeTag for tag::OPTION {}
+impl<const N: usize> BatchMergeTag for tag::ARRAY<N> {}
+impl<const N: usize> BatchMergeTag for tag::MASKED_ARRAY<N> {}
+
 pub struct MapUnionRepr<Tag: MapTag<K, B::Repr>, K, B: LatticeRepr> {
     _phantom: std::marker::PhantomData<(Tag, K, B)>,
 }
@@ -34,11 +53,11 @@ where
 
 impl<K: 'static, SelfTag, DeltaTag, SelfLr: LatticeRepr<Lattice = L>, DeltaLr: LatticeRepr<Lattice = L>, L: Lattice> Merge<MapUnionRepr<DeltaTag, K, DeltaLr>> for MapUnionRepr<SelfTag, K, SelfLr>
 where
-    SelfTag:  MapTag<K, SelfLr::Repr>,
+    SelfTag:  MapTag<K, SelfLr::Repr> + BatchMergeTag,
     DeltaTag: MapTag<K, DeltaLr::Repr>,
     MapUnionRepr<SelfTag,  K, SelfLr>:  LatticeRepr<Lattice = MapUnion<K, L>>,
     MapUnionRepr<DeltaTag, K, DeltaLr>: LatticeRepr<Lattice = MapUnion<K, L>>,
-    <MapUnionRepr<SelfTag,  K, SelfLr>  as LatticeRepr>::Repr: Extend<(K, SelfLr::Repr)> + Collection<K, SelfLr::Repr>,
+    <MapUnionRepr<SelfTag,  K, SelfLr>  as LatticeRepr>::Repr: Extend<(K, SelfLr::Repr)> + CollectionMut<K, SelfLr::Repr>,
     <MapUnionRepr<DeltaTag, K, DeltaLr> as LatticeRepr>::Repr: IntoIterator<Item = (K, DeltaLr::Repr)>,
     SelfLr:  Merge<DeltaLr>,
     DeltaLr: Convert<SelfLr>,
@@ -67,6 +86,71 @@ where
     }
 }
 
+/// Entries are inserted one at a time, which is cheap given the linear scans,
+/// so a key repeated within DELTA has its values merged.
+impl<K: 'static + Eq + Clone, DeltaTag, SelfLr: LatticeRepr<Lattice = L>, DeltaLr: LatticeRepr<Lattice = L>, L: Lattice, const N: usize> Merge<MapUnionRepr<DeltaTag, K, DeltaLr>> for MapUnionRepr<tag::SMALL_VEC<N>, K, SelfLr>
+where
+    DeltaTag: MapTag<K, DeltaLr::Repr>,
+    MapUnionRepr<DeltaTag, K, DeltaLr>: LatticeRepr<Lattice = MapUnion<K, L>>,
+    <MapUnionRepr<DeltaTag, K, DeltaLr> as LatticeRepr>::Repr: IntoIterator<Item = (K, DeltaLr::Repr)>,
+    SelfLr:  Merge<DeltaLr>,
+    SelfLr::Repr: 'static,
+    DeltaLr: Convert<SelfLr>,
+{
+    fn merge(this: &mut SmallVecMap<K, SelfLr::Repr, N>, delta: <MapUnionRepr<DeltaTag, K, DeltaLr> as LatticeRepr>::Repr) -> bool {
+        let mut changed = false;
+        for (k, v) in delta {
+            match this.get_mut(&k) {
+                // Key collision, merge into THIS.
+                Some(target_val) => {
+                    changed |= <SelfLr as Merge<DeltaLr>>::merge(target_val, v);
+                }
+                None => {
+                    changed = true;
+                    this.insert(k, <DeltaLr as Convert<SelfLr>>::convert(v));
+                }
+            }
+        }
+        changed
+    }
+}
+
+impl<K: Eq + Clone, DeltaTag, SelfLr: LatticeRepr<Lattice = L>, DeltaLr: LatticeRepr<Lattice = L>, L: Lattice, const N: usize> TryMerge<MapUnionRepr<DeltaTag, K, DeltaLr>> for MapUnionRepr<tag::MASKED_ARRAY<N>, K, SelfLr>
+where
+    DeltaTag: MapTag<K, DeltaLr::Repr>,
+    MapUnionRepr<DeltaTag, K, DeltaLr>: LatticeRepr<Lattice = MapUnion<K, L>>,
+    <MapUnionRepr<DeltaTag, K, DeltaLr> as LatticeRepr>::Repr: IntoIterator<Item = (K, DeltaLr::Repr)>,
+    SelfLr:  Merge<DeltaLr>,
+    DeltaLr: Convert<SelfLr>,
+{
+    type Overflow = MapUnionRepr<tag::VEC, K, SelfLr>;
+
+    fn try_merge(this: &mut MaskedArray<(K, SelfLr::Repr), N>, delta: <MapUnionRepr<DeltaTag, K, DeltaLr> as LatticeRepr>::Repr) -> (bool, Option<Vec<(K, SelfLr::Repr)>>) {
+        let mut changed = false;
+        let mut overflow = Vec::new();
+        for (k, v) in delta {
+            let existing = this.mask.iter()
+                .zip(this.vals.iter_mut())
+                .find(|(mask, (key, _))| **mask && *key == k);
+            // Key collision, merge into THIS.
+            if let Some((_, (_, target_val))) = existing {
+                changed |= <SelfLr as Merge<DeltaLr>>::merge(target_val, v);
+                continue;
+            }
+            let val: SelfLr::Repr = <DeltaLr as Convert<SelfLr>>::convert(v);
+            match this.mask.iter().position(|mask| !mask) {
+                Some(i) => {
+                    this.mask[i] = true;
+                    this.vals[i] = (k, val);
+                    changed = true;
+                }
+                None => overflow.push((k, val)),
+            }
+        }
+        (changed, if overflow.is_empty() { None } else { Some(overflow) })
+    }
+}
+
 impl<K, SelfInner: LatticeRepr, SelfTag, TargetInner: LatticeRepr, TargetTag> Convert<MapUnionRepr<TargetTag, K, TargetInner>> for MapUnionRepr<SelfTag, K, SelfInner>
 where
     SelfTag: MapTag<K, SelfInner::Repr>,
@@ -148,6 +232,23 @@ where
     }
 }
 
+impl<Tag: MapTag<K, B::Repr>, K, B: LatticeRepr> Split for MapUnionRepr<Tag, K, B>
+where
+    MapUnionRepr<Tag, K, B>: LatticeRepr<Lattice = MapUnion<K, B::Lattice>>,
+    <MapUnionRepr<Tag, K, B> as LatticeRepr>::Repr: Collection<K, B::Repr> + IntoIterator<Item = (K, B::Repr)> + FromIterator<(K, B::Repr)>,
+{
+    fn split(this: Self::Repr) -> Result<(Self::Repr, Self::Repr), Self::Repr> {
+        let len = this.len();
+        if len < 2 {
+            return Err(this);
+        }
+        let mut iter = this.into_iter();
+        let a = iter.by_ref().take(len / 2).collect();
+        let b = iter.collect();
+        Ok((a, b))
+    }
+}
+
 // impl<Tag: MapTag<K, B::Repr>, K, B: LatticeRepr> Bottom for MapUnionRepr<Tag, K, B>
 // where
 //     Tag::Bind: Clone,
@@ -159,6 +260,8 @@ where
 // }
 
 mod fns {
+    use std::ops::{Bound, RangeBounds};
+
     use crate::hide::{Hide, Qualifier};
     use crate::lattice::set_union::{SetTag, SetUnion, SetUnionRepr};
 
@@ -249,6 +352,37 @@ mod fns {
             Hide::new(out)
         }
     }
+
+    impl<Y: Qualifier, K: Clone + Ord, InnerLr: LatticeRepr> Hide<Y, MapUnionRepr<tag::BTREE_MAP, K, InnerLr>> {
+        /// Clone out only the entries with keys in RANGE. A reversed RANGE is
+        /// empty.
+        pub fn range(&self, range: impl RangeBounds<K>) -> Hide<Y, MapUnionRepr<tag::VEC, K, InnerLr>> {
+            // `BTreeMap::range` panics on these instead.
+            let reversed = match (range.start_bound(), range.end_bound()) {
+                (Bound::Excluded(start), Bound::Excluded(end)) => start >= end,
+                (Bound::Included(start) | Bound::Excluded(start), Bound::Included(end) | Bound::Excluded(end)) => start > end,
+                _ => false,
+            };
+            if reversed {
+                return Hide::new(Vec::new());
+            }
+            let out = self.reveal_ref().range(range)
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+            Hide::new(out)
+        }
+    }
+
+    impl<Y: Qualifier, InnerLr: LatticeRepr> Hide<Y, MapUnionRepr<tag::BTREE_MAP, String, InnerLr>> {
+        /// Clone out only the entries with keys starting with PREFIX.
+        pub fn prefix(&self, prefix: &str) -> Hide<Y, MapUnionRepr<tag::VEC, String, InnerLr>> {
+            let out = self.reveal_ref().range::<str, _>((Bound::Included(prefix), Bound::Unbounded))
+                .take_while(|(k, _)| k.starts_with(prefix))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+            Hide::new(out)
+        }
+    }
 }
 
 fn __assert_merges() {
@@ -263,9 +397,27 @@ fn __assert_merges() {
     assert_impl_all!(HashMapHashSet: Merge<HashMapHashSet>);
     assert_impl_all!(HashMapHashSet: Merge<HashMapArraySet>);
 
+    type SortedVecMapSet = MapUnionRepr<tag::SORTED_VEC,   String, SetUnionRepr<tag::SORTED_VEC, u32>>;
+    type SmallVecMapSet  = MapUnionRepr<tag::SMALL_VEC<4>, String, SetUnionRepr<tag::SMALL_VEC<4>, u32>>;
+
+    assert_impl_all!(SortedVecMapSet: Merge<SortedVecMapSet>, Merge<HashMapHashSet>, Merge<SmallVecMapSet>, Compare<HashMapHashSet>);
+    assert_impl_all!(SmallVecMapSet: Merge<SmallVecMapSet>, Merge<HashMapHashSet>, Merge<SortedVecMapSet>, Compare<SortedVecMapSet>);
+    assert_impl_all!(HashMapHashSet: Merge<SortedVecMapSet>, Merge<SmallVecMapSet>);
+
+    type ImHashMapSet  = MapUnionRepr<tag::IM_HASH_MAP,  String, SetUnionRepr<tag::IM_HASH_SET, u32>>;
+    type ImBTreeMapSet = MapUnionRepr<tag::IM_BTREE_MAP, String, SetUnionRepr<tag::HASH_SET, u32>>;
+
+    assert_impl_all!(ImHashMapSet: Merge<ImHashMapSet>, Merge<HashMapHashSet>, Merge<HashMapArraySet>, Merge<ImBTreeMapSet>, Compare<HashMapHashSet>, Convert<HashMapHashSet>);
+    assert_impl_all!(ImBTreeMapSet: Merge<ImBTreeMapSet>, Merge<HashMapHashSet>, Merge<ImHashMapSet>, Compare<ImHashMapSet>, Convert<SortedVecMapSet>);
+    assert_impl_all!(HashMapHashSet: Merge<ImHashMapSet>, Merge<ImBTreeMapSet>, Compare<ImHashMapSet>);
+
     assert_not_impl_any!(HashMapArraySet: Merge<HashMapHashSet>);
     assert_not_impl_any!(HashMapArraySet: Merge<HashMapArraySet>);
 
+    type MaskedArrayMapHashSet = MapUnionRepr<tag::MASKED_ARRAY<4>, String, SetUnionRepr<tag::HASH_SET, u32>>;
+    assert_impl_all!(MaskedArrayMapHashSet: TryMerge<HashMapHashSet>, TryMerge<HashMapArraySet>, TryMerge<MaskedArrayMapHashSet>);
+    assert_not_impl_any!(MaskedArrayMapHashSet: Merge<HashMapHashSet>);
+
     assert_not_impl_any!(OptionMapArraySet: Merge<HashMapHashSet>);
     assert_not_impl_any!(OptionMapArraySet: Merge<HashMapArraySet>);
 }
@@ -24,6 +24,19 @@ pub trait Merge<Delta: LatticeRepr>: LatticeRepr<Lattice = Delta::Lattice> {
     }
 }
 
+/// Merge into a fixed-capacity repr, such as `MASKED_ARRAY<N>`, which can't
+/// implement `Merge` because it may run out of room.
+pub trait TryMerge<Delta: LatticeRepr>: LatticeRepr<Lattice = Delta::Lattice> {
+    /// Where the parts of a delta that don't fit go.
+    type Overflow: LatticeRepr<Lattice = Self::Lattice>;
+
+    /// Merge as much of DELTA into THIS as fits. Return TRUE if THIS changed,
+    /// FALSE if THIS was unchanged, along with the remainder of DELTA if it
+    /// didn't all fit. The remainder can be merged into a larger repr to spill
+    /// over.
+    fn try_merge(this: &mut Self::Repr, delta: Delta::Repr) -> (bool, Option<<Self::Overflow as LatticeRepr>::Repr>);
+}
+
 pub trait Convert<Target: LatticeRepr<Lattice = Self::Lattice>>: LatticeRepr {
     fn convert(this: Self::Repr) -> Target::Repr;
 
@@ -36,6 +49,12 @@ pub trait Compare<Other: LatticeRepr<Lattice = Self::Lattice>>: LatticeRepr {
     fn compare(this: &Self::Repr, other: &Other::Repr) -> Option<std::cmp::Ordering>;
 }
 
+pub trait Split: LatticeRepr {
+    /// Split THIS into two non-empty parts which merge back into THIS.
+    /// Returns THIS unchanged as an `Err` if it cannot be split further.
+    fn split(this: Self::Repr) -> Result<(Self::Repr, Self::Repr), Self::Repr>;
+}
+
 pub trait Debottom: LatticeRepr {
     fn is_bottom(this: &Self::Repr) -> bool;
 
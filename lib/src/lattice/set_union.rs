@@ -1,10 +1,10 @@
 use std::iter::FromIterator;
 use std::cmp::Ordering;
 
-use super::{Lattice, LatticeRepr, Merge, Convert, Compare, Debottom};
+use super::{Lattice, LatticeRepr, Merge, TryMerge, Convert, Compare, Debottom, Split};
 
 use crate::tag;
-use crate::collections::Collection;
+use crate::collections::{Collection, MaskedArray};
 
 
 pub struct SetUnion<T> {
@@ -15,7 +15,12 @@ impl<T> Lattice for SetUnion<T> {}
 pub trait SetTag<T>: tag::Tag1<T> {}
 impl<T> SetTag<T> for tag::HASH_SET {}
 impl<T> SetTag<T> for tag::BTREE_SET {}
+impl<T> SetTag<T> for tag::IM_HASH_SET {}
 impl<T> SetTag<T> for tag::VEC {}
+impl<T> SetTag<T> for tag::SORTED_VEC {}
+impl<T, const N: usize> SetTag<T> for tag::SMALL_VEC<N> {}
+impl SetTag<u32> for tag::BITMAP {}
+impl SetTag<u64> for tag::BITMAP {}
 impl<T> SetTag<T> for tag::SINGLE {}
 impl<T> SetTag<T> for tag::OPTION {}
 impl<T, const N: usize> SetTag<T> for tag::ARRAY<N> {}
@@ -47,6 +52,36 @@ where
     }
 }
 
+impl<T: Eq + Clone, DeltaTag: SetTag<T>, const N: usize> TryMerge<SetUnionRepr<DeltaTag, T>> for SetUnionRepr<tag::MASKED_ARRAY<N>, T>
+where
+    SetUnionRepr<DeltaTag, T>: LatticeRepr<Lattice = SetUnion<T>>,
+    <SetUnionRepr<DeltaTag, T> as LatticeRepr>::Repr: IntoIterator<Item = T>,
+{
+    type Overflow = SetUnionRepr<tag::VEC, T>;
+
+    fn try_merge(this: &mut MaskedArray<T, N>, delta: <SetUnionRepr<DeltaTag, T> as LatticeRepr>::Repr) -> (bool, Option<Vec<T>>) {
+        let mut changed = false;
+        let mut overflow = Vec::new();
+        for item in delta {
+            let present = this.mask.iter()
+                .zip(this.vals.iter())
+                .any(|(mask, val)| *mask && *val == item);
+            if present {
+                continue;
+            }
+            match this.mask.iter().position(|mask| !mask) {
+                Some(i) => {
+                    this.mask[i] = true;
+                    this.vals[i] = item;
+                    changed = true;
+                }
+                None => overflow.push(item),
+            }
+        }
+        (changed, if overflow.is_empty() { None } else { Some(overflow) })
+    }
+}
+
 impl<T, SelfTag: SetTag<T>, TargetTag: SetTag<T>> Convert<SetUnionRepr<TargetTag, T>> for SetUnionRepr<SelfTag, T>
 where
     SetUnionRepr<SelfTag,   T>: LatticeRepr<Lattice = SetUnion<T>>,
@@ -68,7 +103,7 @@ where
 {
     fn compare(this: &<SetUnionRepr<SelfTag, T> as LatticeRepr>::Repr, other: &<SetUnionRepr<TargetTag, T> as LatticeRepr>::Repr) -> Option<Ordering> {
         if this.len() > other.len() {
-            if this.keys().all(|key| other.get(key).is_some()) {
+            if this.keys().all(|key| other.contains(key)) {
                 Some(Ordering::Greater)
             }
             else {
@@ -76,7 +111,7 @@ where
             }
         }
         else if this.len() == other.len() {
-            if this.keys().all(|key| other.get(key).is_some()) {
+            if this.keys().all(|key| other.contains(key)) {
                 Some(Ordering::Equal)
             }
             else {
@@ -84,7 +119,7 @@ where
             }
         }
         else { // this.len() < other.len()
-            if other.keys().all(|key| this.get(key).is_some()) {
+            if other.keys().all(|key| this.contains(key)) {
                 Some(Ordering::Less)
             }
             else {
@@ -94,6 +129,88 @@ where
     }
 }
 
+impl<Tag: SetTag<T>, T> Split for SetUnionRepr<Tag, T>
+where
+    SetUnionRepr<Tag, T>: LatticeRepr<Lattice = SetUnion<T>>,
+    <SetUnionRepr<Tag, T> as LatticeRepr>::Repr: Collection<T, ()> + IntoIterator<Item = T> + FromIterator<T>,
+{
+    fn split(this: Self::Repr) -> Result<(Self::Repr, Self::Repr), Self::Repr> {
+        let len = this.len();
+        if len < 2 {
+            return Err(this);
+        }
+        let mut iter = this.into_iter();
+        let a = iter.by_ref().take(len / 2).collect();
+        let b = iter.collect();
+        Ok((a, b))
+    }
+}
+
+// `BITMAP` reprs can't implement `Collection`, which lends out references to
+// keys, so they get their own `Merge` and `Compare` impls. Merges between
+// bitmaps are a bitwise OR and compares are subset tests. `Convert` is covered
+// by the generic impl above.
+macro_rules! bitmap_impls {
+    ($t:ty, $bitmap:ty, $( [ $( $gen:tt )* ] $tag:ty ),* $(,)?) => {
+        impl Merge<SetUnionRepr<tag::BITMAP, $t>> for SetUnionRepr<tag::BITMAP, $t> {
+            fn merge(this: &mut $bitmap, delta: $bitmap) -> bool {
+                let old_len = this.len();
+                *this |= delta;
+                this.len() > old_len
+            }
+        }
+
+        impl Compare<SetUnionRepr<tag::BITMAP, $t>> for SetUnionRepr<tag::BITMAP, $t> {
+            fn compare(this: &$bitmap, other: &$bitmap) -> Option<Ordering> {
+                match this.len().cmp(&other.len()) {
+                    Ordering::Greater => other.is_subset(this).then(|| Ordering::Greater),
+                    Ordering::Equal => (this == other).then(|| Ordering::Equal),
+                    Ordering::Less => this.is_subset(other).then(|| Ordering::Less),
+                }
+            }
+        }
+
+        $(
+            impl<$( $gen )*> Merge<SetUnionRepr<$tag, $t>> for SetUnionRepr<tag::BITMAP, $t> {
+                fn merge(this: &mut $bitmap, delta: <SetUnionRepr<$tag, $t> as LatticeRepr>::Repr) -> bool {
+                    let old_len = this.len();
+                    this.extend(delta);
+                    this.len() > old_len
+                }
+            }
+
+            impl<$( $gen )*> Compare<SetUnionRepr<$tag, $t>> for SetUnionRepr<tag::BITMAP, $t> {
+                fn compare(this: &$bitmap, other: &<SetUnionRepr<$tag, $t> as LatticeRepr>::Repr) -> Option<Ordering> {
+                    let this_len = this.len() as usize;
+                    if this_len >= other.len() {
+                        other.keys().all(|key| this.contains(*key))
+                            .then(|| this_len.cmp(&other.len()))
+                    }
+                    else {
+                        this.iter().all(|key| other.contains(&key))
+                            .then(|| Ordering::Less)
+                    }
+                }
+            }
+
+            impl<$( $gen )*> Compare<SetUnionRepr<tag::BITMAP, $t>> for SetUnionRepr<$tag, $t> {
+                fn compare(this: &<SetUnionRepr<$tag, $t> as LatticeRepr>::Repr, other: &$bitmap) -> Option<Ordering> {
+                    <SetUnionRepr<tag::BITMAP, $t> as Compare<SetUnionRepr<$tag, $t>>>::compare(other, this)
+                        .map(Ordering::reverse)
+                }
+            }
+        )*
+    };
+}
+bitmap_impls!(u32, roaring::RoaringBitmap,
+    [] tag::HASH_SET, [] tag::BTREE_SET, [] tag::IM_HASH_SET, [] tag::VEC, [] tag::SORTED_VEC, [const N: usize] tag::SMALL_VEC<N>,
+    [] tag::SINGLE, [] tag::OPTION, [const N: usize] tag::ARRAY<N>, [const N: usize] tag::MASKED_ARRAY<N>,
+);
+bitmap_impls!(u64, roaring::RoaringTreemap,
+    [] tag::HASH_SET, [] tag::BTREE_SET, [] tag::IM_HASH_SET, [] tag::VEC, [] tag::SORTED_VEC, [const N: usize] tag::SMALL_VEC<N>,
+    [] tag::SINGLE, [] tag::OPTION, [const N: usize] tag::ARRAY<N>, [const N: usize] tag::MASKED_ARRAY<N>,
+);
+
 // impl<Tag: SetTag<T>, T> Debottom for SetUnionRepr<Tag, T>
 // where
 //     Tag::Bind: Clone,
@@ -121,6 +238,8 @@ fn __assert_merges() {
         Merge<SetUnionRepr<tag::HASH_SET, u32>>,
         Merge<SetUnionRepr<tag::BTREE_SET, u32>>,
         Merge<SetUnionRepr<tag::VEC, u32>>,
+        Merge<SetUnionRepr<tag::SORTED_VEC, u32>>,
+        Merge<SetUnionRepr<tag::SMALL_VEC<8>, u32>>,
         Merge<SetUnionRepr<tag::SINGLE, u32>>,
         Merge<SetUnionRepr<tag::OPTION, u32>>,
         Merge<SetUnionRepr<tag::ARRAY<8>, u32>>,
@@ -131,6 +250,8 @@ fn __assert_merges() {
         Merge<SetUnionRepr<tag::HASH_SET, u32>>,
         Merge<SetUnionRepr<tag::BTREE_SET, u32>>,
         Merge<SetUnionRepr<tag::VEC, u32>>,
+        Merge<SetUnionRepr<tag::SORTED_VEC, u32>>,
+        Merge<SetUnionRepr<tag::SMALL_VEC<8>, u32>>,
         Merge<SetUnionRepr<tag::SINGLE, u32>>,
         Merge<SetUnionRepr<tag::OPTION, u32>>,
         Merge<SetUnionRepr<tag::ARRAY<8>, u32>>,
@@ -141,16 +262,99 @@ fn __assert_merges() {
         Merge<SetUnionRepr<tag::HASH_SET, u32>>,
         Merge<SetUnionRepr<tag::BTREE_SET, u32>>,
         Merge<SetUnionRepr<tag::VEC, u32>>,
+        Merge<SetUnionRepr<tag::SORTED_VEC, u32>>,
+        Merge<SetUnionRepr<tag::SMALL_VEC<8>, u32>>,
+        Merge<SetUnionRepr<tag::SINGLE, u32>>,
+        Merge<SetUnionRepr<tag::OPTION, u32>>,
+        Merge<SetUnionRepr<tag::ARRAY<8>, u32>>,
+        Merge<SetUnionRepr<tag::MASKED_ARRAY<8>, u32>>,
+    );
+
+    assert_impl_all!(SetUnionRepr<tag::SORTED_VEC, u32>:
+        Merge<SetUnionRepr<tag::HASH_SET, u32>>,
+        Merge<SetUnionRepr<tag::BTREE_SET, u32>>,
+        Merge<SetUnionRepr<tag::VEC, u32>>,
+        Merge<SetUnionRepr<tag::SORTED_VEC, u32>>,
+        Merge<SetUnionRepr<tag::SMALL_VEC<8>, u32>>,
         Merge<SetUnionRepr<tag::SINGLE, u32>>,
         Merge<SetUnionRepr<tag::OPTION, u32>>,
         Merge<SetUnionRepr<tag::ARRAY<8>, u32>>,
         Merge<SetUnionRepr<tag::MASKED_ARRAY<8>, u32>>,
+        Convert<SetUnionRepr<tag::HASH_SET, u32>>,
+        Compare<SetUnionRepr<tag::VEC, u32>>,
+    );
+
+    assert_impl_all!(SetUnionRepr<tag::SMALL_VEC<8>, u32>:
+        Merge<SetUnionRepr<tag::HASH_SET, u32>>,
+        Merge<SetUnionRepr<tag::BTREE_SET, u32>>,
+        Merge<SetUnionRepr<tag::VEC, u32>>,
+        Merge<SetUnionRepr<tag::SORTED_VEC, u32>>,
+        Merge<SetUnionRepr<tag::SMALL_VEC<8>, u32>>,
+        Merge<SetUnionRepr<tag::SINGLE, u32>>,
+        Merge<SetUnionRepr<tag::OPTION, u32>>,
+        Merge<SetUnionRepr<tag::ARRAY<8>, u32>>,
+        Merge<SetUnionRepr<tag::MASKED_ARRAY<8>, u32>>,
+        Convert<SetUnionRepr<tag::SORTED_VEC, u32>>,
+        Compare<SetUnionRepr<tag::SORTED_VEC, u32>>,
+    );
+
+    assert_impl_all!(SetUnionRepr<tag::IM_HASH_SET, u32>:
+        Merge<SetUnionRepr<tag::IM_HASH_SET, u32>>,
+        Merge<SetUnionRepr<tag::HASH_SET, u32>>,
+        Merge<SetUnionRepr<tag::VEC, u32>>,
+        Merge<SetUnionRepr<tag::SINGLE, u32>>,
+        Merge<SetUnionRepr<tag::ARRAY<8>, u32>>,
+        Merge<SetUnionRepr<tag::BITMAP, u32>>,
+        Compare<SetUnionRepr<tag::HASH_SET, u32>>,
+        Compare<SetUnionRepr<tag::BITMAP, u32>>,
+        Convert<SetUnionRepr<tag::HASH_SET, u32>>,
+    );
+
+    assert_impl_all!(SetUnionRepr<tag::HASH_SET, u32>:
+        Merge<SetUnionRepr<tag::IM_HASH_SET, u32>>,
+        Compare<SetUnionRepr<tag::IM_HASH_SET, u32>>,
+        Convert<SetUnionRepr<tag::IM_HASH_SET, u32>>,
+    );
+
+    assert_impl_all!(SetUnionRepr<tag::BITMAP, u32>:
+        Merge<SetUnionRepr<tag::BITMAP, u32>>,
+        Merge<SetUnionRepr<tag::HASH_SET, u32>>,
+        Merge<SetUnionRepr<tag::VEC, u32>>,
+        Merge<SetUnionRepr<tag::SINGLE, u32>>,
+        Merge<SetUnionRepr<tag::ARRAY<8>, u32>>,
+        Compare<SetUnionRepr<tag::BITMAP, u32>>,
+        Compare<SetUnionRepr<tag::HASH_SET, u32>>,
+        Convert<SetUnionRepr<tag::HASH_SET, u32>>,
+        Convert<SetUnionRepr<tag::BITMAP, u32>>,
+    );
+
+    assert_impl_all!(SetUnionRepr<tag::BITMAP, u64>:
+        Merge<SetUnionRepr<tag::BITMAP, u64>>,
+        Merge<SetUnionRepr<tag::BTREE_SET, u64>>,
+        Compare<SetUnionRepr<tag::BITMAP, u64>>,
+        Convert<SetUnionRepr<tag::VEC, u64>>,
+    );
+
+    assert_impl_all!(SetUnionRepr<tag::HASH_SET, u32>:
+        Merge<SetUnionRepr<tag::BITMAP, u32>>,
+        Compare<SetUnionRepr<tag::BITMAP, u32>>,
+        Convert<SetUnionRepr<tag::BITMAP, u32>>,
+    );
+
+    assert_impl_all!(SetUnionRepr<tag::MASKED_ARRAY<8>, u32>:
+        TryMerge<SetUnionRepr<tag::HASH_SET, u32>>,
+        TryMerge<SetUnionRepr<tag::VEC, u32>>,
+        TryMerge<SetUnionRepr<tag::SINGLE, u32>>,
+        TryMerge<SetUnionRepr<tag::ARRAY<8>, u32>>,
+        TryMerge<SetUnionRepr<tag::MASKED_ARRAY<8>, u32>>,
     );
 
     assert_not_impl_any!(SetUnionRepr<tag::MASKED_ARRAY<8>, u32>:
         Merge<SetUnionRepr<tag::HASH_SET, u32>>,
         Merge<SetUnionRepr<tag::BTREE_SET, u32>>,
         Merge<SetUnionRepr<tag::VEC, u32>>,
+        Merge<SetUnionRepr<tag::SORTED_VEC, u32>>,
+        Merge<SetUnionRepr<tag::SMALL_VEC<8>, u32>>,
         Merge<SetUnionRepr<tag::SINGLE, u32>>,
         Merge<SetUnionRepr<tag::OPTION, u32>>,
         Merge<SetUnionRepr<tag::ARRAY<8>, u32>>,
@@ -181,7 +385,7 @@ mod fns {
         <SetUnionRepr<Tag, T> as LatticeRepr>::Repr: Collection<T, ()>,
     {
         pub fn contains(&self, val: &T) -> Hide<Value, MaxRepr<bool>> {
-            Hide::new(self.reveal_ref().get(val).is_some())
+            Hide::new(self.reveal_ref().contains(val))
         }
     }
 
@@ -25,11 +25,14 @@ pub use tokio_util;
 pub use bytes;
 pub use serde;
 pub use bincode;
+pub use tracing;
 
 // Modules
 
 pub mod collections;
 
+pub mod format;
+
 pub mod func;
 
 pub mod tag;
@@ -42,7 +45,26 @@ pub mod op;
 
 pub mod comp;
 
+pub mod error;
+
 pub mod metadata;
 
+pub mod metrics;
+
+pub mod testing;
+
+pub mod persistence;
+
+mod framed_streams;
+
 pub mod tcp_server;
 
+pub mod tcp_client;
+
+pub mod sim_net;
+
+pub mod merkle;
+
+#[cfg(unix)]
+pub mod unix_server;
+
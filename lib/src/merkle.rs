@@ -0,0 +1,248 @@
+//! Merkle-tree digests of `MapUnionRepr` state, for anti-entropy which only
+//! transfers the keys that differ between replicas. See `MerkleSyncOp` and
+//! `MerkleSyncComp`.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::net::SocketAddr;
+use std::rc::Rc;
+
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+use crate::lattice::LatticeRepr;
+use crate::tcp_client::TcpClient;
+use crate::tcp_server::TcpServer;
+
+/// Largest frame the length-delimited codec can carry (2-byte length field).
+pub const MAX_FRAME_SIZE: usize = u16::MAX as usize;
+
+fn hash_of<T: Hash + ?Sized>(item: &T) -> u64 {
+    // `DefaultHasher::new()` uses fixed keys, so hashes agree across processes
+    // (built with the same compiler).
+    let mut hasher = DefaultHasher::new();
+    item.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A complete binary hash tree over the entries of a map. Keys are hashed into
+/// `2^depth` leaf buckets, each leaf is an order-independent hash of its
+/// entries, and each inner node hashes its two children.
+///
+/// Node `i` at level `l` has children `2i` and `2i + 1` at level `l + 1`.
+/// Level `0` is the root and level `depth` holds the leaves.
+pub struct MerkleTree {
+    depth: u8,
+    levels: Vec<Vec<u64>>,
+}
+
+impl MerkleTree {
+    pub fn new<'a, K: 'a + Hash, V: 'a + Hash>(depth: u8, entries: impl IntoIterator<Item = (&'a K, &'a V)>) -> Self {
+        assert!(depth < 32, "Merkle tree depth too large: {}.", depth);
+
+        let mut leaves = vec![0_u64; 1 << depth];
+        for (key, val) in entries {
+            let bucket = Self::bucket(depth, key) as usize;
+            leaves[bucket] = leaves[bucket].wrapping_add(Self::entry_hash(key, val));
+        }
+
+        let mut levels = vec![leaves];
+        while levels[0].len() > 1 {
+            let parents = levels[0]
+                .chunks(2)
+                .map(|pair| hash_of(pair))
+                .collect();
+            levels.insert(0, parents);
+        }
+
+        Self { depth, levels }
+    }
+
+    /// The hash an entry contributes to its leaf bucket.
+    pub fn entry_hash<K: Hash, V: Hash>(key: &K, val: &V) -> u64 {
+        hash_of(&(key, val))
+    }
+
+    /// Replace the OLD contribution of an entry in BUCKET with NEW, see
+    /// `entry_hash`. Use zero as OLD for a new entry. Only the path from the
+    /// leaf to the root is rehashed.
+    pub fn update(&mut self, bucket: u64, old: u64, new: u64) {
+        let mut i = bucket as usize;
+        let leaf = &mut self.levels[self.depth as usize][i];
+        *leaf = leaf.wrapping_sub(old).wrapping_add(new);
+        for level in (0..self.depth as usize).rev() {
+            i /= 2;
+            self.levels[level][i] = hash_of(&self.levels[level + 1][2 * i..2 * i + 2]);
+        }
+    }
+
+    /// The leaf bucket of KEY in a tree of the given DEPTH.
+    pub fn bucket<K: Hash + ?Sized>(depth: u8, key: &K) -> u64 {
+        if 0 == depth {
+            0
+        }
+        else {
+            hash_of(key) >> (64 - depth)
+        }
+    }
+
+    pub fn depth(&self) -> u8 {
+        self.depth
+    }
+
+    pub fn root(&self) -> u64 {
+        self.levels[0][0]
+    }
+
+    pub fn level(&self, level: u8) -> &[u64] {
+        &self.levels[level as usize]
+    }
+
+    /// Indices of the NODES (index, hash) at LEVEL which differ from this tree.
+    pub fn diff(&self, level: u8, nodes: &[(u64, u64)]) -> Vec<u64> {
+        let ours = self.level(level);
+        nodes.iter()
+            .filter(|(i, hash)| ours.get(*i as usize).map_or(false, |our_hash| our_hash != hash))
+            .map(|(i, _)| *i)
+            .collect()
+    }
+
+    /// The (index, hash) pairs of the children of the given nodes at LEVEL.
+    pub fn children(&self, level: u8, indices: &[u64]) -> Vec<(u64, u64)> {
+        let below = self.level(level + 1);
+        indices.iter()
+            .flat_map(|i| [2 * i, 2 * i + 1])
+            .map(|i| (i, below[i as usize]))
+            .collect()
+    }
+}
+
+/// Messages exchanged by `MerkleSyncOp` and `MerkleSyncComp`.
+#[derive(Serialize, Deserialize)]
+pub enum MerkleMessage<K, V> {
+    /// Some of the sender's node hashes at a level of its tree.
+    Digest { depth: u8, level: u8, nodes: Vec<(u64, u64)> },
+    /// Request for all of the receiver's entries in the given leaf buckets.
+    Pull { buckets: Vec<u64> },
+    /// Entries of the sender's state.
+    Push { entries: Vec<(K, V)> },
+}
+
+impl<K, V> MerkleMessage<K, V> {
+    fn split(self) -> Result<(Self, Self), Self> {
+        fn halve<T>(mut vec: Vec<T>) -> Result<(Vec<T>, Vec<T>), Vec<T>> {
+            if vec.len() < 2 {
+                return Err(vec);
+            }
+            let b = vec.split_off(vec.len() / 2);
+            Ok((vec, b))
+        }
+
+        match self {
+            Self::Digest { depth, level, nodes } => halve(nodes)
+                .map(|(a, b)| (Self::Digest { depth, level, nodes: a }, Self::Digest { depth, level, nodes: b }))
+                .map_err(|nodes| Self::Digest { depth, level, nodes }),
+            Self::Pull { buckets } => halve(buckets)
+                .map(|(a, b)| (Self::Pull { buckets: a }, Self::Pull { buckets: b }))
+                .map_err(|buckets| Self::Pull { buckets }),
+            Self::Push { entries } => halve(entries)
+                .map(|(a, b)| (Self::Push { entries: a }, Self::Push { entries: b }))
+                .map_err(|entries| Self::Push { entries }),
+        }
+    }
+}
+
+impl<K: Serialize, V: Serialize> MerkleMessage<K, V> {
+    /// Serialize into one or more frames, splitting the message if it is too large.
+    pub(crate) fn encode(self) -> bincode::Result<Vec<Bytes>> {
+        let mut frames = Vec::new();
+        let mut stack = vec![self];
+        while let Some(msg) = stack.pop() {
+            let bytes = bincode::serialize(&msg)?;
+            if bytes.len() <= MAX_FRAME_SIZE {
+                frames.push(bytes.into());
+                continue;
+            }
+            match msg.split() {
+                Ok((a, b)) => {
+                    stack.push(b);
+                    stack.push(a);
+                }
+                Err(_) => return Err(Box::new(bincode::ErrorKind::Custom(
+                    format!("Merkle message of {} bytes cannot be split to fit in a frame.", bytes.len())))),
+            }
+        }
+        Ok(frames)
+    }
+}
+
+/// Where a message came from, and where to send the response.
+#[derive(Clone, Copy, Debug)]
+pub enum MerkleRoute {
+    /// A connection accepted by our `TcpServer`.
+    Server(SocketAddr),
+    /// One of our peer `TcpClient`s, by index.
+    Peer(usize),
+}
+
+struct MerkleSyncState<K, V> {
+    tcp_server: TcpServer,
+    peers: Vec<TcpClient>,
+    depth: u8,
+    inbox: RefCell<VecDeque<(MerkleRoute, MerkleMessage<K, V>)>>,
+}
+
+/// The connections shared by a `MerkleSyncOp` and `MerkleSyncComp` pair.
+///
+/// The op receives all messages, yielding pushed entries as deltas and queueing
+/// digests and pulls for the comp, which answers them from the current state.
+pub struct MerkleSync<K, Lr: LatticeRepr> {
+    state: Rc<MerkleSyncState<K, Lr::Repr>>,
+}
+
+impl<K, Lr: LatticeRepr> MerkleSync<K, Lr> {
+    /// All replicas must use the same DEPTH, a tree has `2^depth` leaf buckets.
+    pub fn new(tcp_server: TcpServer, peers: Vec<TcpClient>, depth: u8) -> Self {
+        let state = Rc::new(MerkleSyncState {
+            tcp_server,
+            peers,
+            depth,
+            inbox: Default::default(),
+        });
+        Self { state }
+    }
+
+    pub fn tcp_server(&self) -> &TcpServer {
+        &self.state.tcp_server
+    }
+
+    pub fn peers(&self) -> &[TcpClient] {
+        &self.state.peers
+    }
+
+    pub fn depth(&self) -> u8 {
+        self.state.depth
+    }
+
+    pub(crate) fn push_inbox(&self, route: MerkleRoute, msg: MerkleMessage<K, Lr::Repr>) {
+        self.state.inbox.borrow_mut().push_back((route, msg));
+    }
+
+    pub(crate) fn pop_inbox(&self) -> Option<(MerkleRoute, MerkleMessage<K, Lr::Repr>)> {
+        self.state.inbox.borrow_mut().pop_front()
+    }
+
+    pub(crate) fn has_inbox(&self) -> bool {
+        !self.state.inbox.borrow().is_empty()
+    }
+}
+
+impl<K, Lr: LatticeRepr> Clone for MerkleSync<K, Lr> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+        }
+    }
+}
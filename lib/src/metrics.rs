@@ -0,0 +1,188 @@
+//! Counters, gauges, and histograms for ops and comps.
+//!
+//! Metrics are updated as the dataflow runs and collected in a `Metrics`
+//! registry, which renders them in the Prometheus text format. See `MetricsOp`
+//! for per-op metrics and `MetricsComp` to periodically dump them.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Histogram bucket upper bounds for latencies, in seconds.
+pub const DEFAULT_LATENCY_BUCKETS: [f64; 8] = [ 1e-6, 1e-5, 1e-4, 1e-3, 1e-2, 1e-1, 1.0, 10.0 ];
+
+/// A monotonically increasing count.
+#[derive(Clone, Default)]
+pub struct Counter(Arc<AtomicU64>);
+
+impl Counter {
+    pub fn inc(&self) {
+        self.add(1);
+    }
+
+    pub fn add(&self, n: u64) {
+        self.0.fetch_add(n, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// A value which can go up and down.
+#[derive(Clone, Default)]
+pub struct Gauge(Arc<AtomicI64>);
+
+impl Gauge {
+    pub fn set(&self, val: i64) {
+        self.0.store(val, Ordering::Relaxed);
+    }
+
+    pub fn add(&self, n: i64) {
+        self.0.fetch_add(n, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> i64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+struct HistogramState {
+    bounds: Vec<f64>,
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+/// Counts of observed values falling under each of a set of bucket bounds.
+#[derive(Clone)]
+pub struct Histogram(Arc<Mutex<HistogramState>>);
+
+impl Histogram {
+    pub fn new(bounds: &[f64]) -> Self {
+        Self(Arc::new(Mutex::new(HistogramState {
+            bounds: bounds.to_vec(),
+            counts: vec![ 0; bounds.len() ],
+            sum: 0.0,
+            count: 0,
+        })))
+    }
+
+    pub fn observe(&self, val: f64) {
+        let mut state = self.0.lock().expect("Poisoned");
+        if let Some(i) = state.bounds.iter().position(|&bound| val <= bound) {
+            state.counts[i] += 1;
+        }
+        state.sum += val;
+        state.count += 1;
+    }
+
+    pub fn observe_duration(&self, duration: Duration) {
+        self.observe(duration.as_secs_f64());
+    }
+
+    pub fn count(&self) -> u64 {
+        self.0.lock().expect("Poisoned").count
+    }
+
+    pub fn sum(&self) -> f64 {
+        self.0.lock().expect("Poisoned").sum
+    }
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self::new(&DEFAULT_LATENCY_BUCKETS)
+    }
+}
+
+#[derive(Clone)]
+pub enum Metric {
+    Counter(Counter),
+    Gauge(Gauge),
+    Histogram(Histogram),
+}
+
+impl From<Counter> for Metric {
+    fn from(counter: Counter) -> Self {
+        Self::Counter(counter)
+    }
+}
+
+impl From<Gauge> for Metric {
+    fn from(gauge: Gauge) -> Self {
+        Self::Gauge(gauge)
+    }
+}
+
+impl From<Histogram> for Metric {
+    fn from(histogram: Histogram) -> Self {
+        Self::Histogram(histogram)
+    }
+}
+
+/// A registry of named metrics. Clones share the same registry.
+#[derive(Clone, Default)]
+pub struct Metrics {
+    metrics: Arc<Mutex<BTreeMap<String, Metric>>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add METRIC as NAME, replacing any metric already named NAME.
+    pub fn register(&self, name: impl Into<String>, metric: impl Into<Metric>) {
+        self.metrics.lock().expect("Poisoned").insert(name.into(), metric.into());
+    }
+
+    pub fn get(&self, name: &str) -> Option<Metric> {
+        self.metrics.lock().expect("Poisoned").get(name).cloned()
+    }
+
+    /// Render all metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for (name, metric) in self.metrics.lock().expect("Poisoned").iter() {
+            // Writing to a `String` cannot fail.
+            let _ = match metric {
+                Metric::Counter(counter) => {
+                    writeln!(out, "# TYPE {} counter\n{} {}", name, name, counter.get())
+                }
+                Metric::Gauge(gauge) => {
+                    writeln!(out, "# TYPE {} gauge\n{} {}", name, name, gauge.get())
+                }
+                Metric::Histogram(histogram) => {
+                    let state = histogram.0.lock().expect("Poisoned");
+                    let _ = writeln!(out, "# TYPE {} histogram", name);
+                    let mut cumulative = 0;
+                    for (bound, count) in state.bounds.iter().zip(state.counts.iter()) {
+                        cumulative += count;
+                        let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, bound, cumulative);
+                    }
+                    let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, state.count);
+                    writeln!(out, "{}_sum {}\n{}_count {}", name, state.sum, name, state.count)
+                }
+            };
+        }
+        out
+    }
+}
+
+/// Bytes sent and received over a transport, e.g. a `TcpServer`.
+#[derive(Clone, Default)]
+pub struct TransportMetrics {
+    pub bytes_sent: Counter,
+    pub bytes_received: Counter,
+}
+
+impl TransportMetrics {
+    /// Register as `<name>_bytes_sent_total` and `<name>_bytes_received_total`.
+    pub fn register(&self, metrics: &Metrics, name: &str) {
+        metrics.register(format!("{}_bytes_sent_total", name), self.bytes_sent.clone());
+        metrics.register(format!("{}_bytes_received_total", name), self.bytes_received.clone());
+    }
+}
@@ -46,22 +46,24 @@ where
     type Ord = BinaryOpOrder<A::Ord, B::Ord, F>;
 
     fn poll_delta(&self, ctx: &mut Context<'_>) -> Poll<Option<Hide<Delta, Self::LatRepr>>> {
-        let not_ready = match self.op_a.poll_delta(ctx) {
-            Poll::Ready(Some(delta_a)) => {
-                let out = self.func.call(delta_a, self.op_b.get_value().into_delta());
-                return Poll::Ready(Some(out));
+        traced("BinaryOp", || {
+            let not_ready = match self.op_a.poll_delta(ctx) {
+                Poll::Ready(Some(delta_a)) => {
+                    let out = self.func.call(delta_a, self.op_b.get_value().into_delta());
+                    return Poll::Ready(Some(out));
+                }
+                Poll::Ready(None) => Poll::Ready(None),
+                Poll::Pending => Poll::Pending,
+            };
+            match self.op_b.poll_delta(ctx) {
+                Poll::Ready(Some(delta_b)) => {
+                    let out = self.func.call(self.op_a.get_value().into_delta(), delta_b);
+                    return Poll::Ready(Some(out));
+                }
+                Poll::Ready(None) => not_ready,
+                Poll::Pending => Poll::Pending,
             }
-            Poll::Ready(None) => Poll::Ready(None),
-            Poll::Pending => Poll::Pending,
-        };
-        match self.op_b.poll_delta(ctx) {
-            Poll::Ready(Some(delta_b)) => {
-                let out = self.func.call(self.op_a.get_value().into_delta(), delta_b);
-                return Poll::Ready(Some(out));
-            }
-            Poll::Ready(None) => not_ready,
-            Poll::Pending => Poll::Pending,
-        }
+        })
     }
 }
 
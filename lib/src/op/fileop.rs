@@ -0,0 +1,123 @@
+use std::cell::RefCell;
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use bytes::BytesMut;
+use tokio::fs::File;
+use tokio::time::Sleep;
+use tokio_util::io::poll_read_buf;
+
+use crate::error::{report, SpinachError};
+use crate::format::FileDecode;
+use crate::hide::{Hide, Delta};
+use crate::lattice::LatticeRepr;
+use crate::metadata::Order;
+
+use super::*;
+
+/// How often a followed file is checked for appends.
+pub const DEFAULT_FOLLOW_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Reads deltas from a file in format F. If following, the op waits for more
+/// data to be appended at the end of the file, like `tail -f`, instead of ending.
+pub struct FileOp<F: FileDecode<Lr>, Lr: LatticeRepr> {
+    file: RefCell<File>,
+    buf: RefCell<BytesMut>,
+    follow: Option<Duration>,
+    sleep: RefCell<Option<Pin<Box<Sleep>>>>,
+    _phantom: std::marker::PhantomData<(F, Lr)>,
+}
+
+impl<F: FileDecode<Lr>, Lr: LatticeRepr> FileOp<F, Lr> {
+    pub fn new(file: File) -> Self {
+        Self {
+            file: RefCell::new(file),
+            buf: Default::default(),
+            follow: None,
+            sleep: Default::default(),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Follow appends, checking for new data every INTERVAL.
+    pub fn new_follow(file: File, interval: Duration) -> Self {
+        Self {
+            follow: Some(interval),
+            ..Self::new(file)
+        }
+    }
+
+    pub async fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        Ok(Self::new(File::open(path).await?))
+    }
+
+    pub async fn open_follow(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        Ok(Self::new_follow(File::open(path).await?, DEFAULT_FOLLOW_INTERVAL))
+    }
+}
+
+impl<F: FileDecode<Lr>, Lr: LatticeRepr> Op for FileOp<F, Lr> {
+    type LatRepr = Lr;
+
+    fn propegate_saturation(&self) {
+    }
+}
+
+impl<F: FileDecode<Lr>, Lr: LatticeRepr> OpDelta for FileOp<F, Lr> {
+    type Ord = FileOrder;
+
+    fn poll_delta(&self, ctx: &mut Context<'_>) -> Poll<Option<Hide<Delta, Self::LatRepr>>> {
+        let mut buf = self.buf.borrow_mut();
+        loop {
+            match F::decode(&mut buf, false) {
+                Ok(Some(repr)) => return Poll::Ready(Some(Hide::new(repr))),
+                Ok(None) => {}
+                Err(err) => {
+                    report(SpinachError::Codec(Box::new(err)));
+                    continue;
+                }
+            }
+
+            if let Some(sleep) = &mut *self.sleep.borrow_mut() {
+                if sleep.as_mut().poll(ctx).is_pending() {
+                    return Poll::Pending;
+                }
+            }
+            self.sleep.replace(None);
+
+            match poll_read_buf(Pin::new(&mut *self.file.borrow_mut()), ctx, &mut *buf) {
+                Poll::Ready(Ok(0)) => {
+                    match self.follow {
+                        // Wait for more data to be appended.
+                        Some(interval) => {
+                            self.sleep.replace(Some(Box::pin(tokio::time::sleep(interval))));
+                        }
+                        None => {
+                            // Flush any final unterminated item.
+                            return match F::decode(&mut buf, true) {
+                                Ok(Some(repr)) => Poll::Ready(Some(Hide::new(repr))),
+                                Ok(None) => Poll::Ready(None),
+                                Err(err) => {
+                                    report(SpinachError::Codec(Box::new(err)));
+                                    Poll::Ready(None)
+                                }
+                            };
+                        }
+                    }
+                }
+                Poll::Ready(Ok(_)) => {}
+                Poll::Ready(Err(err)) => {
+                    report(err);
+                    return Poll::Ready(None);
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+pub enum FileOrder {}
+impl Order for FileOrder {}
@@ -0,0 +1,76 @@
+use std::any::Any;
+use std::task::{Context, Poll};
+
+use serde::de::DeserializeOwned;
+
+use crate::error::report;
+use crate::hide::{Hide, Delta};
+use crate::lattice::LatticeRepr;
+use crate::metadata::Order;
+use crate::tcp_server::TcpServer;
+use crate::tcp_server::serde::deserialize;
+
+use super::optrait::*;
+
+/// Receives lattice values gossiped by peers' `GossipComp`s. Follow with a
+/// `LatticeOp` to merge the incoming states.
+pub struct GossipOp<Lr: Any + LatticeRepr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    tcp_server: TcpServer,
+    _phantom: std::marker::PhantomData<Lr>,
+}
+
+impl<Lr: Any + LatticeRepr> GossipOp<Lr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    pub fn new(tcp_server: TcpServer) -> Self {
+        Self {
+            tcp_server,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<Lr: Any + LatticeRepr> Op for GossipOp<Lr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    type LatRepr = Lr;
+
+    fn propegate_saturation(&self) {
+    }
+}
+
+pub enum GossipOrder {}
+impl Order for GossipOrder {}
+
+impl<Lr: Any + LatticeRepr> OpDelta for GossipOp<Lr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    type Ord = GossipOrder;
+
+    fn poll_delta(&self, ctx: &mut Context<'_>) -> Poll<Option<Hide<Delta, Self::LatRepr>>> {
+        while let Poll::Ready(result) = self.tcp_server.poll_accept(ctx) {
+            if let Err(err) = result {
+                report(err);
+                break;
+            }
+        }
+
+        loop {
+            match self.tcp_server.poll_read(ctx) {
+                Poll::Ready(Some((_addr, bytes_mut))) => {
+                    match deserialize::<Lr>(&*bytes_mut) {
+                        Ok(repr) => return Poll::Ready(Some(Hide::new(repr))),
+                        Err(err) => report(err),
+                    }
+                }
+                _ => return Poll::Pending,
+            }
+        }
+    }
+}
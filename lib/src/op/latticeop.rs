@@ -1,8 +1,15 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::path::Path;
 use std::task::{Context, Poll};
 
+use serde::de::DeserializeOwned;
+use serde::ser::Serialize;
+
+use crate::error::report;
 use crate::lattice::{LatticeRepr, Merge, Convert};
 use crate::hide::{Hide, Delta, Value};
+use crate::metrics::{Counter, Metrics};
+use crate::persistence::{Persistence, DEFAULT_SNAPSHOT_EVERY};
 
 use super::*;
 
@@ -13,6 +20,11 @@ where
 {
     op: O,
     state: RefCell<Hide<Value, Lr>>,
+    persistence: RefCell<Option<Persistence<Lr, O::LatRepr>>>,
+    persist_failed: Cell<bool>,
+    /// State recovered by `persist`, emitted as the first delta.
+    recovered: RefCell<Option<Hide<Delta, Lr>>>,
+    suppressed: Counter,
 }
 
 impl<O: Op, Lr: LatticeRepr + Merge<O::LatRepr>> LatticeOp<O, Lr>
@@ -23,8 +35,18 @@ where
         Self {
             op,
             state: RefCell::new(Hide::new(bottom)),
+            persistence: RefCell::new(None),
+            persist_failed: Cell::new(false),
+            recovered: RefCell::new(None),
+            suppressed: Default::default(),
         }
     }
+
+    /// Count deltas which didn't change the state, and so were suppressed, as
+    /// `<name>_suppressed_total`.
+    pub fn register_metrics(&self, metrics: &Metrics, name: &str) {
+        metrics.register(format!("{}_suppressed_total", name), self.suppressed.clone());
+    }
 }
 
 impl<O: Op, Lr: LatticeRepr + Merge<O::LatRepr>> LatticeOp<O, Lr>
@@ -36,7 +58,40 @@ where
         Self {
             op,
             state: RefCell::new(Hide::new(Default::default())),
+            persistence: RefCell::new(None),
+            persist_failed: Cell::new(false),
+            recovered: RefCell::new(None),
+            suppressed: Default::default(),
+        }
+    }
+}
+
+impl<O: Op, Lr: LatticeRepr + Merge<O::LatRepr>> LatticeOp<O, Lr>
+where
+    O::LatRepr: Convert<Lr>,
+    Lr::Repr: Serialize + DeserializeOwned,
+    <O::LatRepr as LatticeRepr>::Repr: Serialize + DeserializeOwned,
+{
+    /// Persist this op's state in DIR, recovering any state already there.
+    /// Recovered state is emitted downstream as the first delta. Each delta
+    /// which changes the state is logged before it is emitted. If logging
+    /// fails the error is reported and the op ends, without emitting the
+    /// unlogged delta.
+    pub fn persist(self, dir: impl AsRef<Path>) -> std::io::Result<Self> {
+        self.persist_with_snapshot_every(dir, DEFAULT_SNAPSHOT_EVERY)
+    }
+
+    /// Like `persist`, but snapshot after every SNAPSHOT_EVERY deltas.
+    pub fn persist_with_snapshot_every(self, dir: impl AsRef<Path>, snapshot_every: usize) -> std::io::Result<Self> {
+        let persistence = {
+            let mut state = self.state.borrow_mut();
+            Persistence::open(dir, snapshot_every, state.reveal_mut())?
+        };
+        if persistence.recovered() {
+            self.recovered.replace(Some(self.state.borrow().clone().into_delta()));
         }
+        self.persistence.replace(Some(persistence));
+        Ok(self)
     }
 }
 
@@ -58,20 +113,36 @@ where
     type Ord = O::Ord;
 
     fn poll_delta(&self, ctx: &mut Context<'_>) -> Poll<Option<Hide<Delta, Self::LatRepr>>> {
-        loop {
-            match self.op.poll_delta(ctx) {
-                Poll::Ready(Some(delta)) => {
-                    let state = &mut self.state.borrow_mut();
-                    // F::delta(state, &mut delta); // TODO!! Doesn't minimize deltas.
-                    if Lr::merge_hide(state, delta.clone()) {
-                        return Poll::Ready(Some(<O::LatRepr as Convert<Lr>>::convert_hide(delta)))
+        traced("LatticeOp", || {
+            if self.persist_failed.get() {
+                return Poll::Ready(None);
+            }
+            if let Some(recovered) = self.recovered.take() {
+                return Poll::Ready(Some(recovered));
+            }
+            loop {
+                match self.op.poll_delta(ctx) {
+                    Poll::Ready(Some(delta)) => {
+                        let state = &mut self.state.borrow_mut();
+                        // F::delta(state, &mut delta); // TODO!! Doesn't minimize deltas.
+                        if Lr::merge_hide(state, delta.clone()) {
+                            if let Some(persistence) = &mut *self.persistence.borrow_mut() {
+                                if let Err(err) = persistence.log(delta.reveal_ref(), state.reveal_ref()) {
+                                    report(err);
+                                    self.persist_failed.set(true);
+                                    return Poll::Ready(None);
+                                }
+                            }
+                            return Poll::Ready(Some(<O::LatRepr as Convert<Lr>>::convert_hide(delta)))
+                        }
+                        // Else: Delta did not change state, try again.
+                        self.suppressed.inc();
                     }
-                    // Else: Delta did not change state, try again.
+                    Poll::Ready(None) => return Poll::Ready(None),
+                    Poll::Pending => return Poll::Pending,
                 }
-                Poll::Ready(None) => return Poll::Ready(None),
-                Poll::Pending => return Poll::Pending,
             }
-        }
+        })
     }
 }
 
@@ -82,4 +153,8 @@ where
     fn get_value(&self) -> Hide<Value, Self::LatRepr> {
         self.state.borrow().clone()
     }
+
+    fn with_value<R>(&self, f: impl FnOnce(&Hide<Value, Self::LatRepr>) -> R) -> R {
+        f(&self.state.borrow())
+    }
 }
@@ -0,0 +1,142 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::task::{Context, Poll};
+
+use crate::collections::Collection;
+use crate::hide::{Hide, Delta};
+use crate::lattice::LatticeRepr;
+use crate::lattice::map_union::{MapTag, MapUnionRepr};
+use crate::lattice::set_union::SetUnion;
+use crate::metadata::Order;
+use crate::tag;
+
+use super::*;
+
+/// Point reads against a map state, such as a `LatticeOp`. Each `(id, key)`
+/// query is answered with the key's current value, borrowed with
+/// `OpValue::with_value` rather than cloning the whole state. Queried keys stay
+/// subscribed: whenever a state delta touches them their values are re-emitted.
+pub struct LookupOp<Q: OpDelta, S: OpDelta + OpValue, I: Clone, K: Clone, Tag, Lr: LatticeRepr>
+where
+    Q::LatRepr: LatticeRepr<Lattice = SetUnion<(I, K)>>,
+    <Q::LatRepr as LatticeRepr>::Repr: IntoIterator<Item = (I, K)>,
+    Tag: MapTag<K, Lr::Repr>,
+    MapUnionRepr<Tag, K, Lr>: LatticeRepr,
+    S: Op<LatRepr = MapUnionRepr<Tag, K, Lr>>,
+{
+    queries: Q,
+    state: S,
+    subscriptions: RefCell<HashMap<K, HashSet<I>>>,
+}
+
+impl<Q: OpDelta, S: OpDelta + OpValue, I: Clone, K: Clone, Tag, Lr: LatticeRepr> LookupOp<Q, S, I, K, Tag, Lr>
+where
+    Q::LatRepr: LatticeRepr<Lattice = SetUnion<(I, K)>>,
+    <Q::LatRepr as LatticeRepr>::Repr: IntoIterator<Item = (I, K)>,
+    Tag: MapTag<K, Lr::Repr>,
+    MapUnionRepr<Tag, K, Lr>: LatticeRepr,
+    S: Op<LatRepr = MapUnionRepr<Tag, K, Lr>>,
+{
+    pub fn new(queries: Q, state: S) -> Self {
+        Self {
+            queries,
+            state,
+            subscriptions: Default::default(),
+        }
+    }
+}
+
+impl<Q: OpDelta, S: OpDelta + OpValue, I: Clone, K: Clone, Tag, Lr: LatticeRepr> Op for LookupOp<Q, S, I, K, Tag, Lr>
+where
+    Q::LatRepr: LatticeRepr<Lattice = SetUnion<(I, K)>>,
+    <Q::LatRepr as LatticeRepr>::Repr: IntoIterator<Item = (I, K)>,
+    Tag: MapTag<K, Lr::Repr>,
+    MapUnionRepr<Tag, K, Lr>: LatticeRepr,
+    S: Op<LatRepr = MapUnionRepr<Tag, K, Lr>>,
+{
+    type LatRepr = MapUnionRepr<tag::VEC, I, MapUnionRepr<tag::VEC, K, Lr>>;
+
+    fn propegate_saturation(&self) {
+        self.queries.propegate_saturation();
+        self.state.propegate_saturation()
+    }
+}
+
+pub struct LookupOrder<Q: Order, S: Order>(std::marker::PhantomData<(Q, S)>);
+impl<Q: Order, S: Order> Order for LookupOrder<Q, S> {}
+
+impl<Q: OpDelta, S: OpDelta + OpValue, I: Clone + Eq + Hash, K: Clone + Eq + Hash, Tag, Lr: LatticeRepr> OpDelta for LookupOp<Q, S, I, K, Tag, Lr>
+where
+    Q::LatRepr: LatticeRepr<Lattice = SetUnion<(I, K)>>,
+    <Q::LatRepr as LatticeRepr>::Repr: IntoIterator<Item = (I, K)>,
+    Tag: MapTag<K, Lr::Repr>,
+    MapUnionRepr<Tag, K, Lr>: LatticeRepr,
+    S: Op<LatRepr = MapUnionRepr<Tag, K, Lr>>,
+    <MapUnionRepr<Tag, K, Lr> as LatticeRepr>::Repr: Collection<K, Lr::Repr>,
+{
+    type Ord = LookupOrder<Q::Ord, S::Ord>;
+
+    fn poll_delta(&self, ctx: &mut Context<'_>) -> Poll<Option<Hide<Delta, Self::LatRepr>>> {
+        traced("LookupOp", || {
+            loop {
+                let polled_queries = self.queries.poll_delta(ctx);
+                if let Poll::Ready(Some(queries)) = polled_queries {
+                    let mut subscriptions = self.subscriptions.borrow_mut();
+                    let mut out = Vec::new();
+                    self.state.with_value(|state| {
+                        for (id, key) in queries.into_reveal() {
+                            if let Some(val) = state.reveal_ref().get(&key) {
+                                out.push((id.clone(), (key.clone(), val.clone())));
+                            }
+                            subscriptions.entry(key).or_default().insert(id);
+                        }
+                    });
+                    if !out.is_empty() {
+                        return Poll::Ready(Some(Hide::new(group(out))));
+                    }
+                    continue;
+                }
+
+                let polled_state = self.state.poll_delta(ctx);
+                if let Poll::Ready(Some(delta)) = polled_state {
+                    let subscriptions = self.subscriptions.borrow();
+                    let mut out = Vec::new();
+                    self.state.with_value(|state| {
+                        let state = state.reveal_ref();
+                        for key in delta.reveal_ref().keys() {
+                            let ids = match subscriptions.get(key) {
+                                Some(ids) => ids,
+                                None => continue,
+                            };
+                            if let Some(val) = state.get(key) {
+                                for id in ids {
+                                    out.push((id.clone(), (key.clone(), val.clone())));
+                                }
+                            }
+                        }
+                    });
+                    if !out.is_empty() {
+                        return Poll::Ready(Some(Hide::new(group(out))));
+                    }
+                    continue;
+                }
+
+                // Done once there are no more queries and no more updates.
+                return match (polled_queries, polled_state) {
+                    (Poll::Ready(None), Poll::Ready(None)) => Poll::Ready(None),
+                    _ => Poll::Pending,
+                };
+            }
+        })
+    }
+}
+
+/// Group ENTRIES by ID.
+fn group<I: Eq + Hash, K, V>(entries: Vec<(I, (K, V))>) -> Vec<(I, Vec<(K, V)>)> {
+    let mut grouped: HashMap<I, Vec<(K, V)>> = HashMap::new();
+    for (id, entry) in entries {
+        grouped.entry(id).or_default().push(entry);
+    }
+    grouped.into_iter().collect()
+}
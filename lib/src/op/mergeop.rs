@@ -46,16 +46,18 @@ where
     type Ord = MergeOrder<A::Ord, B::Ord>;
 
     fn poll_delta(&self, ctx: &mut Context<'_>) -> Poll<Option<Hide<Delta, Self::LatRepr>>> {
-        let not_ready = match self.op_a.poll_delta(ctx) {
-            Poll::Ready(Some(delta)) => return Poll::Ready(Some(delta)),
-            Poll::Ready(None) => Poll::Ready(None),
-            Poll::Pending => Poll::Pending,
-        };
-        match self.op_b.poll_delta(ctx) {
-            Poll::Ready(Some(delta)) => Poll::Ready(Some(<B::LatRepr as Convert<A::LatRepr>>::convert_hide(delta))),
-            Poll::Ready(None) => not_ready,
-            Poll::Pending => Poll::Pending,
-        }
+        traced("MergeOp", || {
+            let not_ready = match self.op_a.poll_delta(ctx) {
+                Poll::Ready(Some(delta)) => return Poll::Ready(Some(delta)),
+                Poll::Ready(None) => Poll::Ready(None),
+                Poll::Pending => Poll::Pending,
+            };
+            match self.op_b.poll_delta(ctx) {
+                Poll::Ready(Some(delta)) => Poll::Ready(Some(<B::LatRepr as Convert<A::LatRepr>>::convert_hide(delta))),
+                Poll::Ready(None) => not_ready,
+                Poll::Pending => Poll::Pending,
+            }
+        })
     }
 }
 
@@ -0,0 +1,96 @@
+use std::task::{Context, Poll};
+
+use serde::de::DeserializeOwned;
+
+use crate::error::report;
+use crate::hide::{Hide, Delta};
+use crate::lattice::LatticeRepr;
+use crate::lattice::map_union::MapUnionRepr;
+use crate::merkle::{MerkleMessage, MerkleRoute, MerkleSync};
+use crate::metadata::Order;
+use crate::tag;
+
+use super::optrait::*;
+
+/// Receives the key ranges which differ from peers during Merkle sync. Follow
+/// with a `LatticeOp` which is in turn consumed by the `MerkleSyncComp` sharing
+/// the same `MerkleSync`.
+pub struct MerkleSyncOp<K: Clone, Lr: LatticeRepr>
+where
+    K: DeserializeOwned,
+    Lr::Repr: DeserializeOwned,
+{
+    sync: MerkleSync<K, Lr>,
+}
+
+impl<K: Clone, Lr: LatticeRepr> MerkleSyncOp<K, Lr>
+where
+    K: DeserializeOwned,
+    Lr::Repr: DeserializeOwned,
+{
+    pub fn new(sync: MerkleSync<K, Lr>) -> Self {
+        Self { sync }
+    }
+
+    fn recv(&self, route: MerkleRoute, bytes: &[u8]) -> Option<Vec<(K, Lr::Repr)>> {
+        match bincode::deserialize::<MerkleMessage<K, Lr::Repr>>(bytes) {
+            Ok(MerkleMessage::Push { entries }) => Some(entries),
+            Ok(msg) => {
+                self.sync.push_inbox(route, msg);
+                None
+            }
+            Err(err) => {
+                report(err);
+                None
+            }
+        }
+    }
+}
+
+impl<K: Clone, Lr: LatticeRepr> Op for MerkleSyncOp<K, Lr>
+where
+    K: DeserializeOwned,
+    Lr::Repr: DeserializeOwned,
+{
+    type LatRepr = MapUnionRepr<tag::VEC, K, Lr>;
+
+    fn propegate_saturation(&self) {
+    }
+}
+
+pub enum MerkleSyncOrder {}
+impl Order for MerkleSyncOrder {}
+
+impl<K: Clone, Lr: LatticeRepr> OpDelta for MerkleSyncOp<K, Lr>
+where
+    K: DeserializeOwned,
+    Lr::Repr: DeserializeOwned,
+{
+    type Ord = MerkleSyncOrder;
+
+    fn poll_delta(&self, ctx: &mut Context<'_>) -> Poll<Option<Hide<Delta, Self::LatRepr>>> {
+        let tcp_server = self.sync.tcp_server();
+        while let Poll::Ready(result) = tcp_server.poll_accept(ctx) {
+            if let Err(err) = result {
+                report(err);
+                break;
+            }
+        }
+
+        while let Poll::Ready(Some((addr, bytes_mut))) = tcp_server.poll_read(ctx) {
+            if let Some(entries) = self.recv(MerkleRoute::Server(addr), &*bytes_mut) {
+                return Poll::Ready(Some(Hide::new(entries)));
+            }
+        }
+
+        for (i, peer) in self.sync.peers().iter().enumerate() {
+            while let Poll::Ready(bytes_mut) = peer.poll_read(ctx) {
+                if let Some(entries) = self.recv(MerkleRoute::Peer(i), &*bytes_mut) {
+                    return Poll::Ready(Some(Hide::new(entries)));
+                }
+            }
+        }
+
+        Poll::Pending
+    }
+}
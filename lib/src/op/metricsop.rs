@@ -0,0 +1,65 @@
+use std::cell::Cell;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use crate::hide::{Hide, Delta, Value};
+use crate::metrics::{Counter, Histogram, Metrics};
+
+use super::*;
+
+/// Counts the deltas passing through, as `<name>_deltas_total`, and records how
+/// long upstream took to produce each one, as `<name>_poll_seconds`. This is the
+/// time from the first poll for a delta, including any `Pending` polls, until
+/// it is ready.
+pub struct MetricsOp<O: Op> {
+    op: O,
+    deltas: Counter,
+    poll_seconds: Histogram,
+    waiting_since: Cell<Option<Instant>>,
+}
+
+impl<O: Op> MetricsOp<O> {
+    pub fn new(op: O, metrics: &Metrics, name: &str) -> Self {
+        let deltas = Counter::default();
+        let poll_seconds = Histogram::default();
+        metrics.register(format!("{}_deltas_total", name), deltas.clone());
+        metrics.register(format!("{}_poll_seconds", name), poll_seconds.clone());
+        Self { op, deltas, poll_seconds, waiting_since: Cell::new(None) }
+    }
+}
+
+impl<O: Op> Op for MetricsOp<O> {
+    type LatRepr = O::LatRepr;
+
+    fn propegate_saturation(&self) {
+        self.op.propegate_saturation()
+    }
+}
+
+impl<O: OpDelta> OpDelta for MetricsOp<O> {
+    type Ord = O::Ord;
+
+    fn poll_delta(&self, ctx: &mut Context<'_>) -> Poll<Option<Hide<Delta, Self::LatRepr>>> {
+        let start = self.waiting_since.take().unwrap_or_else(Instant::now);
+        let polled = self.op.poll_delta(ctx);
+        match &polled {
+            Poll::Ready(Some(_)) => {
+                self.deltas.inc();
+                self.poll_seconds.observe_duration(start.elapsed());
+            }
+            Poll::Ready(None) => {}
+            Poll::Pending => self.waiting_since.set(Some(start)),
+        }
+        polled
+    }
+}
+
+impl<O: OpValue> OpValue for MetricsOp<O> {
+    fn get_value(&self) -> Hide<Value, Self::LatRepr> {
+        self.op.get_value()
+    }
+
+    fn with_value<R>(&self, f: impl FnOnce(&Hide<Value, Self::LatRepr>) -> R) -> R {
+        self.op.with_value(f)
+    }
+}
@@ -23,6 +23,12 @@ pub use iterop::*;
 mod debugop;
 pub use debugop::*;
 
+mod metricsop;
+pub use metricsop::*;
+
+mod traceop;
+pub use traceop::*;
+
 mod debottomop;
 pub use debottomop::*;
 
@@ -35,6 +41,9 @@ pub use latticeop::*;
 mod morphop;
 pub use morphop::*;
 
+mod monotoneop;
+pub use monotoneop::*;
+
 mod splitop;
 pub use splitop::*;
 
@@ -50,9 +59,18 @@ pub use binaryop::*;
 mod readop;
 pub use readop::*;
 
+mod fileop;
+pub use fileop::*;
+
 mod zipop;
 pub use zipop::*;
 
+mod rangequeryop;
+pub use rangequeryop::*;
+
+mod lookupop;
+pub use lookupop::*;
+
 mod channelop;
 pub use channelop::*;
 
@@ -62,6 +80,34 @@ pub use tcpop::*;
 mod tcpserverop;
 pub use tcpserverop::*;
 
+mod tcpclientop;
+pub use tcpclientop::*;
+
+mod simop;
+pub use simop::*;
+
+mod simserverop;
+pub use simserverop::*;
+
+#[cfg(unix)]
+mod unixop;
+#[cfg(unix)]
+pub use unixop::*;
+
+#[cfg(unix)]
+mod unixserverop;
+#[cfg(unix)]
+pub use unixserverop::*;
+
+mod udpop;
+pub use udpop::*;
+
+mod gossipop;
+pub use gossipop::*;
+
+mod merklesyncop;
+pub use merklesyncop::*;
+
 mod batchconvertop;
 pub use batchconvertop::*;
 
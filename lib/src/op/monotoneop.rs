@@ -0,0 +1,92 @@
+use std::cell::RefCell;
+use std::task::{Context, Poll};
+
+use crate::hide::{Hide, Delta, Value};
+use crate::func::unary::Monotone;
+use crate::lattice::{LatticeRepr, Merge};
+use crate::metadata::Order;
+
+use super::*;
+
+/// Applies a monotone function, which must see the full value rather than
+/// just deltas. Accumulates the input and, whenever it grows, emits the
+/// function of the whole accumulated value.
+pub struct MonotoneOp<O: Op, F: Monotone>
+where
+    F::InLatRepr: Merge<O::LatRepr>,
+{
+    op: O,
+    func: F,
+    state: RefCell<Hide<Value, F::InLatRepr>>,
+}
+
+impl<O: Op, F: Monotone> MonotoneOp<O, F>
+where
+    F::InLatRepr: Merge<O::LatRepr>,
+{
+    pub fn new(op: O, func: F, bottom: <F::InLatRepr as LatticeRepr>::Repr) -> Self {
+        Self {
+            op,
+            func,
+            state: RefCell::new(Hide::new(bottom)),
+        }
+    }
+}
+
+impl<O: Op, F: Monotone> MonotoneOp<O, F>
+where
+    F::InLatRepr: Merge<O::LatRepr>,
+    <F::InLatRepr as LatticeRepr>::Repr: Default,
+{
+    pub fn new_default(op: O, func: F) -> Self {
+        Self::new(op, func, Default::default())
+    }
+}
+
+impl<O: Op, F: Monotone> Op for MonotoneOp<O, F>
+where
+    F::InLatRepr: Merge<O::LatRepr>,
+{
+    type LatRepr = F::OutLatRepr;
+
+    fn propegate_saturation(&self) {
+        self.op.propegate_saturation()
+    }
+}
+
+pub struct MonotoneOrder<O: Order, F>(std::marker::PhantomData<(O, F)>);
+impl<O: Order, F> Order for MonotoneOrder<O, F> {}
+
+impl<O: OpDelta, F: Monotone> OpDelta for MonotoneOp<O, F>
+where
+    F::InLatRepr: Merge<O::LatRepr>,
+{
+    type Ord = MonotoneOrder<O::Ord, F>;
+
+    fn poll_delta(&self, ctx: &mut Context<'_>) -> Poll<Option<Hide<Delta, Self::LatRepr>>> {
+        traced("MonotoneOp", || {
+            loop {
+                match self.op.poll_delta(ctx) {
+                    Poll::Ready(Some(delta)) => {
+                        let mut state = self.state.borrow_mut();
+                        if <F::InLatRepr as Merge<O::LatRepr>>::merge_hide(&mut state, delta) {
+                            return Poll::Ready(Some(self.func.call(state.clone()).into_delta()));
+                        }
+                        // Else: Delta did not change the input, so neither does the output.
+                    }
+                    Poll::Ready(None) => return Poll::Ready(None),
+                    Poll::Pending => return Poll::Pending,
+                }
+            }
+        })
+    }
+}
+
+impl<O: Op, F: Monotone> OpValue for MonotoneOp<O, F>
+where
+    F::InLatRepr: Merge<O::LatRepr>,
+{
+    fn get_value(&self) -> Hide<Value, Self::LatRepr> {
+        self.func.call(self.state.borrow().clone())
+    }
+}
@@ -29,11 +29,13 @@ impl<O: OpDelta, F: Morphism<InLatRepr = O::LatRepr>> OpDelta for MorphismOp<O,
     type Ord = MorphismOrder<O::Ord, F>;
 
     fn poll_delta(&self, ctx: &mut Context<'_>) -> Poll<Option<Hide<Delta, Self::LatRepr>>> {
-        match self.op.poll_delta(ctx) {
-            Poll::Ready(Some(delta)) => Poll::Ready(Some(self.func.call(delta))),
-            Poll::Ready(None) => Poll::Ready(None),
-            Poll::Pending => Poll::Pending,
-        }
+        traced("MorphismOp", || {
+            match self.op.poll_delta(ctx) {
+                Poll::Ready(Some(delta)) => Poll::Ready(Some(self.func.call(delta))),
+                Poll::Ready(None) => Poll::Ready(None),
+                Poll::Pending => Poll::Pending,
+            }
+        })
     }
 }
 
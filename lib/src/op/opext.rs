@@ -1,17 +1,34 @@
 use std::any::Any;
+use std::hash::Hash;
 use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
 
 use serde::ser::Serialize;
+use tokio::fs::File;
+use tokio::net::UdpSocket;
 use tokio::net::tcp::OwnedWriteHalf;
 
-use crate::comp::{DebugComp, NullComp, TcpComp, TcpServerComp};
-use crate::func::unary::{Morphism, ClosureMorphism};
+use crate::collections::Collection;
+use crate::comp::{DebugComp, FileComp, GossipComp, MerkleSyncComp, NullComp, SimComp, SimServerComp, TcpBroadcastComp, TcpClientComp, TcpClientResendComp, TcpComp, TcpMulticastComp, TcpServerComp, UdpComp};
+#[cfg(unix)]
+use crate::comp::{UnixComp, UnixServerComp};
+use crate::format::FileEncode;
+use crate::func::unary::{Monotone, Morphism, ClosureMonotone, ClosureMorphism};
 use crate::func::binary::BinaryMorphism;
-use crate::lattice::{Convert, Debottom, LatticeRepr, Merge, Top};
+use crate::lattice::{Convert, Debottom, LatticeRepr, Merge, Split, Top};
 use crate::lattice::map_union::{MapTag, MapUnionRepr};
 use crate::lattice::pair::PairRepr;
+use crate::lattice::set_union::{SetTag, SetUnion, SetUnionRepr};
+use crate::merkle::MerkleSync;
+use crate::metrics::Metrics;
+use crate::sim_net::SimServer;
+use crate::tcp_client::TcpClient;
 use crate::tcp_server::TcpServer;
-use crate::hide::{Hide, Delta};
+#[cfg(unix)]
+use crate::unix_server::{UnixPeer, UnixServer};
+use crate::hide::{Hide, Delta, Value};
+use crate::tag;
 
 use super::*;
 
@@ -25,6 +42,14 @@ pub trait OpExt: Sized + Op {
         DebugOp::new(self, tag)
     }
 
+    fn metrics(self, metrics: &Metrics, name: &str) -> MetricsOp<Self> {
+        MetricsOp::new(self, metrics, name)
+    }
+
+    fn trace(self, name: &'static str) -> TraceOp<Self> {
+        TraceOp::new(self, name)
+    }
+
     fn debottom(self) -> DebottomOp<Self>
     where
         Self::LatRepr: Debottom,
@@ -43,6 +68,22 @@ pub trait OpExt: Sized + Op {
         MorphismOp::new(self, ClosureMorphism::new(func))
     }
 
+    fn monotone<F: Monotone>(self, func: F) -> MonotoneOp<Self, F>
+    where
+        F::InLatRepr: Merge<Self::LatRepr>,
+        <F::InLatRepr as LatticeRepr>::Repr: Default,
+    {
+        MonotoneOp::new_default(self, func)
+    }
+
+    fn monotone_closure<In: LatticeRepr + Merge<Self::LatRepr>, Out: LatticeRepr, F>(self, func: F) -> MonotoneOp<Self, ClosureMonotone<In, Out, F>>
+    where
+        F: Fn(Hide<Value, In>) -> Hide<Value, Out>,
+        In::Repr: Default,
+    {
+        MonotoneOp::new_default(self, ClosureMonotone::new(func))
+    }
+
     fn topbox(self) -> TopOp<Self>
     where
         Self::LatRepr: Top,
@@ -65,6 +106,28 @@ pub trait OpExt: Sized + Op {
         BinaryOp::new(self, op, func)
     }
 
+    fn range_query<S: OpDelta + OpValue, I: Clone, K: Clone + Ord, Lr: LatticeRepr>(self, state: S) -> RangeQueryOp<Self, S, I, K, Lr>
+    where
+        Self: OpDelta,
+        Self::LatRepr: LatticeRepr<Lattice = SetUnion<(I, KeyRange<K>)>>,
+        <Self::LatRepr as LatticeRepr>::Repr: IntoIterator<Item = (I, KeyRange<K>)>,
+        S: Op<LatRepr = MapUnionRepr<tag::BTREE_MAP, K, Lr>>,
+    {
+        RangeQueryOp::new(self, state)
+    }
+
+    fn lookup<S: OpDelta + OpValue, I: Clone, K: Clone, Tag, Lr: LatticeRepr>(self, state: S) -> LookupOp<Self, S, I, K, Tag, Lr>
+    where
+        Self: OpDelta,
+        Self::LatRepr: LatticeRepr<Lattice = SetUnion<(I, K)>>,
+        <Self::LatRepr as LatticeRepr>::Repr: IntoIterator<Item = (I, K)>,
+        Tag: MapTag<K, Lr::Repr>,
+        MapUnionRepr<Tag, K, Lr>: LatticeRepr,
+        S: Op<LatRepr = MapUnionRepr<Tag, K, Lr>>,
+    {
+        LookupOp::new(self, state)
+    }
+
     fn lattice_default<Lr: LatticeRepr + Merge<Self::LatRepr>>(self) -> LatticeOp<Self, Lr>
     where
         Self::LatRepr: Convert<Lr>,
@@ -99,6 +162,13 @@ pub trait OpExt: Sized + Op {
         DebugComp::new(self, tag)
     }
 
+    fn comp_file<F: FileEncode<Self::LatRepr>>(self, file: File) -> FileComp<Self, F>
+    where
+        Self: OpDelta,
+    {
+        FileComp::new(self, file)
+    }
+
     fn comp_null(self) -> NullComp<Self>
     where
         Self: OpDelta,
@@ -114,6 +184,43 @@ pub trait OpExt: Sized + Op {
         TcpComp::new(self, tcp_write)
     }
 
+    fn comp_tcp_client<Lr: Any + LatticeRepr>(self, tcp_client: TcpClient) -> TcpClientComp<Self>
+    where
+        Self: OpDelta<LatRepr = Lr>,
+        Lr::Repr: Serialize,
+    {
+        TcpClientComp::new(self, tcp_client)
+    }
+
+    fn comp_tcp_client_resend<Lr: Any + Split>(self, tcp_client: TcpClient) -> TcpClientResendComp<Self>
+    where
+        Self: OpDelta<LatRepr = Lr> + OpValue,
+        Lr::Repr: Serialize,
+    {
+        TcpClientResendComp::new(self, tcp_client)
+    }
+
+    fn comp_gossip(self, peers: Vec<TcpClient>, period: Duration, fanout: usize) -> GossipComp<Self>
+    where
+        Self: OpDelta + OpValue,
+        Self::LatRepr: Any + Split,
+        <Self::LatRepr as LatticeRepr>::Repr: Serialize,
+    {
+        GossipComp::new(self, peers, period, fanout)
+    }
+
+    fn comp_merkle_sync<Tag, K, Lr: LatticeRepr>(self, sync: MerkleSync<K, Lr>, period: Duration) -> MerkleSyncComp<Self, Tag, K, Lr>
+    where
+        Self: OpDelta<LatRepr = MapUnionRepr<Tag, K, Lr>> + OpValue,
+        Tag: MapTag<K, Lr::Repr>,
+        MapUnionRepr<Tag, K, Lr>: LatticeRepr,
+        <Self::LatRepr as LatticeRepr>::Repr: Collection<K, Lr::Repr>,
+        K: Clone + Eq + Hash + Serialize,
+        Lr::Repr: Hash + Serialize,
+    {
+        MerkleSyncComp::new(self, sync, period)
+    }
+
     fn comp_tcp_server<Lr: Any + LatticeRepr, Tag>(self, tcp_server: TcpServer) -> TcpServerComp<Self, Tag, Lr>
     where
         Tag: MapTag<SocketAddr, Lr::Repr>,
@@ -124,4 +231,71 @@ pub trait OpExt: Sized + Op {
     {
         TcpServerComp::new(self, tcp_server)
     }
+
+    fn comp_sim<Lr: Any + LatticeRepr>(self, sim_server: SimServer, peers: Vec<SocketAddr>) -> SimComp<Self>
+    where
+        Self: OpDelta<LatRepr = Lr>,
+        Lr::Repr: Serialize,
+    {
+        SimComp::new(self, sim_server, peers)
+    }
+
+    fn comp_sim_server<Lr: Any + LatticeRepr, Tag>(self, sim_server: SimServer) -> SimServerComp<Self, Tag, Lr>
+    where
+        Tag: MapTag<SocketAddr, Lr::Repr>,
+        MapUnionRepr<Tag, SocketAddr, Lr>: LatticeRepr,
+        Self: OpDelta<LatRepr = MapUnionRepr<Tag, SocketAddr, Lr>>,
+        <Self::LatRepr as LatticeRepr>::Repr: IntoIterator<Item = (SocketAddr, Lr::Repr)>,
+        Lr::Repr: Serialize,
+    {
+        SimServerComp::new(self, sim_server)
+    }
+
+    fn comp_tcp_broadcast<Lr: Any + LatticeRepr>(self, tcp_server: TcpServer) -> TcpBroadcastComp<Self>
+    where
+        Self: OpDelta<LatRepr = Lr>,
+        Lr::Repr: Serialize,
+    {
+        TcpBroadcastComp::new(self, tcp_server)
+    }
+
+    fn comp_tcp_multicast<Lr: Any + LatticeRepr, Tag>(self, tcp_server: TcpServer) -> TcpMulticastComp<Self, Tag, Lr>
+    where
+        Tag: SetTag<SocketAddr>,
+        SetUnionRepr<Tag, SocketAddr>: LatticeRepr,
+        <SetUnionRepr<Tag, SocketAddr> as LatticeRepr>::Repr: IntoIterator<Item = SocketAddr>,
+        Self: OpDelta<LatRepr = PairRepr<SetUnionRepr<Tag, SocketAddr>, Lr>>,
+        Lr::Repr: Serialize,
+    {
+        TcpMulticastComp::new(self, tcp_server)
+    }
+
+    #[cfg(unix)]
+    fn comp_unix<Lr: Any + LatticeRepr>(self, unix_write: tokio::net::unix::OwnedWriteHalf) -> UnixComp<Self>
+    where
+        Self: OpDelta<LatRepr = Lr>,
+        Lr::Repr: Serialize,
+    {
+        UnixComp::new(self, unix_write)
+    }
+
+    #[cfg(unix)]
+    fn comp_unix_server<Lr: Any + LatticeRepr, Tag>(self, unix_server: UnixServer) -> UnixServerComp<Self, Tag, Lr>
+    where
+        Tag: MapTag<UnixPeer, Lr::Repr>,
+        MapUnionRepr<Tag, UnixPeer, Lr>: LatticeRepr,
+        Self: OpDelta<LatRepr = MapUnionRepr<Tag, UnixPeer, Lr>>,
+        <Self::LatRepr as LatticeRepr>::Repr: IntoIterator<Item = (UnixPeer, Lr::Repr)>,
+        Lr::Repr: Serialize,
+    {
+        UnixServerComp::new(self, unix_server)
+    }
+
+    fn comp_udp<Lr: Any + LatticeRepr + Split>(self, socket: Arc<UdpSocket>, peers: Vec<SocketAddr>) -> UdpComp<Self>
+    where
+        Self: OpDelta<LatRepr = Lr>,
+        Lr::Repr: Serialize,
+    {
+        UdpComp::new(self, socket, peers)
+    }
 }
@@ -16,9 +16,16 @@ pub trait OpDelta: Op {
     /// Ordering metadata.
     type Ord: Order;
 
+    /// Errors are passed to the polling comp with `error::report`.
     fn poll_delta(&self, ctx: &mut Context<'_>) -> Poll<Option<Hide<Delta, Self::LatRepr>>>;
 }
 
 pub trait OpValue: Op {
     fn get_value(&self) -> Hide<Value, Self::LatRepr>;
+
+    /// Call F with a reference to the current value. Ops which hold their
+    /// value, like `LatticeOp`, override this to avoid cloning it.
+    fn with_value<R>(&self, f: impl FnOnce(&Hide<Value, Self::LatRepr>) -> R) -> R {
+        f(&self.get_value())
+    }
 }
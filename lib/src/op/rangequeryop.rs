@@ -0,0 +1,150 @@
+use std::ops::{Bound, RangeBounds};
+use std::task::{Context, Poll};
+
+use serde::{Deserialize, Serialize};
+
+use crate::hide::{Hide, Delta};
+use crate::lattice::LatticeRepr;
+use crate::lattice::map_union::MapUnionRepr;
+use crate::lattice::set_union::SetUnion;
+use crate::metadata::Order;
+use crate::tag;
+
+use super::*;
+
+/// A range of keys to scan, see `RangeQueryOp`.
+#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeyRange<K> {
+    pub start: Bound<K>,
+    pub end: Bound<K>,
+}
+
+impl<K: Clone> KeyRange<K> {
+    pub fn new(range: impl RangeBounds<K>) -> Self {
+        fn cloned<K: Clone>(bound: Bound<&K>) -> Bound<K> {
+            match bound {
+                Bound::Included(k) => Bound::Included(k.clone()),
+                Bound::Excluded(k) => Bound::Excluded(k.clone()),
+                Bound::Unbounded => Bound::Unbounded,
+            }
+        }
+        Self {
+            start: cloned(range.start_bound()),
+            end: cloned(range.end_bound()),
+        }
+    }
+}
+
+impl KeyRange<String> {
+    /// All strings starting with PREFIX.
+    pub fn prefix(prefix: &str) -> Self {
+        // Strings starting with PREFIX sort before PREFIX with its last
+        // incrementable char incremented.
+        let mut end: Vec<char> = prefix.chars().collect();
+        let end = loop {
+            match end.pop() {
+                Some(c) => {
+                    let next = (c as u32 + 1..=char::MAX as u32).find_map(std::char::from_u32);
+                    if let Some(next) = next {
+                        end.push(next);
+                        break Bound::Excluded(end.into_iter().collect());
+                    }
+                }
+                None => break Bound::Unbounded,
+            }
+        };
+        Self {
+            start: Bound::Included(prefix.to_owned()),
+            end,
+        }
+    }
+}
+
+impl<K> RangeBounds<K> for KeyRange<K> {
+    fn start_bound(&self) -> Bound<&K> {
+        as_ref(&self.start)
+    }
+    fn end_bound(&self) -> Bound<&K> {
+        as_ref(&self.end)
+    }
+}
+
+fn as_ref<K>(bound: &Bound<K>) -> Bound<&K> {
+    match bound {
+        Bound::Included(k) => Bound::Included(k),
+        Bound::Excluded(k) => Bound::Excluded(k),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+/// Answers range scans against a `BTREE_MAP` state, such as a `LatticeOp`,
+/// borrowing the state with `OpValue::with_value` rather than cloning it.
+/// Each `(id, range)` query is answered once, with the entries in range at the
+/// time, keyed by the query's ID.
+pub struct RangeQueryOp<Q: OpDelta, S: OpDelta + OpValue, I: Clone, K: Clone, Lr: LatticeRepr>
+where
+    Q::LatRepr: LatticeRepr<Lattice = SetUnion<(I, KeyRange<K>)>>,
+    <Q::LatRepr as LatticeRepr>::Repr: IntoIterator<Item = (I, KeyRange<K>)>,
+    S: Op<LatRepr = MapUnionRepr<tag::BTREE_MAP, K, Lr>>,
+{
+    queries: Q,
+    state: S,
+}
+
+impl<Q: OpDelta, S: OpDelta + OpValue, I: Clone, K: Clone, Lr: LatticeRepr> RangeQueryOp<Q, S, I, K, Lr>
+where
+    Q::LatRepr: LatticeRepr<Lattice = SetUnion<(I, KeyRange<K>)>>,
+    <Q::LatRepr as LatticeRepr>::Repr: IntoIterator<Item = (I, KeyRange<K>)>,
+    S: Op<LatRepr = MapUnionRepr<tag::BTREE_MAP, K, Lr>>,
+{
+    pub fn new(queries: Q, state: S) -> Self {
+        Self { queries, state }
+    }
+}
+
+impl<Q: OpDelta, S: OpDelta + OpValue, I: Clone, K: Clone, Lr: LatticeRepr> Op for RangeQueryOp<Q, S, I, K, Lr>
+where
+    Q::LatRepr: LatticeRepr<Lattice = SetUnion<(I, KeyRange<K>)>>,
+    <Q::LatRepr as LatticeRepr>::Repr: IntoIterator<Item = (I, KeyRange<K>)>,
+    S: Op<LatRepr = MapUnionRepr<tag::BTREE_MAP, K, Lr>>,
+{
+    type LatRepr = MapUnionRepr<tag::VEC, I, MapUnionRepr<tag::VEC, K, Lr>>;
+
+    fn propegate_saturation(&self) {
+        self.queries.propegate_saturation();
+        self.state.propegate_saturation()
+    }
+}
+
+pub struct RangeQueryOrder<O: Order>(std::marker::PhantomData<O>);
+impl<O: Order> Order for RangeQueryOrder<O> {}
+
+impl<Q: OpDelta, S: OpDelta + OpValue, I: Clone, K: Clone + Ord, Lr: LatticeRepr> OpDelta for RangeQueryOp<Q, S, I, K, Lr>
+where
+    Q::LatRepr: LatticeRepr<Lattice = SetUnion<(I, KeyRange<K>)>>,
+    <Q::LatRepr as LatticeRepr>::Repr: IntoIterator<Item = (I, KeyRange<K>)>,
+    S: Op<LatRepr = MapUnionRepr<tag::BTREE_MAP, K, Lr>>,
+{
+    type Ord = RangeQueryOrder<Q::Ord>;
+
+    fn poll_delta(&self, ctx: &mut Context<'_>) -> Poll<Option<Hide<Delta, Self::LatRepr>>> {
+        traced("RangeQueryOp", || {
+            // Bring the state up to date first, so queries see all ready deltas.
+            while let Poll::Ready(Some(_)) = self.state.poll_delta(ctx) {}
+
+            match self.queries.poll_delta(ctx) {
+                Poll::Ready(Some(queries)) => {
+                    let out = self.state.with_value(|state| {
+                        queries.into_reveal().into_iter()
+                            .map(|(id, range)| (id, state.range(range).into_reveal()))
+                            .collect()
+                    });
+                    Poll::Ready(Some(Hide::new(out)))
+                }
+                Poll::Ready(None) => Poll::Ready(None),
+                Poll::Pending => Poll::Pending,
+            }
+        })
+    }
+}
@@ -6,6 +6,7 @@ use std::task::{Context, Poll};
 use tokio::io::{AsyncRead, Stdin, BufReader, Lines, AsyncBufReadExt};
 
 use crate::collections::Single;
+use crate::error::report;
 use crate::hide::{Hide, Delta};
 use crate::tag::{SINGLE};
 use crate::lattice::set_union::{SetUnionRepr};
@@ -55,7 +56,7 @@ impl<R: AsyncRead + Unpin> OpDelta for ReadOp<R> {
             match Pin::new(&mut *self.reader.borrow_mut()).as_mut().poll_next_line(ctx) {
                 Poll::Pending => return Poll::Pending,
                 Poll::Ready(Result::Ok(opt)) => return Poll::Ready(opt.map(|x| Hide::new(Single(x)))),
-                Poll::Ready(Result::Err(err)) => println!("ERROR: {}", err),
+                Poll::Ready(Result::Err(err)) => report(err),
             }
         }
     }
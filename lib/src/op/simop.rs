@@ -0,0 +1,69 @@
+use std::any::Any;
+use std::task::{Context, Poll};
+
+use serde::de::DeserializeOwned;
+
+use crate::error::report;
+use crate::hide::{Hide, Delta};
+use crate::lattice::LatticeRepr;
+use crate::metadata::Order;
+use crate::sim_net::SimServer;
+use crate::tcp_server::serde::deserialize;
+
+use super::optrait::*;
+
+/// Receives deltas sent to a `SimServer` from any node, like a `TcpOp`.
+pub struct SimOp<Lr: Any + LatticeRepr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    sim_server: SimServer,
+    _phantom: std::marker::PhantomData<Lr>,
+}
+
+impl<Lr: Any + LatticeRepr> SimOp<Lr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    pub fn new(sim_server: SimServer) -> Self {
+        Self {
+            sim_server,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<Lr: Any + LatticeRepr> Op for SimOp<Lr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    type LatRepr = Lr;
+
+    fn propegate_saturation(&self) {
+    }
+}
+
+pub enum SimOrder {}
+impl Order for SimOrder {}
+
+impl<Lr: Any + LatticeRepr> OpDelta for SimOp<Lr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    type Ord = SimOrder;
+
+    fn poll_delta(&self, ctx: &mut Context<'_>) -> Poll<Option<Hide<Delta, Self::LatRepr>>> {
+        loop {
+            match self.sim_server.poll_read(ctx) {
+                Poll::Ready(Some((_addr, bytes_mut))) => {
+                    match deserialize::<Lr>(&*bytes_mut) {
+                        Ok(repr) => return Poll::Ready(Some(Hide::new(repr))),
+                        Err(err) => report(err),
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
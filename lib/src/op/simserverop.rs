@@ -0,0 +1,73 @@
+use std::any::Any;
+use std::net::SocketAddr;
+use std::task::{Context, Poll};
+
+use serde::de::DeserializeOwned;
+
+use crate::collections::{Single};
+use crate::error::report;
+use crate::hide::{Hide, Delta};
+use crate::lattice::LatticeRepr;
+use crate::lattice::map_union::{MapUnionRepr};
+use crate::metadata::Order;
+use crate::sim_net::SimServer;
+use crate::tag;
+use crate::tcp_server::serde::deserialize;
+
+use super::optrait::*;
+
+/// Receives deltas keyed by the sending node's address, like a `TcpServerOp`.
+pub struct SimServerOp<Lr: Any + LatticeRepr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    sim_server: SimServer,
+    _phantom: std::marker::PhantomData<Lr>,
+}
+
+impl<Lr: Any + LatticeRepr> SimServerOp<Lr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    pub fn new(sim_server: SimServer) -> Self {
+        Self {
+            sim_server,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<Lr: Any + LatticeRepr> Op for SimServerOp<Lr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    type LatRepr = MapUnionRepr<tag::SINGLE, SocketAddr, Lr>;
+
+    fn propegate_saturation(&self) {
+    }
+}
+
+pub enum SimServerOrder {}
+impl Order for SimServerOrder {}
+
+impl<Lr: Any + LatticeRepr> OpDelta for SimServerOp<Lr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    type Ord = SimServerOrder;
+
+    fn poll_delta(&self, ctx: &mut Context<'_>) -> Poll<Option<Hide<Delta, Self::LatRepr>>> {
+        loop {
+            match self.sim_server.poll_read(ctx) {
+                Poll::Ready(Some((addr, bytes_mut))) => {
+                    match deserialize::<Lr>(&*bytes_mut) {
+                        Ok(repr) => return Poll::Ready(Some(Hide::new(Single((addr, repr))))),
+                        Err(err) => report(err),
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
@@ -3,6 +3,7 @@ use std::task::{Context, Poll, Waker};
 use std::rc::{Rc, Weak};
 
 use crate::hide::{Hide, Delta, Value};
+use crate::metrics::{Gauge, Metrics};
 
 use super::*;
 
@@ -17,6 +18,7 @@ struct SplitterState<O: Op> {
     op: O,
     closed: Cell<bool>,
     splits: RefCell<Vec<Weak<RefCell<SplitOpState<O>>>>>,
+    buffered: Gauge,
 }
 
 pub struct Splitter<O: Op> {
@@ -29,10 +31,17 @@ impl<O: Op> Splitter<O> {
             op,
             closed: Cell::new(false),
             splits: Default::default(),
+            buffered: Default::default(),
         });
         Self { state }
     }
 
+    /// Track the number of deltas waiting in splits' buffers for their split
+    /// to be polled, as `<name>_split_buffered`.
+    pub fn register_metrics(&self, metrics: &Metrics, name: &str) {
+        metrics.register(format!("{}_split_buffered", name), self.state.buffered.clone());
+    }
+
     #[must_use]
     fn internal_add_split_reveal(&self) -> SplitOp<O> {
         let mut splits = self.state.splits.borrow_mut();
@@ -69,11 +78,27 @@ pub struct SplitOp<O: Op> {
     split: RefCell<Option<Rc<RefCell<SplitOpState<O>>>>>,
 }
 
+impl<O: Op> SplitOp<O> {
+    fn remove_split(&self) {
+        if let Some(split) = self.split.borrow_mut().take() {
+            if split.borrow().delta.is_some() {
+                self.splitter.buffered.add(-1);
+            }
+        }
+    }
+}
+
+impl<O: Op> Drop for SplitOp<O> {
+    fn drop(&mut self) {
+        self.remove_split();
+    }
+}
+
 impl<O: Op> Op for SplitOp<O> {
     type LatRepr = O::LatRepr;
 
     fn propegate_saturation(&self) {
-        self.split.borrow_mut().take();
+        self.remove_split();
         // TODO: somehow propegate when all splits are removed? Depending on if dynamic or not.
     }
 }
@@ -82,87 +107,91 @@ impl<O: OpDelta> OpDelta for SplitOp<O> {
     type Ord = O::Ord;
 
     fn poll_delta(&self, ctx: &mut Context<'_>) -> Poll<Option<Hide<Delta, Self::LatRepr>>> {
-        if self.splitter.closed.get() {
-            return Poll::Ready(None);
-        }
-
-        let split = self.split.borrow();
-        match &*split {
-            None => Poll::Ready(None),
-            Some(split_rc) => {
-                let mut split = split_rc.borrow_mut();
+        traced("SplitOp", || {
+            if self.splitter.closed.get() {
+                return Poll::Ready(None);
+            }
 
-                // Check if we have a value waiting.
-                match split.delta.take() {
-                    Some(polled) => {
-                        return Poll::Ready(Some(polled));
-                    }
-                    None => {
-                        split.waker.replace(ctx.waker().clone());
+            let split = self.split.borrow();
+            match &*split {
+                None => Poll::Ready(None),
+                Some(split_rc) => {
+                    let mut split = split_rc.borrow_mut();
+
+                    // Check if we have a value waiting.
+                    match split.delta.take() {
+                        Some(polled) => {
+                            self.splitter.buffered.add(-1);
+                            return Poll::Ready(Some(polled));
+                        }
+                        None => {
+                            split.waker.replace(ctx.waker().clone());
+                        }
                     }
-                }
 
-                // Remove any weak (removed) splits.
-                let mut splits = Vec::new();
-                {
-                    self.splitter.splits.borrow_mut().retain(|split_weak| {
-                        match split_weak.upgrade() {
-                            Some(split) => {
-                                splits.push(split);
-                                true
+                    // Remove any weak (removed) splits.
+                    let mut splits = Vec::new();
+                    {
+                        self.splitter.splits.borrow_mut().retain(|split_weak| {
+                            match split_weak.upgrade() {
+                                Some(split) => {
+                                    splits.push(split);
+                                    true
+                                }
+                                None => false
                             }
-                            None => false
-                        }
-                    });
-                }
-                // Get our index.
-                let index = splits.iter().enumerate().find_map(|(i, split_other)| {
-                    if Rc::ptr_eq(&split_rc, split_other) {
-                        Some(i)
+                        });
                     }
-                    else {
-                        None
-                    }
-                }).expect("WE DONT EXIST :C");
-
-                // Iterate in circular order, so each successive split checks the next split.
-                let (splits_before, splits_after) = splits.split_at_mut(index);
-                let splits_after = &mut splits_after[1..]; // Skip self.
-
-                // Check if other splits are ready to receive a value.
-                for split in splits_after.iter().chain(splits_before.iter()) {
-                    let split = split.borrow();
-                    if let Some(_) = split.delta {
-                        // If any split has it's value filled, wake it up and return pending.
-                        if let Some(waker) = &split.waker {
-                            waker.wake_by_ref();
+                    // Get our index.
+                    let index = splits.iter().enumerate().find_map(|(i, split_other)| {
+                        if Rc::ptr_eq(&split_rc, split_other) {
+                            Some(i)
+                        }
+                        else {
+                            None
+                        }
+                    }).expect("WE DONT EXIST :C");
+
+                    // Iterate in circular order, so each successive split checks the next split.
+                    let (splits_before, splits_after) = splits.split_at_mut(index);
+                    let splits_after = &mut splits_after[1..]; // Skip self.
+
+                    // Check if other splits are ready to receive a value.
+                    for split in splits_after.iter().chain(splits_before.iter()) {
+                        let split = split.borrow();
+                        if let Some(_) = split.delta {
+                            // If any split has it's value filled, wake it up and return pending.
+                            if let Some(waker) = &split.waker {
+                                waker.wake_by_ref();
+                            }
+                            return Poll::Pending;
                         }
-                        return Poll::Pending;
                     }
-                }
-
-                // Poll upstream.
-                match self.splitter.op.poll_delta(ctx) {
-                    Poll::Ready(Some(delta)) => {
-                        for split in splits_after.iter_mut().chain(splits_before.iter_mut()) {
-                            let mut split = split.borrow_mut();
-                            let old_delta_opt = split.delta.replace(delta.clone());
-                            assert!(old_delta_opt.is_none());
 
-                            if let Some(waker) = split.waker.take() {
-                                waker.wake();
+                    // Poll upstream.
+                    match self.splitter.op.poll_delta(ctx) {
+                        Poll::Ready(Some(delta)) => {
+                            for split in splits_after.iter_mut().chain(splits_before.iter_mut()) {
+                                let mut split = split.borrow_mut();
+                                let old_delta_opt = split.delta.replace(delta.clone());
+                                assert!(old_delta_opt.is_none());
+
+                                if let Some(waker) = split.waker.take() {
+                                    waker.wake();
+                                }
                             }
+                            self.splitter.buffered.add((splits.len() - 1) as i64);
+                            Poll::Ready(Some(delta))
                         }
-                        Poll::Ready(Some(delta))
-                    }
-                    Poll::Ready(None) => {
-                        self.splitter.closed.replace(true);
-                        Poll::Ready(None)
+                        Poll::Ready(None) => {
+                            self.splitter.closed.replace(true);
+                            Poll::Ready(None)
+                        }
+                        Poll::Pending => Poll::Pending,
                     }
-                    Poll::Pending => Poll::Pending,
                 }
             }
-        }
+        })
     }
 }
 
@@ -170,6 +199,10 @@ impl<O: OpValue> OpValue for SplitOp<O> {
     fn get_value(&self) -> Hide<Value, Self::LatRepr> {
         self.splitter.op.get_value()
     }
+
+    fn with_value<R>(&self, f: impl FnOnce(&Hide<Value, Self::LatRepr>) -> R) -> R {
+        self.splitter.op.with_value(f)
+    }
 }
 
 
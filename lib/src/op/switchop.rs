@@ -145,55 +145,57 @@ where
     type Ord = SwitchOrder<O::Ord, S>;
 
     fn poll_delta(&self, ctx: &mut Context<'_>) -> Poll<Option<Hide<Delta, Self::LatRepr>>> {
-        let (state_this, state_other) = S::swap_state(&self.state_a, &self.state_b);
-
-        // Check if we have a value waiting.
-        {
-            let mut state_this = state_this.borrow_mut();
-            match state_this.delta.take() {
-                Some(polled) => {
-                    return Poll::Ready(Some(polled));
-                }
-                None => {
-                    state_this.waker.replace(ctx.waker().clone());
+        traced("SwitchOp", || {
+            let (state_this, state_other) = S::swap_state(&self.state_a, &self.state_b);
+
+            // Check if we have a value waiting.
+            {
+                let mut state_this = state_this.borrow_mut();
+                match state_this.delta.take() {
+                    Some(polled) => {
+                        return Poll::Ready(Some(polled));
+                    }
+                    None => {
+                        state_this.waker.replace(ctx.waker().clone());
+                    }
                 }
             }
-        }
 
-        // Check if other splits are ready to receive a value.
-        {
-            let state_other = state_other.borrow();
-            if let Some(_) = state_other.delta {
-                // Other has it's value filled, wake it up and return pending.
-                if let Some(waker) = &state_other.waker {
-                    waker.wake_by_ref()
+            // Check if other splits are ready to receive a value.
+            {
+                let state_other = state_other.borrow();
+                if let Some(_) = state_other.delta {
+                    // Other has it's value filled, wake it up and return pending.
+                    if let Some(waker) = &state_other.waker {
+                        waker.wake_by_ref()
+                    }
+                    return Poll::Pending
                 }
-                return Poll::Pending
             }
-        }
-
-        // Poll upstream.
-        match self.op.poll_delta(ctx) {
-            Poll::Ready(Some(delta)) => {
-                let (delta_a, delta_b) = delta.split();
-                let (delta_this, delta_other) = S::swap(delta_a, delta_b);
-
-                {
-                    // Handle other_state.
-                    let mut state_other = state_other.borrow_mut();
-                    let old_delta_opt = state_other.delta.replace(delta_other);
-                    assert!(old_delta_opt.is_none());
 
-                    if let Some(waker) = state_other.waker.take() {
-                        waker.wake();
+            // Poll upstream.
+            match self.op.poll_delta(ctx) {
+                Poll::Ready(Some(delta)) => {
+                    let (delta_a, delta_b) = delta.split();
+                    let (delta_this, delta_other) = S::swap(delta_a, delta_b);
+
+                    {
+                        // Handle other_state.
+                        let mut state_other = state_other.borrow_mut();
+                        let old_delta_opt = state_other.delta.replace(delta_other);
+                        assert!(old_delta_opt.is_none());
+
+                        if let Some(waker) = state_other.waker.take() {
+                            waker.wake();
+                        }
                     }
-                }
 
-                Poll::Ready(Some(delta_this))
+                    Poll::Ready(Some(delta_this))
+                }
+                Poll::Ready(None) => Poll::Ready(None),
+                Poll::Pending => Poll::Pending,
             }
-            Poll::Ready(None) => Poll::Ready(None),
-            Poll::Pending => Poll::Pending,
-        }
+        })
     }
 }
 
@@ -0,0 +1,66 @@
+use std::any::Any;
+use std::task::{Context, Poll};
+
+use serde::de::DeserializeOwned;
+
+use crate::error::report;
+use crate::hide::{Hide, Delta};
+use crate::lattice::LatticeRepr;
+use crate::tcp_client::TcpClient;
+use crate::tcp_server::serde::deserialize;
+
+use super::optrait::*;
+use super::tcpop::TcpOrder;
+
+/// Receives deltas from a reconnecting `TcpClient`. Does not end when the
+/// connection is lost, instead waits for the client to reconnect.
+pub struct TcpClientOp<Lr: Any + LatticeRepr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    tcp_client: TcpClient,
+    _phantom: std::marker::PhantomData<Lr>,
+}
+
+impl<Lr: Any + LatticeRepr> TcpClientOp<Lr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    pub fn new(tcp_client: TcpClient) -> Self {
+        Self {
+            tcp_client,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<Lr: Any + LatticeRepr> Op for TcpClientOp<Lr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    type LatRepr = Lr;
+
+    fn propegate_saturation(&self) {
+    }
+}
+
+impl<Lr: Any + LatticeRepr> OpDelta for TcpClientOp<Lr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    type Ord = TcpOrder;
+
+    fn poll_delta(&self, ctx: &mut Context<'_>) -> Poll<Option<Hide<Delta, Self::LatRepr>>> {
+        loop {
+            match self.tcp_client.poll_read(ctx) {
+                Poll::Ready(bytes_mut) => {
+                    match deserialize::<Lr>(&*bytes_mut) {
+                        Ok(repr) => return Poll::Ready(Some(Hide::new(repr))),
+                        Err(err) => report(err),
+                    }
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
@@ -8,9 +8,12 @@ use tokio::net::tcp::OwnedReadHalf;
 use tokio_util::codec::{FramedRead, LengthDelimitedCodec};
 use serde::de::DeserializeOwned;
 
+use crate::error::report;
 use crate::hide::{Hide, Delta};
 use crate::lattice::LatticeRepr;
+use crate::merkle::MAX_FRAME_SIZE;
 use crate::metadata::Order;
+use crate::metrics::{Counter, Metrics};
 use crate::tcp_server::serde::deserialize;
 
 use super::optrait::*;
@@ -20,6 +23,7 @@ where
     Lr::Repr: DeserializeOwned,
 {
     framed_read: RefCell<FramedRead<OwnedReadHalf, LengthDelimitedCodec>>,
+    bytes_received: Counter,
     _phantom: std::marker::PhantomData<Lr>,
 }
 
@@ -30,12 +34,19 @@ where
     pub fn new(tcp_read: OwnedReadHalf) -> Self {
         let framed_read = LengthDelimitedCodec::builder()
             .length_field_length(2)
+            .max_frame_length(MAX_FRAME_SIZE)
             .new_read(tcp_read);
         Self {
             framed_read: RefCell::new(framed_read),
+            bytes_received: Default::default(),
             _phantom: std::marker::PhantomData,
         }
     }
+
+    /// Count bytes read as `<name>_bytes_received_total`.
+    pub fn register_metrics(&self, metrics: &Metrics, name: &str) {
+        metrics.register(format!("{}_bytes_received_total", name), self.bytes_received.clone());
+    }
 }
 
 impl<Lr: Any + LatticeRepr> Op for TcpOp<Lr>
@@ -59,22 +70,22 @@ where
     type Ord = TcpOrder;
 
     fn poll_delta(&self, ctx: &mut Context<'_>) -> Poll<Option<Hide<Delta, Self::LatRepr>>> {
-        match Pin::new(&mut *self.framed_read.borrow_mut()).poll_next(ctx) {
-            Poll::Ready(None) => Poll::Ready(None),
-            Poll::Ready(Some(Err(err))) => {
-                println!("TcpOp Error {}", err);
-                Poll::Pending
-            }
-            Poll::Ready(Some(Ok(bytes_mut))) => {
-                match deserialize::<Lr>(&*bytes_mut) {
-                    Ok(repr) => Poll::Ready(Some(Hide::new(repr))),
-                    Err(err) => {
-                        eprintln!("Failed to deserialize: {}", err);
-                        Poll::Pending
+        loop {
+            match Pin::new(&mut *self.framed_read.borrow_mut()).poll_next(ctx) {
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Ready(Some(Err(err))) => {
+                    report(err);
+                    return Poll::Ready(None);
+                }
+                Poll::Ready(Some(Ok(bytes_mut))) => {
+                    self.bytes_received.add(bytes_mut.len() as u64);
+                    match deserialize::<Lr>(&*bytes_mut) {
+                        Ok(repr) => return Poll::Ready(Some(Hide::new(repr))),
+                        Err(err) => report(err),
                     }
                 }
+                Poll::Pending => return Poll::Pending,
             }
-            Poll::Pending => Poll::Pending
         }
     }
 }
@@ -5,6 +5,7 @@ use std::task::{Context, Poll};
 use serde::de::DeserializeOwned;
 
 use crate::collections::{Single};
+use crate::error::report;
 use crate::hide::{Hide, Delta};
 use crate::lattice::LatticeRepr;
 use crate::lattice::map_union::{MapUnionRepr};
@@ -20,7 +21,6 @@ where
     Lr::Repr: DeserializeOwned,
 {
     tcp_server: TcpServer,
-    msgs: std::cell::Cell<usize>,
     _phantom: std::marker::PhantomData<Lr>,
 }
 
@@ -31,7 +31,6 @@ where
     pub fn new(tcp_server: TcpServer) -> Self {
         Self {
             tcp_server,
-            msgs: Default::default(),
             _phantom: std::marker::PhantomData,
         }
     }
@@ -59,33 +58,20 @@ where
 
     fn poll_delta(&self, ctx: &mut Context<'_>) -> Poll<Option<Hide<Delta, Self::LatRepr>>> {
 
-        match self.tcp_server.poll_accept(ctx) {
-            Poll::Ready(Ok(addr)) => println!("New client! {}", addr),
-            Poll::Ready(Err(err)) => eprintln!("Accept err! {}", err),
-            Poll::Pending => (),
+        if let Poll::Ready(Err(err)) = self.tcp_server.poll_accept(ctx) {
+            report(err);
         }
 
-        match self.tcp_server.poll_read(ctx) {
-            Poll::Ready(Some((addr, bytes_mut))) => {
-                match deserialize::<Lr>(&*bytes_mut) {
-                    Ok(repr) => {
-                        {
-                            let msgs = self.msgs.get() + 1;
-                            if 1 == msgs || 0 == msgs % 20000 {
-                                let time = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis();
-                                println!("{} MESSAGES RECIEVED: {}", time, msgs);
-                            }
-                            self.msgs.set(msgs);
-                        }
-                        Poll::Ready(Some(Hide::new(Single((addr, repr)))))
-                    }
-                    Err(err) => {
-                        eprintln!("Failed to deserialize: {}", err);
-                        Poll::Pending
+        loop {
+            match self.tcp_server.poll_read(ctx) {
+                Poll::Ready(Some((addr, bytes_mut))) => {
+                    match deserialize::<Lr>(&*bytes_mut) {
+                        Ok(repr) => return Poll::Ready(Some(Hide::new(Single((addr, repr))))),
+                        Err(err) => report(err),
                     }
                 }
+                _ => return Poll::Pending,
             }
-            _ => Poll::Pending,
         }
     }
 }
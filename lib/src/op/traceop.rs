@@ -0,0 +1,94 @@
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use serde::ser::Serialize;
+use tracing::field;
+
+use crate::hide::{Hide, Delta, Value};
+use crate::lattice::LatticeRepr;
+
+use super::*;
+
+/// Run POLL, the body of `OpDelta::poll_delta`, in a `tracing` span for OP.
+///
+/// Upstream ops are polled within the span, so a delta's span tree follows
+/// its path through the graph. When a delta is produced the span records
+/// `elapsed_us`, the time taken to produce it.
+pub(crate) fn traced<Lr: LatticeRepr>(
+    op: &'static str,
+    poll: impl FnOnce() -> Poll<Option<Hide<Delta, Lr>>>,
+) -> Poll<Option<Hide<Delta, Lr>>> {
+    traced_with_size(op, None, poll)
+}
+
+/// Like `traced`, but also record `repr_bytes`, the size of each delta as
+/// given by REPR_BYTES.
+fn traced_with_size<Lr: LatticeRepr>(
+    op: &'static str,
+    repr_bytes: Option<fn(&Lr::Repr) -> Option<u64>>,
+    poll: impl FnOnce() -> Poll<Option<Hide<Delta, Lr>>>,
+) -> Poll<Option<Hide<Delta, Lr>>> {
+    let span = tracing::trace_span!("poll_delta",
+        op,
+        lattice = std::any::type_name::<Lr>(),
+        repr_bytes = field::Empty,
+        elapsed_us = field::Empty);
+    if span.is_disabled() {
+        return poll();
+    }
+
+    let _enter = span.enter();
+    let start = Instant::now();
+    let polled = poll();
+    if let Poll::Ready(Some(delta)) = &polled {
+        if let Some(size) = repr_bytes.and_then(|repr_bytes| repr_bytes(delta.reveal_ref())) {
+            span.record("repr_bytes", size);
+        }
+        span.record("elapsed_us", start.elapsed().as_micros() as u64);
+    }
+    polled
+}
+
+/// Traces deltas passing through under NAME, see `traced`. Use to label
+/// parts of a graph, e.g. the reads and writes of a server. Spans also record
+/// `repr_bytes`, the serialized size of each delta.
+pub struct TraceOp<O: Op> {
+    op: O,
+    name: &'static str,
+}
+
+impl<O: Op> TraceOp<O> {
+    pub fn new(op: O, name: &'static str) -> Self {
+        Self { op, name }
+    }
+}
+
+impl<O: Op> Op for TraceOp<O> {
+    type LatRepr = O::LatRepr;
+
+    fn propegate_saturation(&self) {
+        self.op.propegate_saturation()
+    }
+}
+
+impl<O: OpDelta> OpDelta for TraceOp<O>
+where
+    <O::LatRepr as LatticeRepr>::Repr: Serialize,
+{
+    type Ord = O::Ord;
+
+    fn poll_delta(&self, ctx: &mut Context<'_>) -> Poll<Option<Hide<Delta, Self::LatRepr>>> {
+        let repr_bytes: fn(&_) -> _ = |repr| bincode::serialized_size(repr).ok();
+        traced_with_size(self.name, Some(repr_bytes), || self.op.poll_delta(ctx))
+    }
+}
+
+impl<O: OpValue> OpValue for TraceOp<O> {
+    fn get_value(&self) -> Hide<Value, Self::LatRepr> {
+        self.op.get_value()
+    }
+
+    fn with_value<R>(&self, f: impl FnOnce(&Hide<Value, Self::LatRepr>) -> R) -> R {
+        self.op.with_value(f)
+    }
+}
@@ -0,0 +1,80 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use serde::de::DeserializeOwned;
+use tokio::io::ReadBuf;
+use tokio::net::UdpSocket;
+
+use crate::error::report;
+use crate::hide::{Hide, Delta};
+use crate::lattice::LatticeRepr;
+use crate::metadata::Order;
+use crate::tcp_server::serde::deserialize;
+
+use super::optrait::*;
+
+/// Largest possible UDP payload.
+pub const MAX_DATAGRAM_SIZE: usize = 65_507;
+
+/// Receives one delta per datagram. Datagrams may be dropped, duplicated, or
+/// reordered, which lattice merges are insensitive to.
+pub struct UdpOp<Lr: Any + LatticeRepr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    socket: Arc<UdpSocket>,
+    buf: RefCell<Vec<u8>>,
+    _phantom: std::marker::PhantomData<Lr>,
+}
+
+impl<Lr: Any + LatticeRepr> UdpOp<Lr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    pub fn new(socket: Arc<UdpSocket>) -> Self {
+        Self {
+            socket,
+            buf: RefCell::new(vec![0; MAX_DATAGRAM_SIZE]),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<Lr: Any + LatticeRepr> Op for UdpOp<Lr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    type LatRepr = Lr;
+
+    fn propegate_saturation(&self) {
+    }
+}
+
+pub enum UdpOrder {}
+impl Order for UdpOrder {}
+
+impl<Lr: Any + LatticeRepr> OpDelta for UdpOp<Lr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    type Ord = UdpOrder;
+
+    fn poll_delta(&self, ctx: &mut Context<'_>) -> Poll<Option<Hide<Delta, Self::LatRepr>>> {
+        let mut buf = self.buf.borrow_mut();
+        loop {
+            let mut read_buf = ReadBuf::new(&mut *buf);
+            match self.socket.poll_recv(ctx, &mut read_buf) {
+                Poll::Ready(Ok(())) => {
+                    match deserialize::<Lr>(read_buf.filled()) {
+                        Ok(repr) => return Poll::Ready(Some(Hide::new(repr))),
+                        Err(err) => report(err),
+                    }
+                }
+                Poll::Ready(Err(err)) => report(err),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::task::{Context, Poll};
+use std::pin::Pin;
+
+use futures_core::stream::Stream;
+use tokio::net::unix::OwnedReadHalf;
+use tokio_util::codec::{FramedRead, LengthDelimitedCodec};
+use serde::de::DeserializeOwned;
+
+use crate::error::report;
+use crate::hide::{Hide, Delta};
+use crate::lattice::LatticeRepr;
+use crate::merkle::MAX_FRAME_SIZE;
+use crate::metadata::Order;
+use crate::tcp_server::serde::deserialize;
+
+use super::optrait::*;
+
+pub struct UnixOp<Lr: Any + LatticeRepr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    framed_read: RefCell<FramedRead<OwnedReadHalf, LengthDelimitedCodec>>,
+    _phantom: std::marker::PhantomData<Lr>,
+}
+
+impl<Lr: Any + LatticeRepr> UnixOp<Lr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    pub fn new(unix_read: OwnedReadHalf) -> Self {
+        let framed_read = LengthDelimitedCodec::builder()
+            .length_field_length(2)
+            .max_frame_length(MAX_FRAME_SIZE)
+            .new_read(unix_read);
+        Self {
+            framed_read: RefCell::new(framed_read),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<Lr: Any + LatticeRepr> Op for UnixOp<Lr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    type LatRepr = Lr;
+
+    fn propegate_saturation(&self) {
+    }
+}
+
+pub enum UnixOrder {}
+impl Order for UnixOrder {}
+
+impl<Lr: Any + LatticeRepr> OpDelta for UnixOp<Lr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    type Ord = UnixOrder;
+
+    fn poll_delta(&self, ctx: &mut Context<'_>) -> Poll<Option<Hide<Delta, Self::LatRepr>>> {
+        loop {
+            match Pin::new(&mut *self.framed_read.borrow_mut()).poll_next(ctx) {
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Ready(Some(Err(err))) => {
+                    report(err);
+                    return Poll::Ready(None);
+                }
+                Poll::Ready(Some(Ok(bytes_mut))) => {
+                    match deserialize::<Lr>(&*bytes_mut) {
+                        Ok(repr) => return Poll::Ready(Some(Hide::new(repr))),
+                        Err(err) => report(err),
+                    }
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use std::any::Any;
+use std::task::{Context, Poll};
+
+use serde::de::DeserializeOwned;
+
+use crate::collections::{Single};
+use crate::error::report;
+use crate::hide::{Hide, Delta};
+use crate::lattice::LatticeRepr;
+use crate::lattice::map_union::{MapUnionRepr};
+use crate::tag;
+use crate::tcp_server::serde::deserialize;
+use crate::unix_server::{UnixPeer, UnixServer};
+
+use super::optrait::*;
+use super::unixop::UnixOrder;
+
+pub struct UnixServerOp<Lr: Any + LatticeRepr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    unix_server: UnixServer,
+    _phantom: std::marker::PhantomData<Lr>,
+}
+
+impl<Lr: Any + LatticeRepr> UnixServerOp<Lr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    pub fn new(unix_server: UnixServer) -> Self {
+        Self {
+            unix_server,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<Lr: Any + LatticeRepr> Op for UnixServerOp<Lr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    type LatRepr = MapUnionRepr<tag::SINGLE, UnixPeer, Lr>;
+
+    fn propegate_saturation(&self) {
+    }
+}
+
+impl<Lr: Any + LatticeRepr> OpDelta for UnixServerOp<Lr>
+where
+    Lr::Repr: DeserializeOwned,
+{
+    type Ord = UnixOrder;
+
+    fn poll_delta(&self, ctx: &mut Context<'_>) -> Poll<Option<Hide<Delta, Self::LatRepr>>> {
+        while let Poll::Ready(result) = self.unix_server.poll_accept(ctx) {
+            if let Err(err) = result {
+                report(err);
+                break;
+            }
+        }
+
+        loop {
+            match self.unix_server.poll_read(ctx) {
+                Poll::Ready(Some((peer, bytes_mut))) => {
+                    match deserialize::<Lr>(&*bytes_mut) {
+                        Ok(repr) => return Poll::Ready(Some(Hide::new(Single((peer, repr))))),
+                        Err(err) => report(err),
+                    }
+                }
+                _ => return Poll::Pending,
+            }
+        }
+    }
+}
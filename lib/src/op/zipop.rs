@@ -61,37 +61,39 @@ where
     type Ord = A::Ord;
 
     fn poll_delta(&self, ctx: &mut Context<'_>) -> Poll<Option<Hide<Delta, Self::LatRepr>>> {
-        let delta_a_opt = self.delta_a_opt.take();
+        traced("ZipOp", || {
+            let delta_a_opt = self.delta_a_opt.take();
 
-        let delta_a = match delta_a_opt {
-            Some(delta_a) => delta_a,
-            None => {
-                match self.op_a.poll_delta(ctx) {
-                    Poll::Ready(Some(delta_a)) => delta_a,
-                    Poll::Ready(None) => return Poll::Ready(None),
-                    Poll::Pending => return Poll::Pending,
+            let delta_a = match delta_a_opt {
+                Some(delta_a) => delta_a,
+                None => {
+                    match self.op_a.poll_delta(ctx) {
+                        Poll::Ready(Some(delta_a)) => delta_a,
+                        Poll::Ready(None) => return Poll::Ready(None),
+                        Poll::Pending => return Poll::Pending,
+                    }
                 }
-            }
-        };
+            };
 
-        match self.op_b.poll_delta(ctx) {
-            Poll::Ready(Some(delta_b)) => {
-                let mut out = Vec::new();
-                let delta_as: Vec<_> = delta_a.into_reveal().into_iter().collect();
-                let delta_bs: Vec<_> = delta_b.into_reveal().into_iter().collect();
-                for val_a in delta_as.iter() {
-                    for val_b in delta_bs.iter() {
-                        out.push((val_a.clone(), val_b.clone()));
+            match self.op_b.poll_delta(ctx) {
+                Poll::Ready(Some(delta_b)) => {
+                    let mut out = Vec::new();
+                    let delta_as: Vec<_> = delta_a.into_reveal().into_iter().collect();
+                    let delta_bs: Vec<_> = delta_b.into_reveal().into_iter().collect();
+                    for val_a in delta_as.iter() {
+                        for val_b in delta_bs.iter() {
+                            out.push((val_a.clone(), val_b.clone()));
+                        }
                     }
-                }
-                return Poll::Ready(Some(Hide::new(out)))
-            },
-            Poll::Ready(None) => panic!(),
-            Poll::Pending => {},
-        };
+                    return Poll::Ready(Some(Hide::new(out)))
+                },
+                Poll::Ready(None) => panic!(),
+                Poll::Pending => {},
+            };
 
-        self.delta_a_opt.replace(Some(delta_a));
+            self.delta_a_opt.replace(Some(delta_a));
 
-        Poll::Pending
+            Poll::Pending
+        })
     }
 }
\ No newline at end of file
@@ -0,0 +1,198 @@
+//! On-disk persistence of lattice state, see `LatticeOp::persist`.
+//!
+//! State is stored in a directory as a snapshot of the merged value plus a
+//! write-ahead log of the deltas merged since. Recovery loads the snapshot and
+//! replays the log. Because merges are idempotent, replaying deltas which are
+//! already in the snapshot is harmless, so a crash between writing a snapshot
+//! and truncating the log loses nothing.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{Cursor, Error, ErrorKind, Result, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::JoinHandle;
+
+use serde::de::DeserializeOwned;
+use serde::ser::Serialize;
+
+use crate::lattice::{LatticeRepr, Merge};
+
+pub const DEFAULT_SNAPSHOT_EVERY: usize = 10_000;
+
+const SNAPSHOT_FILE: &str = "snapshot";
+const SNAPSHOT_TMP_FILE: &str = "snapshot.tmp";
+const WAL_FILE: &str = "wal";
+
+fn to_io_error(err: bincode::Error) -> Error {
+    Error::new(ErrorKind::InvalidData, err)
+}
+
+/// A write for the writer thread, already serialized.
+enum Job {
+    Log(Vec<u8>),
+    Snapshot(Vec<u8>),
+}
+
+/// Runs on the writer thread until the sender is dropped. After an error
+/// later jobs are skipped, the error is returned to the op by the next call.
+fn run_writer(dir: PathBuf, mut wal: File, jobs: mpsc::Receiver<Job>, error: Arc<Mutex<Option<Error>>>) {
+    let mut run = |job| -> Result<()> {
+        match job {
+            Job::Log(bytes) => wal.write_all(&*bytes),
+            Job::Snapshot(bytes) => {
+                let tmp_path = dir.join(SNAPSHOT_TMP_FILE);
+                {
+                    let mut tmp = File::create(&tmp_path)?;
+                    tmp.write_all(&*bytes)?;
+                    tmp.sync_all()?;
+                }
+                fs::rename(&tmp_path, dir.join(SNAPSHOT_FILE))?;
+
+                wal.set_len(0)?;
+                wal.sync_all()
+            }
+        }
+    };
+    for job in jobs {
+        if let Err(err) = run(job) {
+            error.lock().expect("Poisoned").replace(err);
+            return;
+        }
+    }
+}
+
+/// Persisted `Lr` state, logging `DeltaLr` deltas.
+///
+/// Deltas and snapshots are serialized by the caller but written, and
+/// fsynced, on a dedicated writer thread so they never block the executor.
+/// Dropping the `Persistence` waits for pending writes to finish.
+pub struct Persistence<Lr: LatticeRepr, DeltaLr: LatticeRepr> {
+    jobs: Option<mpsc::Sender<Job>>,
+    writer: Option<JoinHandle<()>>,
+    error: Arc<Mutex<Option<Error>>>,
+    snapshot_every: usize,
+    logged: usize,
+    recovered: bool,
+    serialize_value: fn(&Lr::Repr) -> bincode::Result<Vec<u8>>,
+    serialize_delta: fn(&DeltaLr::Repr) -> bincode::Result<Vec<u8>>,
+}
+
+impl<Lr: LatticeRepr + Merge<DeltaLr>, DeltaLr: LatticeRepr> Persistence<Lr, DeltaLr> {
+    /// Open (or create) the persisted state in DIR, recovering it into STATE.
+    /// A snapshot is taken after every SNAPSHOT_EVERY logged deltas.
+    pub fn open(dir: impl AsRef<Path>, snapshot_every: usize, state: &mut Lr::Repr) -> Result<Self>
+    where
+        Lr::Repr: Serialize + DeserializeOwned,
+        DeltaLr::Repr: Serialize + DeserializeOwned,
+    {
+        let dir = dir.as_ref().to_owned();
+        fs::create_dir_all(&dir)?;
+
+        let mut recovered = false;
+        match fs::read(dir.join(SNAPSHOT_FILE)) {
+            Ok(bytes) => {
+                // The snapshot includes the initial state.
+                *state = bincode::deserialize(&*bytes).map_err(to_io_error)?;
+                recovered = true;
+            }
+            Err(err) if ErrorKind::NotFound == err.kind() => {}
+            Err(err) => return Err(err),
+        }
+
+        let mut logged = 0;
+        let wal = OpenOptions::new().create(true).append(true).open(dir.join(WAL_FILE))?;
+        {
+            let bytes = fs::read(dir.join(WAL_FILE))?;
+            let mut cursor = Cursor::new(&*bytes);
+            while (cursor.position() as usize) < bytes.len() {
+                let start = cursor.position();
+                match bincode::deserialize_from(&mut cursor) {
+                    Ok(delta) => {
+                        <Lr as Merge<DeltaLr>>::merge(state, delta);
+                        logged += 1;
+                        recovered = true;
+                    }
+                    Err(err) => {
+                        // A torn write at the end of the log, drop it.
+                        tracing::warn!(start, %err, "truncating torn write-ahead log");
+                        wal.set_len(start)?;
+                        break;
+                    }
+                }
+            }
+        }
+
+        let (jobs, jobs_recv) = mpsc::channel();
+        let error: Arc<Mutex<Option<Error>>> = Default::default();
+        let writer = {
+            let error = error.clone();
+            std::thread::Builder::new()
+                .name("spinach-persistence".to_owned())
+                .spawn(move || run_writer(dir, wal, jobs_recv, error))?
+        };
+
+        Ok(Self {
+            jobs: Some(jobs),
+            writer: Some(writer),
+            error,
+            snapshot_every,
+            logged,
+            recovered,
+            serialize_value: |repr| bincode::serialize(repr),
+            serialize_delta: |repr| bincode::serialize(repr),
+        })
+    }
+}
+
+impl<Lr: LatticeRepr, DeltaLr: LatticeRepr> Persistence<Lr, DeltaLr> {
+    /// If `open` found a snapshot or logged deltas.
+    pub fn recovered(&self) -> bool {
+        self.recovered
+    }
+
+    /// Append DELTA to the write-ahead log, snapshotting VALUE if due.
+    /// Log writes are handed to the OS but not fsynced. Returns the error of
+    /// an earlier failed write, if any.
+    pub fn log(&mut self, delta: &DeltaLr::Repr, value: &Lr::Repr) -> Result<()> {
+        let bytes = (self.serialize_delta)(delta).map_err(to_io_error)?;
+        self.send(Job::Log(bytes))?;
+        self.logged += 1;
+
+        if self.logged >= self.snapshot_every {
+            self.snapshot(value)?;
+        }
+        Ok(())
+    }
+
+    /// Write VALUE as the snapshot and clear the write-ahead log.
+    pub fn snapshot(&mut self, value: &Lr::Repr) -> Result<()> {
+        let bytes = (self.serialize_value)(value).map_err(to_io_error)?;
+        self.send(Job::Snapshot(bytes))?;
+        self.logged = 0;
+        Ok(())
+    }
+
+    fn send(&self, job: Job) -> Result<()> {
+        if let Some(err) = self.error.lock().expect("Poisoned").take() {
+            return Err(err);
+        }
+        self.jobs.as_ref().expect("Open")
+            .send(job)
+            .map_err(|_| Error::new(ErrorKind::BrokenPipe, "Persistence writer thread ended."))
+    }
+}
+
+impl<Lr: LatticeRepr, DeltaLr: LatticeRepr> Drop for Persistence<Lr, DeltaLr> {
+    fn drop(&mut self) {
+        // Close the channel so the writer finishes pending jobs and exits.
+        self.jobs.take();
+        if let Some(writer) = self.writer.take() {
+            if writer.join().is_err() {
+                tracing::error!("persistence writer thread panicked");
+            }
+        }
+        if let Some(err) = self.error.lock().expect("Poisoned").take() {
+            tracing::error!(%err, "persistence write failed");
+        }
+    }
+}
@@ -0,0 +1,260 @@
+//! An in-memory network for deterministic distributed tests.
+//!
+//! Nodes bind a `SimServer` to an address on a `SimNetwork`, which stands in
+//! for a `TcpServer`; see `SimOp`, `SimServerOp`, `SimComp`, and `SimServerComp`.
+//! Messages are only delivered when the network is stepped, one logical tick at
+//! a time. A seeded RNG decides which messages are dropped, duplicated, or
+//! delayed (and so reordered), so a run is reproducible from its seed.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::{Error, ErrorKind, Result};
+use std::net::SocketAddr;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+use bytes::{Bytes, BytesMut};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+use crate::metrics::{Metrics, TransportMetrics};
+
+/// Faults injected by a `SimNetwork`. Delays are in ticks, see `SimNetwork::step`.
+#[derive(Clone, Debug)]
+pub struct SimConfig {
+    /// Probability each message is dropped.
+    pub drop_rate: f64,
+    /// Probability each message is delivered twice.
+    pub duplicate_rate: f64,
+    pub min_delay: u64,
+    pub max_delay: u64,
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        Self {
+            drop_rate: 0.0,
+            duplicate_rate: 0.0,
+            min_delay: 1,
+            max_delay: 1,
+        }
+    }
+}
+
+/// Message counts for a `SimNetwork`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SimStats {
+    pub sent: u64,
+    pub delivered: u64,
+    pub dropped: u64,
+    pub duplicated: u64,
+}
+
+struct InFlight {
+    deliver_at: u64,
+    from: SocketAddr,
+    to: SocketAddr,
+    bytes: Bytes,
+}
+
+#[derive(Default)]
+struct Node {
+    inbox: VecDeque<(SocketAddr, Bytes)>,
+    waker: Option<Waker>,
+}
+
+struct SimNetworkState {
+    config: SimConfig,
+    rng: StdRng,
+    now: u64,
+    nodes: HashMap<SocketAddr, Node>,
+    in_flight: Vec<InFlight>,
+    partitions: Vec<HashSet<SocketAddr>>,
+    stats: SimStats,
+}
+
+impl SimNetworkState {
+    fn is_connected(&self, a: SocketAddr, b: SocketAddr) -> bool {
+        self.partitions.iter().all(|partition| partition.contains(&a) == partition.contains(&b))
+    }
+}
+
+/// A simulated network. Clones share the same network.
+#[derive(Clone)]
+pub struct SimNetwork {
+    state: Rc<RefCell<SimNetworkState>>,
+}
+
+impl SimNetwork {
+    pub fn new(seed: u64, config: SimConfig) -> Self {
+        assert!(config.min_delay <= config.max_delay, "SimConfig min_delay > max_delay.");
+        assert!((0.0..=1.0).contains(&config.drop_rate), "SimConfig drop_rate not in 0.0..=1.0.");
+        assert!((0.0..=1.0).contains(&config.duplicate_rate), "SimConfig duplicate_rate not in 0.0..=1.0.");
+        let state = SimNetworkState {
+            config,
+            rng: StdRng::seed_from_u64(seed),
+            now: 0,
+            nodes: Default::default(),
+            in_flight: Default::default(),
+            partitions: Default::default(),
+            stats: Default::default(),
+        };
+        Self {
+            state: Rc::new(RefCell::new(state)),
+        }
+    }
+
+    pub fn bind(&self, addr: SocketAddr) -> Result<SimServer> {
+        let mut state = self.state.borrow_mut();
+        if state.nodes.contains_key(&addr) {
+            return Err(Error::new(ErrorKind::AddrInUse, format!("Addr in use: {}.", addr)));
+        }
+        state.nodes.insert(addr, Default::default());
+        Ok(SimServer {
+            network: self.clone(),
+            addr,
+            transport: Default::default(),
+        })
+    }
+
+    /// Cut NODES off from the rest of the network. Messages crossing the
+    /// partition, including those already in flight, are dropped.
+    pub fn partition(&self, nodes: &[SocketAddr]) {
+        self.state.borrow_mut().partitions.push(nodes.iter().copied().collect());
+    }
+
+    /// Remove all partitions.
+    pub fn heal(&self) {
+        self.state.borrow_mut().partitions.clear();
+    }
+
+    pub fn stats(&self) -> SimStats {
+        self.state.borrow().stats
+    }
+
+    /// If no messages are in flight.
+    pub fn is_idle(&self) -> bool {
+        self.state.borrow().in_flight.is_empty()
+    }
+
+    /// Advance one tick, delivering the messages due in a random order.
+    /// Returns the number of messages delivered.
+    pub fn step(&self) -> usize {
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+        state.now += 1;
+
+        let now = state.now;
+        let (mut due, in_flight): (Vec<_>, Vec<_>) = std::mem::take(&mut state.in_flight)
+            .into_iter()
+            .partition(|msg| msg.deliver_at <= now);
+        state.in_flight = in_flight;
+        due.shuffle(&mut state.rng);
+
+        let mut delivered = 0;
+        for msg in due {
+            if !state.is_connected(msg.from, msg.to) {
+                state.stats.dropped += 1;
+                continue;
+            }
+            let node = state.nodes.get_mut(&msg.to).expect("Messages are only sent to bound nodes.");
+            node.inbox.push_back((msg.from, msg.bytes));
+            if let Some(waker) = node.waker.take() {
+                waker.wake();
+            }
+            delivered += 1;
+        }
+        state.stats.delivered += delivered as u64;
+        delivered
+    }
+
+    /// Let the nodes run and step the network until no messages are in flight.
+    /// Nodes must be running on the current thread, e.g. joined with this future.
+    pub async fn run_until_idle(&self) {
+        loop {
+            tokio::task::yield_now().await;
+            if self.is_idle() {
+                return;
+            }
+            self.step();
+        }
+    }
+
+    fn send(&self, from: SocketAddr, to: SocketAddr, bytes: Bytes) -> Result<()> {
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+        if !state.nodes.contains_key(&to) {
+            return Err(Error::new(ErrorKind::NotConnected, format!("Addr not found: {}.", to)));
+        }
+        state.stats.sent += 1;
+
+        if state.rng.gen_bool(state.config.drop_rate) {
+            state.stats.dropped += 1;
+            return Ok(());
+        }
+        let copies = if state.rng.gen_bool(state.config.duplicate_rate) {
+            state.stats.duplicated += 1;
+            2
+        }
+        else {
+            1
+        };
+        for _ in 0..copies {
+            let delay = state.rng.gen_range(state.config.min_delay..=state.config.max_delay);
+            state.in_flight.push(InFlight {
+                deliver_at: state.now + delay,
+                from,
+                to,
+                bytes: bytes.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    fn poll_recv(&self, addr: SocketAddr, ctx: &mut Context<'_>) -> Poll<(SocketAddr, Bytes)> {
+        let mut state = self.state.borrow_mut();
+        let node = state.nodes.get_mut(&addr).expect("SimServer not bound.");
+        match node.inbox.pop_front() {
+            Some(msg) => Poll::Ready(msg),
+            None => {
+                node.waker.replace(ctx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// A node bound to a `SimNetwork`, in place of a `TcpServer`.
+#[derive(Clone)]
+pub struct SimServer {
+    network: SimNetwork,
+    addr: SocketAddr,
+    transport: TransportMetrics,
+}
+
+impl SimServer {
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.addr)
+    }
+
+    pub fn register_metrics(&self, metrics: &Metrics, name: &str) {
+        self.transport.register(metrics, name);
+    }
+
+    pub async fn write(&self, addr: SocketAddr, item: Bytes) -> Result<()> {
+        let len = item.len();
+        self.network.send(self.addr, addr, item)?;
+        self.transport.bytes_sent.add(len as u64);
+        Ok(())
+    }
+
+    /// Poll for a message from any node.
+    pub fn poll_read(&self, ctx: &mut Context<'_>) -> Poll<Option<(SocketAddr, BytesMut)>> {
+        self.network.poll_recv(self.addr, ctx)
+            .map(|(from, bytes)| {
+                self.transport.bytes_received.add(bytes.len() as u64);
+                Some((from, BytesMut::from(&*bytes)))
+            })
+    }
+}
@@ -2,7 +2,9 @@
 
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
-use crate::collections::{Single, Array, MaskedArray};
+use roaring::{RoaringBitmap, RoaringTreemap};
+
+use crate::collections::{Single, Array, MaskedArray, SortedVec, SortedVecMap, SmallVecSet, SmallVecMap};
 
 pub trait Tag1<T> {
     type Bind;
@@ -35,6 +37,25 @@ impl<T, U> Tag2<T, U> for BTREE_MAP {
 }
 
 
+/// Persistent `im::HashSet`, cheap to clone.
+pub enum IM_HASH_SET {}
+impl<T> Tag1<T> for IM_HASH_SET {
+    type Bind = im::HashSet<T>;
+}
+
+/// Persistent `im::HashMap`, cheap to clone.
+pub enum IM_HASH_MAP {}
+impl<T, U> Tag2<T, U> for IM_HASH_MAP {
+    type Bind = im::HashMap<T, U>;
+}
+
+/// Persistent `im::OrdMap`, cheap to clone.
+pub enum IM_BTREE_MAP {}
+impl<T, U> Tag2<T, U> for IM_BTREE_MAP {
+    type Bind = im::OrdMap<T, U>;
+}
+
+
 pub enum VEC {}
 impl<T> Tag1<T> for VEC {
     type Bind = Vec<T>;
@@ -44,6 +65,34 @@ impl<T, U> Tag2<T, U> for VEC {
 }
 
 
+pub enum SORTED_VEC {}
+impl<T> Tag1<T> for SORTED_VEC {
+    type Bind = SortedVec<T>;
+}
+impl<T, U> Tag2<T, U> for SORTED_VEC {
+    type Bind = SortedVecMap<T, U>;
+}
+
+
+pub struct SMALL_VEC<const N: usize>([(); N]);
+impl<T, const N: usize> Tag1<T> for SMALL_VEC<N> {
+    type Bind = SmallVecSet<T, N>;
+}
+impl<T, U, const N: usize> Tag2<T, U> for SMALL_VEC<N> {
+    type Bind = SmallVecMap<T, U, N>;
+}
+
+
+/// Compressed (roaring) bitmap, for sets of `u32` or `u64` only.
+pub enum BITMAP {}
+impl Tag1<u32> for BITMAP {
+    type Bind = RoaringBitmap;
+}
+impl Tag1<u64> for BITMAP {
+    type Bind = RoaringTreemap;
+}
+
+
 pub enum SINGLE {}
 impl<T> Tag1<T> for SINGLE {
     type Bind = Single<T>;
@@ -0,0 +1,261 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Poll, Context, Waker};
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use bytes::{Bytes, BytesMut};
+use futures::future;
+use futures::sink::Sink;
+use tokio::io::{Error, ErrorKind, Result};
+use tokio::net::{TcpStream, ToSocketAddrs};
+use tokio::time::Sleep;
+use tokio_util::codec::{Framed, LengthDelimitedCodec};
+use tokio_stream::Stream;
+
+use crate::merkle::MAX_FRAME_SIZE;
+use crate::metrics::{Metrics, TransportMetrics};
+
+pub const DEFAULT_BACKOFF_MIN: Duration = Duration::from_millis(100);
+pub const DEFAULT_BACKOFF_MAX: Duration = Duration::from_secs(10);
+
+struct TcpClientState {
+    stream: Option<Framed<TcpStream, LengthDelimitedCodec>>,
+    connecting: Option<Pin<Box<dyn Future<Output = Result<TcpStream>> + Send>>>,
+    sleep: Option<Pin<Box<Sleep>>>,
+    backoff: Duration,
+    generation: usize,
+    /// Tasks waiting on the client. The reconnect future, backoff sleep, and
+    /// stream only wake the last task to poll them, so the others are woken
+    /// here whenever the connection changes.
+    waiting: Vec<Waker>,
+}
+
+impl TcpClientState {
+    fn wait(&mut self, ctx: &Context<'_>) {
+        if !self.waiting.iter().any(|waker| waker.will_wake(ctx.waker())) {
+            self.waiting.push(ctx.waker().clone());
+        }
+    }
+
+    fn wake_all(&mut self) {
+        for waker in self.waiting.drain(..) {
+            waker.wake();
+        }
+    }
+
+    fn connect(&mut self, stream: TcpStream) {
+        self.stream = Some(TcpClient::frame(stream));
+        self.generation += 1;
+        self.wake_all();
+    }
+
+    fn disconnect(&mut self) {
+        self.stream = None;
+        self.wake_all();
+    }
+}
+
+struct TcpClientInternal {
+    addr: SocketAddr,
+    backoff_min: Duration,
+    backoff_max: Duration,
+    state: Mutex<TcpClientState>,
+    transport: TransportMetrics,
+}
+
+/// A TCP connection to a single server which transparently reconnects, with
+/// exponential backoff, whenever the connection is lost.
+///
+/// Each successful (re)connection increments the client's generation, which
+/// writers can use to detect that the server may have missed earlier writes.
+pub struct TcpClient {
+    handle: Arc<TcpClientInternal>,
+}
+
+impl Clone for TcpClient {
+    fn clone(&self) -> Self {
+        Self {
+            handle: self.handle.clone(),
+        }
+    }
+}
+
+impl TcpClient {
+    /// Create a client for ADDR. The connection is established lazily, on the first poll.
+    pub fn new(addr: SocketAddr) -> Self {
+        Self::new_with_backoff(addr, DEFAULT_BACKOFF_MIN, DEFAULT_BACKOFF_MAX)
+    }
+
+    pub fn new_with_backoff(addr: SocketAddr, backoff_min: Duration, backoff_max: Duration) -> Self {
+        let handle = Arc::new(TcpClientInternal {
+            addr,
+            backoff_min,
+            backoff_max,
+            state: Mutex::new(TcpClientState {
+                stream: None,
+                connecting: None,
+                sleep: None,
+                backoff: backoff_min,
+                generation: 0,
+                waiting: Vec::new(),
+            }),
+            transport: Default::default(),
+        });
+        Self { handle }
+    }
+
+    /// Resolve ADDR and establish the initial connection. Only this initial
+    /// connection may fail, subsequent disconnects are retried.
+    pub async fn connect(addr: impl ToSocketAddrs) -> Result<Self> {
+        let addr = tokio::net::lookup_host(addr).await?
+            .next()
+            .ok_or_else(|| Error::new(ErrorKind::AddrNotAvailable, "Failed to resolve address."))?;
+        let stream = TcpStream::connect(addr).await?;
+
+        let client = Self::new(addr);
+        {
+            let mut state = client.handle.state.lock().expect("Poisoned");
+            state.connect(stream);
+        }
+        Ok(client)
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.handle.addr
+    }
+
+    /// Register byte counts, see `TransportMetrics`.
+    pub fn register_metrics(&self, metrics: &Metrics, name: &str) {
+        self.handle.transport.register(metrics, name);
+    }
+
+    fn frame(stream: TcpStream) -> Framed<TcpStream, LengthDelimitedCodec> {
+        LengthDelimitedCodec::builder()
+            .length_field_length(2)
+            .max_frame_length(MAX_FRAME_SIZE)
+            .new_framed(stream)
+    }
+
+    fn poll_connect_internal(&self, state: &mut TcpClientState, ctx: &mut Context<'_>) -> Poll<usize> {
+        loop {
+            if state.stream.is_some() {
+                return Poll::Ready(state.generation);
+            }
+
+            if let Some(sleep) = &mut state.sleep {
+                match sleep.as_mut().poll(ctx) {
+                    Poll::Ready(()) => {
+                        state.sleep = None;
+                    }
+                    Poll::Pending => {
+                        state.wait(ctx);
+                        return Poll::Pending;
+                    }
+                }
+            }
+
+            let connecting = state.connecting
+                .get_or_insert_with(|| Box::pin(TcpStream::connect(self.handle.addr)));
+            match connecting.as_mut().poll(ctx) {
+                Poll::Ready(Ok(stream)) => {
+                    state.connecting = None;
+                    state.backoff = self.handle.backoff_min;
+                    state.connect(stream);
+                }
+                Poll::Ready(Err(_)) => {
+                    state.connecting = None;
+                    state.sleep = Some(Box::pin(tokio::time::sleep(state.backoff)));
+                    state.backoff = std::cmp::min(2 * state.backoff, self.handle.backoff_max);
+                }
+                Poll::Pending => {
+                    state.wait(ctx);
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+
+    /// Poll until connected, returning the generation of the current connection.
+    pub fn poll_connected(&self, ctx: &mut Context<'_>) -> Poll<usize> {
+        let mut state = self.handle.state.lock().expect("Poisoned");
+        self.poll_connect_internal(&mut state, ctx)
+    }
+
+    /// Wait until connected, returning the generation of the current connection.
+    pub async fn connected(&self) -> usize {
+        future::poll_fn(|ctx| self.poll_connected(ctx)).await
+    }
+
+    /// Write ITEM on the current connection. If the write fails the connection is dropped
+    /// (to be reestablished on the next poll) and the error is returned.
+    pub async fn write(&self, item: Bytes) -> Result<()> {
+        let mut item = Some(item);
+        future::poll_fn(|ctx| self.poll_write(ctx, &mut item)).await
+    }
+
+    fn poll_write(&self, ctx: &mut Context<'_>, item: &mut Option<Bytes>) -> Poll<Result<()>> {
+        let mut state = self.handle.state.lock().expect("Poisoned");
+        if let Poll::Pending = self.poll_connect_internal(&mut state, ctx) {
+            return Poll::Pending;
+        }
+        let stream = state.stream.as_mut().expect("Connected");
+
+        if item.is_some() {
+            let result = match Pin::new(&mut *stream).poll_ready(ctx) {
+                Poll::Ready(Ok(())) => {
+                    let item = item.take().unwrap();
+                    self.handle.transport.bytes_sent.add(item.len() as u64);
+                    Pin::new(&mut *stream).start_send(item)
+                }
+                Poll::Ready(Err(err)) => Err(err),
+                Poll::Pending => {
+                    state.wait(ctx);
+                    return Poll::Pending;
+                }
+            };
+            if let Err(err) = result {
+                state.disconnect();
+                return Poll::Ready(Err(err));
+            }
+        }
+
+        match Pin::new(stream).poll_flush(ctx) {
+            Poll::Ready(Ok(())) => Poll::Ready(Ok(())),
+            Poll::Ready(Err(err)) => {
+                state.disconnect();
+                Poll::Ready(Err(err))
+            }
+            Poll::Pending => {
+                state.wait(ctx);
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Poll for the next frame. Never ends, disconnects are retried.
+    pub fn poll_read(&self, ctx: &mut Context<'_>) -> Poll<BytesMut> {
+        let mut state = self.handle.state.lock().expect("Poisoned");
+        loop {
+            if let Poll::Pending = self.poll_connect_internal(&mut state, ctx) {
+                return Poll::Pending;
+            }
+            let stream = state.stream.as_mut().expect("Connected");
+
+            match Pin::new(stream).poll_next(ctx) {
+                Poll::Ready(Some(Ok(bytes))) => {
+                    self.handle.transport.bytes_received.add(bytes.len() as u64);
+                    return Poll::Ready(bytes);
+                }
+                Poll::Ready(Some(Err(_))) | Poll::Ready(None) => {
+                    state.disconnect();
+                }
+                Poll::Pending => {
+                    state.wait(ctx);
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+}
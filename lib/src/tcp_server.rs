@@ -1,20 +1,20 @@
-use std::collections::HashMap;
-use std::pin::Pin;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 use std::task::{Poll, Context};
 use std::net::SocketAddr;
 
 use bytes::{Bytes, BytesMut};
-use futures::sink::SinkExt;
 use tokio::io::{Result};
 use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
-use tokio_util::codec::{Framed, LengthDelimitedCodec};
-use tokio_stream::Stream;
+
+use crate::framed_streams::FramedStreams;
+use crate::metrics::{Counter, Metrics, TransportMetrics};
 
 
 struct TcpServerInternal {
     listener: TcpListener,
-    streams: Mutex<HashMap<SocketAddr, Framed<TcpStream, LengthDelimitedCodec>>>,
+    streams: FramedStreams<SocketAddr, TcpStream>,
+    transport: TransportMetrics,
+    connections: Counter,
 }
 
 pub struct TcpServer {
@@ -37,36 +37,52 @@ impl TcpServer {
                 let handle = Arc::new(TcpServerInternal {
                     listener,
                     streams: Default::default(),
+                    transport: Default::default(),
+                    connections: Default::default(),
                 });
                 Self { handle }
             })
     }
 
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.handle.listener.local_addr()
+    }
+
+    /// Register byte counts and `<name>_connections_total`, the number of accepted connections.
+    pub fn register_metrics(&self, metrics: &Metrics, name: &str) {
+        self.handle.transport.register(metrics, name);
+        metrics.register(format!("{}_connections_total", name), self.handle.connections.clone());
+    }
+
     pub async fn write(&self, addr: SocketAddr, item: Bytes) -> Result<()> {
-        let mut streams = self.handle.streams.lock().expect("Poisoned");
-        match streams.get_mut(&addr) {
-            Some(stream) => {
-                stream.send(item).await?;
-                Ok(())
-            }
-            None => Err(std::io::Error::new(
-                std::io::ErrorKind::NotConnected,
-                format!("Addr not found: {}.", addr)))
-        }
+        let len = item.len();
+        self.handle.streams.write(addr, item).await?;
+        self.handle.transport.bytes_sent.add(len as u64);
+        Ok(())
+    }
+
+    /// Write ITEM to every currently connected peer. Peers which fail to
+    /// receive the write are dropped from the server.
+    pub async fn broadcast(&self, item: Bytes) {
+        let len = item.len();
+        let sent = self.handle.streams.broadcast(item).await;
+        self.handle.transport.bytes_sent.add((sent * len) as u64);
+    }
+
+    /// Write ITEM to each of ADDRS. Addresses which are not connected are
+    /// skipped, and peers which fail to receive the write are dropped from
+    /// the server, as in `broadcast`.
+    pub async fn multicast(&self, addrs: impl IntoIterator<Item = SocketAddr>, item: Bytes) {
+        let len = item.len();
+        let sent = self.handle.streams.multicast(addrs, item).await;
+        self.handle.transport.bytes_sent.add((sent * len) as u64);
     }
 
     pub fn poll_accept(&self, ctx: &mut Context<'_>) -> Poll<Result<SocketAddr>> {
         match self.handle.listener.poll_accept(ctx) {
             Poll::Ready(Ok((stream, addr))) => {
-
-                let framed_stream = LengthDelimitedCodec::builder()
-                    .length_field_length(2)
-                    .new_framed(stream);
-
-                {
-                    let mut streams = self.handle.streams.lock().expect("Poisoned");
-                    streams.insert(addr, framed_stream);
-                }
+                self.handle.streams.insert(addr, stream);
+                self.handle.connections.inc();
 
                 Poll::Ready(Ok(addr))
             }
@@ -75,35 +91,14 @@ impl TcpServer {
         }
     }
 
+    /// Poll for a frame from any connection. Connection failures are reported
+    /// to the polling comp, see `error::report`.
     pub fn poll_read(&self, ctx: &mut Context<'_>) -> Poll<Option<(SocketAddr, BytesMut)>> {
-        let mut item = None;
-        {
-            let mut streams = self.handle.streams.lock().expect("Poisoned");
-
-            streams.retain(|addr, stream| {
-                if item.is_some() { // "break"
-                    return true;
-                }
-
-                match Pin::new(stream).poll_next(ctx) {
-                    Poll::Ready(Some(Ok(bytes))) => {
-                        item.replace((*addr, bytes));
-                        true
-                    }
-                    Poll::Ready(Some(Err(err))) => {
-                        eprintln!("TCP ERROR on {}: {}", addr, err);
-                        true // TODO?
-                    }
-                    Poll::Ready(None) => false,
-                    Poll::Pending => true,
-                }
-            });
-        }
-
-        match item {
-            Some((addr, bytes)) => Poll::Ready(Some((addr, bytes))),
-            None => Poll::Pending,
-        }
+        self.handle.streams.poll_read(ctx)
+            .map(|item| item.map(|(addr, bytes)| {
+                self.handle.transport.bytes_received.add(bytes.len() as u64);
+                (addr, bytes)
+            }))
     }
 }
 
@@ -111,13 +106,14 @@ pub(crate) mod serde {
     use std::any::Any;
 
     use bincode::{ErrorKind, Result};
-    use bytes::{BufMut, BytesMut};
+    use bytes::{BufMut, Bytes, BytesMut};
     use serde::ser::Serialize;
     use serde::de::DeserializeOwned;
 
-    use crate::lattice::LatticeRepr;
+    use crate::lattice::{LatticeRepr, Split};
+    use crate::merkle::MAX_FRAME_SIZE;
 
-    pub(crate) fn serialize<Lr: Any + LatticeRepr>(repr: Lr::Repr) -> Result<BytesMut>
+    pub(crate) fn serialize<Lr: Any + LatticeRepr>(repr: &Lr::Repr) -> Result<BytesMut>
     where
         Lr::Repr: Serialize,
     {
@@ -129,6 +125,32 @@ pub(crate) mod serde {
             .map(|_| writer.into_inner())
     }
 
+    /// Serialize REPR into frames of at most `MAX_FRAME_SIZE` bytes, splitting
+    /// it into parts which merge back into REPR as needed.
+    pub(crate) fn serialize_frames<Lr: Any + Split>(repr: Lr::Repr) -> Result<Vec<Bytes>>
+    where
+        Lr::Repr: Serialize,
+    {
+        let mut frames = Vec::new();
+        let mut stack = vec![ repr ];
+        while let Some(repr) = stack.pop() {
+            let bytes = serialize::<Lr>(&repr)?;
+            if bytes.len() <= MAX_FRAME_SIZE {
+                frames.push(bytes.freeze());
+                continue;
+            }
+            match Lr::split(repr) {
+                Ok((a, b)) => {
+                    stack.push(b);
+                    stack.push(a);
+                }
+                Err(_) => return Err(Box::new(ErrorKind::Custom(
+                    format!("Value of {} bytes cannot be split to fit in a frame.", bytes.len())))),
+            }
+        }
+        Ok(frames)
+    }
+
     pub(crate) fn deserialize<Lr: Any + LatticeRepr>(bytes: &[u8]) -> Result<Lr::Repr>
     where
         Lr::Repr: DeserializeOwned,
@@ -0,0 +1,244 @@
+//! Deterministic testing of ops without a runtime.
+//!
+//! A `Stepper` polls an op one `poll_delta` at a time with a waker which only
+//! counts wakes, so a test controls exactly when upstream is polled. Sources
+//! are scripted with `ScriptedOp`, which yields deltas or `Pending` on demand.
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Context, Poll, Waker};
+
+use futures::task::{self, ArcWake};
+
+use crate::hide::{Hide, Delta};
+use crate::lattice::{Compare, LatticeRepr};
+use crate::metadata::Order;
+use crate::op::{Op, OpDelta, OpValue};
+
+/// Panic unless A and B are equal lattice values.
+#[track_caller]
+pub fn assert_lattice_eq<A, B>(a: &A::Repr, b: &B::Repr)
+where
+    A: LatticeRepr + Compare<B>,
+    B: LatticeRepr<Lattice = A::Lattice>,
+    A::Repr: Debug,
+    B::Repr: Debug,
+{
+    if Some(std::cmp::Ordering::Equal) != A::compare(a, b) {
+        panic!("Lattice values not equal.\n  left: {:?}\n right: {:?}", a, b);
+    }
+}
+
+struct WakeCount(AtomicUsize);
+
+impl ArcWake for WakeCount {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Polls an op one step at a time.
+pub struct Stepper<O: OpDelta> {
+    op: O,
+    wakes: Arc<WakeCount>,
+    waker: Waker,
+}
+
+impl<O: OpDelta> Stepper<O> {
+    pub fn new(op: O) -> Self {
+        let wakes = Arc::new(WakeCount(AtomicUsize::new(0)));
+        let waker = task::waker(wakes.clone());
+        Self { op, wakes, waker }
+    }
+
+    pub fn op(&self) -> &O {
+        &self.op
+    }
+
+    /// Poll the op once.
+    pub fn step(&self) -> Poll<Option<Hide<Delta, O::LatRepr>>> {
+        let mut ctx = Context::from_waker(&self.waker);
+        self.op.poll_delta(&mut ctx)
+    }
+
+    /// Step until the op is `Pending` or done, returning all deltas emitted.
+    pub fn drain(&self) -> Vec<<O::LatRepr as LatticeRepr>::Repr> {
+        let mut deltas = Vec::new();
+        while let Poll::Ready(Some(delta)) = self.step() {
+            deltas.push(delta.into_reveal());
+        }
+        deltas
+    }
+
+    /// Number of times the op has woken the stepper's waker.
+    pub fn wake_count(&self) -> usize {
+        self.wakes.0.load(Ordering::SeqCst)
+    }
+
+    /// Step once and panic unless the op emits a delta equal to EXPECTED.
+    #[track_caller]
+    pub fn assert_delta<Lr>(&self, expected: Lr::Repr)
+    where
+        Lr: LatticeRepr<Lattice = <O::LatRepr as LatticeRepr>::Lattice>,
+        O::LatRepr: Compare<Lr>,
+        <O::LatRepr as LatticeRepr>::Repr: Debug,
+        Lr::Repr: Debug,
+    {
+        match self.step() {
+            Poll::Ready(Some(delta)) => assert_lattice_eq::<O::LatRepr, Lr>(delta.reveal_ref(), &expected),
+            Poll::Ready(None) => panic!("Expected delta {:?}, op is done.", expected),
+            Poll::Pending => panic!("Expected delta {:?}, op is pending.", expected),
+        }
+    }
+
+    /// Step once and panic unless the op is `Pending`.
+    #[track_caller]
+    pub fn assert_pending(&self) {
+        if let Poll::Ready(polled) = self.step() {
+            panic!("Expected pending, op is {}.", if polled.is_some() { "ready" } else { "done" });
+        }
+    }
+
+    /// Step once and panic unless the op is done.
+    #[track_caller]
+    pub fn assert_done(&self) {
+        match self.step() {
+            Poll::Ready(None) => {}
+            Poll::Ready(Some(_)) => panic!("Expected done, op is ready."),
+            Poll::Pending => panic!("Expected done, op is pending."),
+        }
+    }
+}
+
+impl<O: OpDelta + OpValue> Stepper<O> {
+    /// Panic unless the op's current value equals EXPECTED.
+    #[track_caller]
+    pub fn assert_value<Lr>(&self, expected: Lr::Repr)
+    where
+        Lr: LatticeRepr<Lattice = <O::LatRepr as LatticeRepr>::Lattice>,
+        O::LatRepr: Compare<Lr>,
+        <O::LatRepr as LatticeRepr>::Repr: Debug,
+        Lr::Repr: Debug,
+    {
+        assert_lattice_eq::<O::LatRepr, Lr>(self.op.get_value().reveal_ref(), &expected);
+    }
+}
+
+/// A step of a `ScriptedOp`.
+pub enum Step<Lr: LatticeRepr> {
+    Delta(Lr::Repr),
+    Pending,
+}
+
+struct ScriptState<Lr: LatticeRepr> {
+    steps: VecDeque<Step<Lr>>,
+    closed: bool,
+    waker: Option<Waker>,
+}
+
+/// Handle for adding steps to a `ScriptedOp`. The op is done once its steps
+/// run out after the script is closed or dropped.
+pub struct Script<Lr: LatticeRepr> {
+    state: Rc<RefCell<ScriptState<Lr>>>,
+}
+
+impl<Lr: LatticeRepr> Script<Lr> {
+    fn push(&self, step: Step<Lr>) {
+        let mut state = self.state.borrow_mut();
+        state.steps.push_back(step);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+
+    pub fn delta(&self, delta: Lr::Repr) {
+        self.push(Step::Delta(delta));
+    }
+
+    /// Make the op return `Pending` once, without waking.
+    pub fn pending(&self) {
+        self.push(Step::Pending);
+    }
+
+    pub fn close(&self) {
+        let mut state = self.state.borrow_mut();
+        state.closed = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<Lr: LatticeRepr> Drop for Script<Lr> {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+/// A source op which follows a script. Returns `Pending` when out of steps
+/// until more are added.
+pub struct ScriptedOp<Lr: LatticeRepr> {
+    state: Rc<RefCell<ScriptState<Lr>>>,
+    saturated: Cell<bool>,
+}
+
+impl<Lr: LatticeRepr> ScriptedOp<Lr> {
+    pub fn new() -> (Self, Script<Lr>) {
+        let state = Rc::new(RefCell::new(ScriptState {
+            steps: VecDeque::new(),
+            closed: false,
+            waker: None,
+        }));
+        let op = Self {
+            state: state.clone(),
+            saturated: Cell::new(false),
+        };
+        (op, Script { state })
+    }
+
+    /// Create an op which follows STEPS and then is done.
+    pub fn from_steps(steps: impl IntoIterator<Item = Step<Lr>>) -> Self {
+        let (op, script) = Self::new();
+        for step in steps {
+            script.push(step);
+        }
+        op
+    }
+
+    /// If `propegate_saturation` has been called.
+    pub fn is_saturated(&self) -> bool {
+        self.saturated.get()
+    }
+}
+
+impl<Lr: LatticeRepr> Op for ScriptedOp<Lr> {
+    type LatRepr = Lr;
+
+    fn propegate_saturation(&self) {
+        self.saturated.set(true);
+    }
+}
+
+pub enum ScriptedOrder {}
+impl Order for ScriptedOrder {}
+
+impl<Lr: LatticeRepr> OpDelta for ScriptedOp<Lr> {
+    type Ord = ScriptedOrder;
+
+    fn poll_delta(&self, ctx: &mut Context<'_>) -> Poll<Option<Hide<Delta, Self::LatRepr>>> {
+        let mut state = self.state.borrow_mut();
+        match state.steps.pop_front() {
+            Some(Step::Delta(delta)) => Poll::Ready(Some(Hide::new(delta))),
+            Some(Step::Pending) => Poll::Pending,
+            None if state.closed => Poll::Ready(None),
+            None => {
+                state.waker = Some(ctx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
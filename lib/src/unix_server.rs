@@ -0,0 +1,70 @@
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Poll, Context};
+
+use bytes::{Bytes, BytesMut};
+use tokio::io::{Result};
+use tokio::net::{UnixListener, UnixStream};
+
+use crate::framed_streams::FramedStreams;
+
+/// Identifies a connection to a `UnixServer`. Unix socket peers are usually
+/// unnamed so, unlike `TcpServer`, connections are numbered as they are accepted.
+pub type UnixPeer = usize;
+
+struct UnixServerInternal {
+    listener: UnixListener,
+    next_peer: AtomicUsize,
+    streams: FramedStreams<UnixPeer, UnixStream>,
+}
+
+pub struct UnixServer {
+    handle: Arc<UnixServerInternal>,
+}
+
+impl Clone for UnixServer {
+    fn clone(&self) -> Self {
+        Self {
+            handle: self.handle.clone(),
+        }
+    }
+}
+
+impl UnixServer {
+    pub fn bind(path: impl AsRef<Path>) -> Result<Self> {
+        let result_listener = UnixListener::bind(path);
+        result_listener
+            .map(|listener| {
+                let handle = Arc::new(UnixServerInternal {
+                    listener,
+                    next_peer: AtomicUsize::new(0),
+                    streams: Default::default(),
+                });
+                Self { handle }
+            })
+    }
+
+    pub async fn write(&self, peer: UnixPeer, item: Bytes) -> Result<()> {
+        self.handle.streams.write(peer, item).await
+    }
+
+    pub fn poll_accept(&self, ctx: &mut Context<'_>) -> Poll<Result<UnixPeer>> {
+        match self.handle.listener.poll_accept(ctx) {
+            Poll::Ready(Ok((stream, _addr))) => {
+                let peer = self.handle.next_peer.fetch_add(1, Ordering::Relaxed);
+                self.handle.streams.insert(peer, stream);
+
+                Poll::Ready(Ok(peer))
+            }
+            Poll::Ready(Err(err)) => Poll::Ready(Err(err)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    /// Poll for a frame from any connection. Connection failures are reported
+    /// to the polling comp, see `error::report`.
+    pub fn poll_read(&self, ctx: &mut Context<'_>) -> Poll<Option<(UnixPeer, BytesMut)>> {
+        self.handle.streams.poll_read(ctx)
+    }
+}
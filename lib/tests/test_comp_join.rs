@@ -0,0 +1,54 @@
+use tokio::sync::mpsc;
+
+use spinach::comp::{Comp, CompExt, Supervision};
+use spinach::format::JsonLines;
+use spinach::hide::Hide;
+use spinach::lattice::set_union::SetUnionRepr;
+use spinach::op::{ChannelOp, IterOp, OpExt};
+use spinach::tag;
+
+type MyDeltaRepr = SetUnionRepr<tag::VEC, u64>;
+
+/// A comp which fails on its first delta: writing to a read-only file.
+async fn failing_comp(name: &str) -> Result<impl Comp, String> {
+    let path = std::env::temp_dir().join(format!("spinach_test_comp_join_{}_{}", name, std::process::id()));
+    tokio::fs::File::create(&path).await.map_err(|e| e.to_string())?;
+    let file = tokio::fs::File::open(&path).await.map_err(|e| e.to_string())?;
+    tokio::fs::remove_file(&path).await.map_err(|e| e.to_string())?;
+
+    Ok(IterOp::<MyDeltaRepr, _>::new(vec![ vec![ 1 ] ])
+        .comp_file::<JsonLines>(file))
+}
+
+#[tokio::test]
+pub async fn test_join_complete() -> Result<(), String> {
+    let comps: Vec<_> = (0..3)
+        .map(|i| IterOp::<MyDeltaRepr, _>::new(vec![ vec![ i ], vec![ i + 10 ] ]).comp_null())
+        .collect();
+    comps.run().await.map_err(|e| format!("{:?}", e))
+}
+
+#[tokio::test]
+pub async fn test_join_fail_fast() -> Result<(), String> {
+    // Never completes on its own.
+    let (_send, recv) = mpsc::unbounded_channel::<Hide<_, MyDeltaRepr>>();
+    let comp = ChannelOp::<MyDeltaRepr>::new(recv)
+        .comp_null()
+        .join(failing_comp("fail_fast").await?);
+
+    let err = comp.run().await.unwrap_err();
+    assert_eq!(1, err.index);
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn test_join_supervised() -> Result<(), String> {
+    let comp = IterOp::<MyDeltaRepr, _>::new(vec![ vec![ 1 ] ])
+        .comp_null()
+        .join(failing_comp("ignore").await?.supervise(Supervision::Ignore));
+    comp.run().await.map_err(|e| format!("{:?}", e))?;
+
+    let comp = failing_comp("restart").await?.supervise(Supervision::Restart(2));
+    // After the error, the comp's input is empty.
+    comp.run().await.map_err(|e| format!("{:?}", e))
+}
@@ -0,0 +1,57 @@
+use futures::future;
+use tokio::sync::mpsc;
+
+use spinach::comp::{CompExt, Supervision};
+use spinach::error::SpinachError;
+use spinach::format::JsonLines;
+use spinach::hide::Hide;
+use spinach::lattice::set_union::SetUnionRepr;
+use spinach::op::{ChannelOp, FileOp, IterOp, OpDelta, OpExt};
+use spinach::tag;
+
+type MyDeltaRepr = SetUnionRepr<tag::VEC, u64>;
+
+#[tokio::test]
+pub async fn test_error_decode() -> Result<(), String> {
+    let path = std::env::temp_dir().join(format!("spinach_test_error_{}", std::process::id()));
+    tokio::fs::write(&path, "[1]\nnot json\n[2]\n").await.map_err(|e| e.to_string())?;
+
+    let comp = FileOp::<JsonLines, MyDeltaRepr>::open(&path).await.map_err(|e| e.to_string())?
+        .comp_null();
+    match comp.run().await {
+        Err(SpinachError::Codec(_)) => {}
+        other => return Err(format!("Expected a codec error, got: {:?}", other)),
+    }
+
+    // The remaining lines are still read.
+    let comp = FileOp::<JsonLines, MyDeltaRepr>::open(&path).await.map_err(|e| e.to_string())?
+        .comp_null()
+        .supervise(Supervision::Restart(usize::MAX));
+    comp.run().await.map_err(|e| e.to_string())?;
+
+    tokio::fs::remove_file(&path).await.map_err(|e| e.to_string())
+}
+
+#[tokio::test]
+pub async fn test_error_isolated() -> Result<(), String> {
+    let path = std::env::temp_dir().join(format!("spinach_test_error_isolated_{}", std::process::id()));
+    tokio::fs::write(&path, "[1]\nnot json\n[2]\n").await.map_err(|e| e.to_string())?;
+
+    // The error is reported along with a delta, it must not fail the other comp.
+    let (_send, recv) = mpsc::unbounded_channel::<Hide<_, MyDeltaRepr>>();
+    let comp = FileOp::<JsonLines, MyDeltaRepr>::open(&path).await.map_err(|e| e.to_string())?
+        .comp_null()
+        .join(ChannelOp::<MyDeltaRepr>::new(recv).comp_null());
+    let err = comp.run().await.unwrap_err();
+    assert_eq!(0, err.index);
+
+    // Errors reported outside of any comp are not returned by later comps.
+    let op = FileOp::<JsonLines, MyDeltaRepr>::open(&path).await.map_err(|e| e.to_string())?;
+    while future::poll_fn(|ctx| op.poll_delta(ctx)).await.is_some() {}
+    IterOp::<MyDeltaRepr, _>::new(vec![ vec![ 1 ] ])
+        .comp_null()
+        .run().await
+        .map_err(|e| e.to_string())?;
+
+    tokio::fs::remove_file(&path).await.map_err(|e| e.to_string())
+}
@@ -0,0 +1,50 @@
+use std::collections::HashSet;
+
+use futures::future;
+
+use spinach::comp::CompExt;
+use spinach::format::{Bincode, FileDecode, FileEncode, JsonLines};
+use spinach::lattice::set_union::SetUnionRepr;
+use spinach::op::{FileOp, IterOp, OpDelta, OpExt, OpValue};
+use spinach::tag;
+
+type MyLatRepr = SetUnionRepr<tag::HASH_SET, u64>;
+type MyDeltaRepr = SetUnionRepr<tag::VEC, u64>;
+
+async fn roundtrip<F>(name: &str) -> Result<(), String>
+where
+    F: FileEncode<MyDeltaRepr> + FileDecode<MyDeltaRepr>,
+{
+    let path = std::env::temp_dir().join(format!("spinach_test_file_{}_{}", name, std::process::id()));
+
+    let deltas: Vec<Vec<u64>> = (0..10).map(|i| vec![ 2 * i, 2 * i + 1 ]).collect();
+    let expected: HashSet<u64> = (0..20).collect();
+
+    let file = tokio::fs::File::create(&path).await.map_err(|e| e.to_string())?;
+    IterOp::<MyDeltaRepr, _>::new(deltas)
+        .comp_file::<F>(file)
+        .run().await
+        .map_err(|e| e.to_string())?;
+
+    let op = FileOp::<F, MyDeltaRepr>::open(&path).await.map_err(|e| e.to_string())?
+        .lattice_default::<MyLatRepr>();
+    let mut count = 0;
+    while future::poll_fn(|ctx| op.poll_delta(ctx)).await.is_some() {
+        count += 1;
+    }
+
+    assert_eq!(10, count);
+    assert_eq!(expected, op.get_value().into_reveal());
+
+    tokio::fs::remove_file(&path).await.map_err(|e| e.to_string())
+}
+
+#[tokio::test]
+pub async fn test_file_json_lines() -> Result<(), String> {
+    roundtrip::<JsonLines>("json_lines").await
+}
+
+#[tokio::test]
+pub async fn test_file_bincode() -> Result<(), String> {
+    roundtrip::<Bincode>("bincode").await
+}
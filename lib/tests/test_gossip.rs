@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+use std::task::Poll;
+use std::time::Duration;
+
+use futures::future;
+
+use spinach::comp::CompExt;
+use spinach::lattice::map_union::MapUnionRepr;
+use spinach::lattice::ord::MaxRepr;
+use spinach::op::{GossipOp, MergeOp, OnceOp, OpDelta, OpExt, OpValue, Splitter};
+use spinach::tag;
+use spinach::tcp_client::TcpClient;
+use spinach::tcp_server::TcpServer;
+
+type MyLatRepr = MapUnionRepr<tag::HASH_MAP, u64, MaxRepr<u64>>;
+type MyDeltaRepr = MapUnionRepr<tag::VEC, u64, MaxRepr<u64>>;
+
+/// Gossip between two replicas starting with ITEMS_A and ITEMS_B until both
+/// reach EXPECTED.
+async fn run_gossip(items_a: Vec<(u64, u64)>, items_b: Vec<(u64, u64)>, expected: HashMap<u64, u64>) -> Result<(), String> {
+    let server_a = TcpServer::bind("127.0.0.1:0").await.map_err(|e| e.to_string())?;
+    let server_b = TcpServer::bind("127.0.0.1:0").await.map_err(|e| e.to_string())?;
+    let addr_a = server_a.local_addr().map_err(|e| e.to_string())?;
+    let addr_b = server_b.local_addr().map_err(|e| e.to_string())?;
+
+    // Fanout larger than the number of peers.
+    let fanout = 3;
+
+    let splitter_a = Splitter::new(MergeOp::new(OnceOp::<MyDeltaRepr>::new(items_a), GossipOp::<MyLatRepr>::new(server_a))
+        .lattice_default::<MyLatRepr>());
+    let comp_a = splitter_a.add_split().comp_gossip(vec![ TcpClient::new(addr_b) ], Duration::from_millis(10), fanout);
+    let read_a = splitter_a.add_split();
+
+    let splitter_b = Splitter::new(MergeOp::new(OnceOp::<MyDeltaRepr>::new(items_b), GossipOp::<MyLatRepr>::new(server_b))
+        .lattice_default::<MyLatRepr>());
+    let comp_b = splitter_b.add_split().comp_gossip(vec![ TcpClient::new(addr_a) ], Duration::from_millis(10), fanout);
+    let read_b = splitter_b.add_split();
+
+    let drain = |op| future::poll_fn(move |ctx| {
+        while let Poll::Ready(Some(_)) = OpDelta::poll_delta(op, ctx) {}
+        Poll::<()>::Pending
+    });
+    let converged = async {
+        while expected != read_a.get_value().into_reveal() || expected != read_b.get_value().into_reveal() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    };
+
+    tokio::select! {
+        result = comp_a.run() => Err(format!("{:?}", result)),
+        result = comp_b.run() => Err(format!("{:?}", result)),
+        _ = drain(&read_a) => unreachable!(),
+        _ = drain(&read_b) => unreachable!(),
+        result = tokio::time::timeout(Duration::from_secs(5), converged) => result.map_err(|e| e.to_string()),
+    }
+}
+
+#[tokio::test]
+pub async fn test_gossip() -> Result<(), String> {
+    // Overlapping keys, B's values are larger.
+    let items_a: Vec<(u64, u64)> = (0..60).map(|k| (k, k)).collect();
+    let items_b: Vec<(u64, u64)> = (40..100).map(|k| (k, k + 1)).collect();
+    let expected: HashMap<u64, u64> = (0..100).map(|k| (k, if k < 40 { k } else { k + 1 })).collect();
+    run_gossip(items_a, items_b, expected).await
+}
+
+#[tokio::test]
+pub async fn test_gossip_large() -> Result<(), String> {
+    // Too large for a single frame.
+    let items_a: Vec<(u64, u64)> = (0..10_000).map(|k| (k, k)).collect();
+    let expected: HashMap<u64, u64> = items_a.iter().copied().collect();
+    run_gossip(items_a, Vec::new(), expected).await
+}
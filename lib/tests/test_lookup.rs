@@ -0,0 +1,46 @@
+use spinach::lattice::map_union::MapUnionRepr;
+use spinach::lattice::ord::MaxRepr;
+use spinach::lattice::set_union::SetUnionRepr;
+use spinach::op::{LatticeOp, OpExt};
+use spinach::tag;
+use spinach::testing::{ScriptedOp, Stepper};
+
+type StateLatRepr = MapUnionRepr<tag::HASH_MAP, &'static str, MaxRepr<u32>>;
+type WritesLatRepr = MapUnionRepr<tag::VEC, &'static str, MaxRepr<u32>>;
+type QueriesLatRepr = SetUnionRepr<tag::VEC, (usize, &'static str)>;
+
+#[test]
+pub fn test_lookup_op() -> Result<(), String> {
+    let (writes, write_script) = ScriptedOp::<WritesLatRepr>::new();
+    let (queries, query_script) = ScriptedOp::<QueriesLatRepr>::new();
+    let state = LatticeOp::<_, StateLatRepr>::new_default(writes);
+    let stepper = Stepper::new(queries.lookup(state));
+
+    // Unsubscribed keys aren't emitted.
+    write_script.delta(vec![ ("a", 1), ("b", 2) ]);
+    stepper.assert_pending();
+
+    query_script.delta(vec![ (0, "a"), (1, "a"), (1, "c") ]);
+    let mut answer = stepper.drain().remove(0);
+    answer.sort();
+    assert_eq!(vec![ (0, vec![ ("a", 1) ]), (1, vec![ ("a", 1) ]) ], answer);
+
+    // Subscribed keys are re-emitted when they grow.
+    write_script.delta(vec![ ("c", 3), ("b", 5) ]);
+    assert_eq!(vec![ vec![ (1, vec![ ("c", 3) ]) ] ], stepper.drain());
+
+    // Writes which don't change the state are suppressed upstream.
+    write_script.delta(vec![ ("a", 0) ]);
+    stepper.assert_pending();
+
+    write_script.delta(vec![ ("a", 4) ]);
+    let mut update = stepper.drain().remove(0);
+    update.sort();
+    assert_eq!(vec![ (0, vec![ ("a", 4) ]), (1, vec![ ("a", 4) ]) ], update);
+
+    query_script.close();
+    stepper.assert_pending();
+    write_script.close();
+    stepper.assert_done();
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+use std::task::Poll;
+use std::time::Duration;
+
+use futures::future;
+
+use spinach::comp::CompExt;
+use spinach::lattice::map_union::MapUnionRepr;
+use spinach::lattice::ord::MaxRepr;
+use spinach::merkle::{MerkleSync, MerkleTree};
+use spinach::op::{MergeOp, MerkleSyncOp, OnceOp, OpDelta, OpExt, OpValue, Splitter};
+use spinach::tag;
+use spinach::tcp_client::TcpClient;
+use spinach::tcp_server::TcpServer;
+
+#[test]
+pub fn test_merkle_tree_update() -> Result<(), String> {
+    let depth = 4;
+    let mut entries: HashMap<u64, u64> = (0..100).map(|k| (k, k)).collect();
+    let mut tree = MerkleTree::new(depth, entries.iter());
+
+    // Change existing entries and add new ones.
+    for (key, val) in (50..150).map(|k| (k, 2 * k)) {
+        let old = entries.get(&key).map(|old| MerkleTree::entry_hash(&key, old)).unwrap_or(0);
+        tree.update(MerkleTree::bucket(depth, &key), old, MerkleTree::entry_hash(&key, &val));
+        entries.insert(key, val);
+    }
+
+    let expected = MerkleTree::new(depth, entries.iter());
+    if (0..=depth).any(|level| expected.level(level) != tree.level(level)) {
+        return Err("Updated tree differs from rebuilt tree.".to_owned());
+    }
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn test_merkle_sync() -> Result<(), String> {
+    type MyLatRepr = MapUnionRepr<tag::HASH_MAP, u64, MaxRepr<u64>>;
+    type MyDeltaRepr = MapUnionRepr<tag::VEC, u64, MaxRepr<u64>>;
+
+    let server_a = TcpServer::bind("127.0.0.1:0").await.map_err(|e| e.to_string())?;
+    let server_b = TcpServer::bind("127.0.0.1:0").await.map_err(|e| e.to_string())?;
+    let addr_b = server_b.local_addr().map_err(|e| e.to_string())?;
+
+    // Only A initiates, B responds.
+    let sync_a = MerkleSync::<u64, MaxRepr<u64>>::new(server_a, vec![ TcpClient::new(addr_b) ], 4);
+    let sync_b = MerkleSync::<u64, MaxRepr<u64>>::new(server_b, vec![], 4);
+
+    // Overlapping keys, B's values are larger.
+    let items_a: Vec<(u64, u64)> = (0..600).map(|k| (k, k)).collect();
+    let items_b: Vec<(u64, u64)> = (400..1000).map(|k| (k, k + 1)).collect();
+    let expected: HashMap<u64, u64> = (0..1000).map(|k| (k, if k < 400 { k } else { k + 1 })).collect();
+
+    let splitter_a = Splitter::new(MergeOp::new(OnceOp::<MyDeltaRepr>::new(items_a), MerkleSyncOp::new(sync_a.clone()))
+        .lattice_default::<MyLatRepr>());
+    let comp_a = splitter_a.add_split().comp_merkle_sync(sync_a, Duration::from_millis(10));
+    let read_a = splitter_a.add_split();
+
+    let splitter_b = Splitter::new(MergeOp::new(OnceOp::<MyDeltaRepr>::new(items_b), MerkleSyncOp::new(sync_b.clone()))
+        .lattice_default::<MyLatRepr>());
+    let comp_b = splitter_b.add_split().comp_merkle_sync(sync_b, Duration::from_millis(10));
+    let read_b = splitter_b.add_split();
+
+    let drain = |op| future::poll_fn(move |ctx| {
+        while let Poll::Ready(Some(_)) = OpDelta::poll_delta(op, ctx) {}
+        Poll::<()>::Pending
+    });
+    let converged = async {
+        while expected != read_a.get_value().into_reveal() || expected != read_b.get_value().into_reveal() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    };
+
+    tokio::select! {
+        result = comp_a.run() => Err(format!("{:?}", result)),
+        result = comp_b.run() => Err(format!("{:?}", result)),
+        _ = drain(&read_a) => unreachable!(),
+        _ = drain(&read_b) => unreachable!(),
+        result = tokio::time::timeout(Duration::from_secs(5), converged) => result.map_err(|e| e.to_string()),
+    }
+}
@@ -0,0 +1,72 @@
+use spinach::comp::{CompExt, MetricsComp};
+use spinach::lattice::set_union::SetUnionRepr;
+use spinach::metrics::{Metric, Metrics};
+use spinach::op::{IterOp, OpExt, Splitter};
+use spinach::tag;
+use spinach::testing::{ScriptedOp, Stepper};
+
+type MyLatRepr = SetUnionRepr<tag::HASH_SET, u64>;
+type MyDeltaRepr = SetUnionRepr<tag::VEC, u64>;
+
+fn counter(metrics: &Metrics, name: &str) -> Result<u64, String> {
+    match metrics.get(name) {
+        Some(Metric::Counter(counter)) => Ok(counter.get()),
+        _ => Err(format!("Missing counter: {}.", name)),
+    }
+}
+
+#[tokio::test]
+pub async fn test_metrics() -> Result<(), String> {
+    let metrics = Metrics::new();
+
+    let op = IterOp::<MyDeltaRepr, _>::new(vec![ vec![ 1 ], vec![ 2 ], vec![ 1 ], vec![ 3 ], vec![ 2 ] ])
+        .metrics(&metrics, "input")
+        .lattice_default::<MyLatRepr>();
+    op.register_metrics(&metrics, "state");
+
+    let splitter = Splitter::new(op);
+    splitter.register_metrics(&metrics, "state");
+    let comp = splitter.add_split().comp_null()
+        .join(splitter.add_split().metrics(&metrics, "output").comp_null());
+    comp.run().await.map_err(|e| format!("{:?}", e))?;
+
+    assert_eq!(5, counter(&metrics, "input_deltas_total")?);
+    assert_eq!(2, counter(&metrics, "state_suppressed_total")?);
+    assert_eq!(3, counter(&metrics, "output_deltas_total")?);
+    match metrics.get("state_split_buffered") {
+        Some(Metric::Gauge(gauge)) => assert_eq!(0, gauge.get()),
+        _ => return Err("Missing gauge.".to_owned()),
+    }
+
+    let mut out = Vec::new();
+    let comp = MetricsComp::new(metrics.clone(), &mut out, std::time::Duration::from_secs(1));
+    comp.run_until(async {}).await.map_err(|e| e.to_string())?;
+    drop(comp);
+
+    let text = String::from_utf8(out).map_err(|e| e.to_string())?;
+    assert!(text.contains("# TYPE input_deltas_total counter\ninput_deltas_total 5\n"));
+    assert!(text.contains("input_poll_seconds_count 5\n"));
+    Ok(())
+}
+
+#[test]
+pub fn test_metrics_poll_seconds_pending() -> Result<(), String> {
+    let metrics = Metrics::new();
+
+    let (op, script) = ScriptedOp::<MyDeltaRepr>::new();
+    let stepper = Stepper::new(op.metrics(&metrics, "input"));
+    stepper.assert_pending();
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    script.delta(vec![ 1 ]);
+    stepper.assert_delta::<MyDeltaRepr>(vec![ 1 ]);
+
+    // The wait while pending is included.
+    match metrics.get("input_poll_seconds") {
+        Some(Metric::Histogram(histogram)) => {
+            assert_eq!(1, histogram.count());
+            assert!(histogram.sum() >= 0.05);
+        }
+        _ => return Err("Missing histogram.".to_owned()),
+    }
+    Ok(())
+}
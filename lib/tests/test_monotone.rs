@@ -0,0 +1,45 @@
+use spinach::func::unary::{ClosureMonotone, Monotone};
+use spinach::hide::{Hide, Value};
+use spinach::lattice::ord::MaxRepr;
+use spinach::lattice::set_union::SetUnionRepr;
+use spinach::op::{OpExt, OpValue};
+use spinach::tag;
+use spinach::testing::{ScriptedOp, Stepper};
+
+type VecSet = SetUnionRepr<tag::VEC, u32>;
+type HashSetRepr = SetUnionRepr<tag::HASH_SET, u32>;
+
+#[test]
+pub fn test_monotone_count() -> Result<(), String> {
+    let (op, script) = ScriptedOp::<VecSet>::new();
+    let op = op.monotone_closure(|set: Hide<Value, HashSetRepr>| Hide::<Value, MaxRepr<usize>>::new(set.into_reveal().len()));
+    let stepper = Stepper::new(op);
+
+    script.delta(vec![ 1, 2 ]);
+    stepper.assert_delta::<MaxRepr<usize>>(2);
+
+    // Counting needs the full value, each delta alone has only one item.
+    script.delta(vec![ 3 ]);
+    stepper.assert_delta::<MaxRepr<usize>>(3);
+
+    // No change to the input, no output.
+    script.delta(vec![ 2, 3 ]);
+    stepper.assert_pending();
+    stepper.assert_value::<MaxRepr<usize>>(3);
+
+    script.close();
+    stepper.assert_done();
+    Ok(())
+}
+
+#[test]
+pub fn test_closure_monotone() -> Result<(), String> {
+    let max = ClosureMonotone::new(|set: Hide<Value, VecSet>| {
+        Hide::<Value, MaxRepr<Option<u32>>>::new(set.into_reveal().into_iter().max())
+    });
+    assert_eq!(Some(5), max.call(Hide::new(vec![ 5, 1 ])).into_reveal());
+
+    let op = ScriptedOp::<VecSet>::from_steps(vec![]).monotone(max);
+    assert_eq!(None, op.get_value().into_reveal());
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use std::collections::{BTreeSet, HashSet};
+
+use spinach::func::unary::{Compose, ConvertMorphism, Fst, Identity, MapValues, Morphism, PairMap, Snd};
+use spinach::hide::{Hide, Value};
+use spinach::lattice::map_union::MapUnionRepr;
+use spinach::lattice::pair::PairRepr;
+use spinach::lattice::set_union::SetUnionRepr;
+use spinach::op::OpExt;
+use spinach::tag;
+use spinach::testing::{ScriptedOp, Stepper};
+
+type VecSet = SetUnionRepr<tag::VEC, u32>;
+type BTreeSetRepr = SetUnionRepr<tag::BTREE_SET, u32>;
+type HashSetRepr = SetUnionRepr<tag::HASH_SET, u32>;
+type VecMap = MapUnionRepr<tag::VEC, &'static str, VecSet>;
+
+#[test]
+pub fn test_pair_combinators() -> Result<(), String> {
+    let pair: Hide<Value, PairRepr<VecSet, VecSet>> = Hide::new((vec![ 2, 1 ], vec![ 3 ]));
+
+    let fst = Compose::new(Fst::new(), ConvertMorphism::<VecSet, BTreeSetRepr>::new());
+    let out: BTreeSet<u32> = fst.call(pair.clone()).into_reveal();
+    assert_eq!(vec![ 1, 2 ], out.into_iter().collect::<Vec<_>>());
+
+    let snd = Snd::<VecSet, VecSet>::new();
+    assert_eq!(vec![ 3 ], snd.call(pair.clone()).into_reveal());
+
+    let both = PairMap::new(Identity::<VecSet>::new(), ConvertMorphism::<VecSet, HashSetRepr>::new());
+    let (a, b) = both.call(pair).into_reveal();
+    assert_eq!(vec![ 2, 1 ], a);
+    assert_eq!(vec![ 3 ].into_iter().collect::<HashSet<_>>(), b);
+    Ok(())
+}
+
+#[test]
+pub fn test_fused_morphism_op() -> Result<(), String> {
+    let (op, script) = ScriptedOp::<PairRepr<VecMap, VecSet>>::new();
+
+    // Take the map, and convert each value to a sorted set, in one op.
+    let func = Compose::new(
+        Fst::new(),
+        MapValues::<tag::VEC, _, _>::new(ConvertMorphism::<VecSet, BTreeSetRepr>::new()),
+    );
+    let stepper = Stepper::new(op.morphism(func));
+
+    script.delta((vec![ ("a", vec![ 3, 1 ]), ("b", vec![]) ], vec![ 7 ]));
+    let deltas = stepper.drain();
+    assert_eq!(1, deltas.len());
+    let expected: Vec<(&'static str, BTreeSet<u32>)> = vec![
+        ("a", vec![ 1, 3 ].into_iter().collect()),
+        ("b", BTreeSet::new()),
+    ];
+    assert_eq!(expected, deltas[0]);
+    Ok(())
+}
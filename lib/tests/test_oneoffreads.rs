@@ -19,7 +19,7 @@ pub async fn test_oneoffreads() -> Result<(), String> {
 
         let updater = splitter.add_split().comp_debug("Updater");
         let _updater_task = tokio::task::spawn_local(async move {
-            println!("{:?}", updater.run().await.unwrap_err());
+            println!("{:?}", updater.run().await);
         });
 
         send_write.send(Hide::new(1)).map_err(|e| e.to_string())?;
@@ -30,7 +30,7 @@ pub async fn test_oneoffreads() -> Result<(), String> {
         let read_a = splitter.add_split()
             .topbox()
             .comp_debug("Read A");
-        println!("Read A Done: {:?}", read_a.run().await.unwrap_err());
+        println!("Read A Done: {:?}", read_a.run().await);
 
         send_write.send(Hide::new(4)).map_err(|e| e.to_string())?;
         tokio::task::yield_now().await;
@@ -39,7 +39,7 @@ pub async fn test_oneoffreads() -> Result<(), String> {
         let read_b = splitter.add_split()
             .topbox()
             .comp_debug("Read B");
-        println!("Read B Done: {:?}", read_b.run().await.unwrap_err());
+        println!("Read B Done: {:?}", read_b.run().await);
 
         tokio::task::yield_now().await;
         let _ = _updater_task;
@@ -0,0 +1,71 @@
+use std::collections::HashSet;
+
+use futures::future;
+use serde::Deserialize;
+
+use spinach::collections::Single;
+use spinach::func::unary::{ParseCsv, ParseJson};
+use spinach::lattice::set_union::SetUnionRepr;
+use spinach::op::{IterOp, OpDelta, OpExt, OpValue};
+use spinach::tag;
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+struct Row {
+    name: String,
+    count: u32,
+}
+
+async fn drain<O: OpDelta + OpValue>(op: O) -> O {
+    while future::poll_fn(|ctx| op.poll_delta(ctx)).await.is_some() {}
+    op
+}
+
+#[tokio::test]
+pub async fn test_parse_json() -> Result<(), String> {
+    let lines = vec![ r#"{ "name": "a", "count": 1 }"#, r#"{ "name": "b" }"#, r#"{ "name": "c", "count": 3 }"# ];
+
+    let (op_rows, op_errors) = IterOp::<SetUnionRepr<tag::SINGLE, String>, _>::new(lines.into_iter().map(|line| Single(line.to_owned())))
+        .morphism(ParseJson::<Row>::new())
+        .switch();
+    let op_rows = op_rows.lattice_default::<SetUnionRepr<tag::HASH_SET, Row>>();
+    let op_errors = op_errors.lattice_default::<SetUnionRepr<tag::VEC, _>>();
+
+    let (op_rows, op_errors) = future::join(drain(op_rows), drain(op_errors)).await;
+
+    let expected: HashSet<Row> = vec![
+        Row { name: "a".to_owned(), count: 1 },
+        Row { name: "c".to_owned(), count: 3 },
+    ].into_iter().collect();
+    assert_eq!(expected, op_rows.get_value().into_reveal());
+
+    let errors = op_errors.get_value().into_reveal();
+    assert_eq!(1, errors.len());
+    assert_eq!(r#"{ "name": "b" }"#, errors[0].input);
+
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn test_parse_csv() -> Result<(), String> {
+    let lines = vec![ "3,c", "x,y", "1,a" ];
+
+    let (op_rows, op_errors) = IterOp::<SetUnionRepr<tag::SINGLE, String>, _>::new(lines.into_iter().map(|line| Single(line.to_owned())))
+        .morphism(ParseCsv::<Row>::new_with_headers(vec![ "count", "name" ]))
+        .switch();
+    let op_rows = op_rows.lattice_default::<SetUnionRepr<tag::HASH_SET, Row>>();
+    let op_errors = op_errors.lattice_default::<SetUnionRepr<tag::VEC, _>>();
+
+    let (op_rows, op_errors) = future::join(drain(op_rows), drain(op_errors)).await;
+
+    let expected: HashSet<Row> = vec![
+        Row { name: "a".to_owned(), count: 1 },
+        Row { name: "c".to_owned(), count: 3 },
+    ].into_iter().collect();
+    assert_eq!(expected, op_rows.get_value().into_reveal());
+
+    let errors = op_errors.get_value().into_reveal();
+    assert_eq!(1, errors.len());
+    assert_eq!("x,y", errors[0].input);
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use std::collections::BTreeMap;
+
+use spinach::func::binary::{self, BinaryMorphism, CartesianProduct};
+use spinach::func::unary::{self, Identity, Morphism};
+use spinach::hide::{Hide, Value};
+use spinach::lattice::map_union::MapUnionRepr;
+use spinach::lattice::set_union::SetUnionRepr;
+use spinach::tag;
+
+type VecSet = SetUnionRepr<tag::VEC, u32>;
+type VecMap = MapUnionRepr<tag::VEC, u32, VecSet>;
+type BTreeMapRepr = MapUnionRepr<tag::BTREE_MAP, u32, VecSet>;
+
+#[test]
+pub fn test_btree_partitioned() -> Result<(), String> {
+    let item: Hide<Value, VecMap> = Hide::new(vec![ (30, vec![ 3 ]), (10, vec![ 1 ]), (20, vec![ 2 ]) ]);
+
+    let func = unary::BTreePartitioned::<VecMap, _, _>::new(Identity::<VecSet>::new());
+    let out = func.call(item).into_reveal();
+    assert_eq!(vec![ 10, 20, 30 ], out.into_keys().collect::<Vec<_>>());
+    Ok(())
+}
+
+#[test]
+pub fn test_btree_partitioned_binary() -> Result<(), String> {
+    let a: Hide<Value, BTreeMapRepr> = Hide::new(vec![ (3, vec![ 30 ]), (1, vec![ 10 ]), (2, vec![ 20 ]) ].into_iter().collect());
+    let b: Hide<Value, BTreeMapRepr> = Hide::new(vec![ (2, vec![ 21 ]), (3, vec![ 31 ]), (4, vec![ 41 ]) ].into_iter().collect());
+
+    let func = binary::BTreePartitioned::new(CartesianProduct::<VecSet, u32, VecSet, u32, SetUnionRepr<tag::VEC, (u32, u32)>>::new());
+    let out = func.call(a, b).into_reveal();
+    assert_eq!(vec![ 2, 3 ], out.keys().copied().collect::<Vec<_>>());
+    assert_eq!(vec![ (20, 21) ], out[&2]);
+    Ok(())
+}
+
+#[test]
+pub fn test_range_partitioned() -> Result<(), String> {
+    let item: Hide<Value, VecMap> = Hide::new(vec![ (25, vec![ 2 ]), (5, vec![ 0 ]), (10, vec![ 1 ]), (40, vec![ 4 ]), (15, vec![ 1 ]) ]);
+
+    // Ranges: [..10), [10..20), [20..30), [30..).
+    let func = unary::RangePartitioned::<VecMap, _, _>::new(vec![ 10, 20, 30 ], Identity::<VecSet>::new());
+    assert_eq!(0, func.partition(&9));
+    assert_eq!(1, func.partition(&10));
+    assert_eq!(3, func.partition(&100));
+
+    let out = func.call(item).into_reveal();
+    let shards: BTreeMap<usize, Vec<u32>> = out.into_iter()
+        .map(|(i, shard)| (i, shard.into_keys().collect()))
+        .collect();
+    let expected: BTreeMap<usize, Vec<u32>> = vec![
+        (0, vec![ 5 ]),
+        (1, vec![ 10, 15 ]),
+        (2, vec![ 25 ]),
+        (3, vec![ 40 ]),
+    ].into_iter().collect();
+    assert_eq!(expected, shards);
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use std::collections::HashSet;
+use std::io::Write;
+
+use futures::future;
+
+use spinach::lattice::set_union::SetUnionRepr;
+use spinach::op::{IterOp, OpDelta, OpExt, OpValue};
+use spinach::tag;
+
+#[tokio::test]
+pub async fn test_persistence_recover() -> Result<(), String> {
+    type MyLatRepr = SetUnionRepr<tag::HASH_SET, u64>;
+    type MyDeltaRepr = SetUnionRepr<tag::VEC, u64>;
+
+    let dir = std::env::temp_dir().join(format!("spinach_test_persistence_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let deltas: Vec<Vec<u64>> = (0..10).map(|i| vec![ 2 * i, 2 * i + 1 ]).collect();
+    let expected: HashSet<u64> = (0..20).collect();
+
+    {
+        // Snapshots after 4 and 8 deltas, leaving 2 deltas in the log.
+        let op = IterOp::<MyDeltaRepr, _>::new(deltas)
+            .lattice_default::<MyLatRepr>()
+            .persist_with_snapshot_every(&dir, 4)
+            .map_err(|e| e.to_string())?;
+        while future::poll_fn(|ctx| op.poll_delta(ctx)).await.is_some() {}
+        assert_eq!(expected, op.get_value().into_reveal());
+    }
+
+    let op = IterOp::<MyDeltaRepr, _>::new(vec![])
+        .lattice_default::<MyLatRepr>()
+        .persist(&dir)
+        .map_err(|e| e.to_string())?;
+    assert_eq!(expected, op.get_value().into_reveal());
+
+    // The recovered state is emitted downstream.
+    let recovered = future::poll_fn(|ctx| op.poll_delta(ctx)).await
+        .ok_or("Recovered state not emitted.")?;
+    assert_eq!(expected, recovered.into_reveal());
+    assert!(future::poll_fn(|ctx| op.poll_delta(ctx)).await.is_none());
+
+    std::fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn test_persistence_torn_wal() -> Result<(), String> {
+    type MyLatRepr = SetUnionRepr<tag::HASH_SET, u64>;
+    type MyDeltaRepr = SetUnionRepr<tag::VEC, u64>;
+
+    let dir = std::env::temp_dir().join(format!("spinach_test_persistence_torn_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let deltas: Vec<Vec<u64>> = (0..5).map(|i| vec![ i ]).collect();
+    let expected: HashSet<u64> = (0..5).collect();
+
+    {
+        // Every delta stays in the log.
+        let op = IterOp::<MyDeltaRepr, _>::new(deltas)
+            .lattice_default::<MyLatRepr>()
+            .persist(&dir)
+            .map_err(|e| e.to_string())?;
+        while future::poll_fn(|ctx| op.poll_delta(ctx)).await.is_some() {}
+    }
+
+    // Simulate a crash partway through writing a delta of five elements.
+    let wal_path = dir.join("wal");
+    let wal_len = std::fs::metadata(&wal_path).map_err(|e| e.to_string())?.len();
+    {
+        let mut wal = std::fs::OpenOptions::new().append(true).open(&wal_path).map_err(|e| e.to_string())?;
+        wal.write_all(&[ 5, 0, 0, 0, 0, 0, 0, 0, 42, 0, 0 ]).map_err(|e| e.to_string())?;
+    }
+
+    let op = IterOp::<MyDeltaRepr, _>::new(vec![])
+        .lattice_default::<MyLatRepr>()
+        .persist(&dir)
+        .map_err(|e| e.to_string())?;
+    assert_eq!(expected, op.get_value().into_reveal());
+    assert_eq!(wal_len, std::fs::metadata(&wal_path).map_err(|e| e.to_string())?.len());
+    drop(op);
+
+    std::fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(())
+}
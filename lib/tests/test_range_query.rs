@@ -0,0 +1,84 @@
+use std::collections::BTreeMap;
+use std::ops::Bound;
+
+use spinach::hide::{Hide, Value};
+use spinach::lattice::map_union::MapUnionRepr;
+use spinach::lattice::ord::MaxRepr;
+use spinach::lattice::set_union::SetUnionRepr;
+use spinach::op::{KeyRange, LatticeOp, OpExt};
+use spinach::tag;
+use spinach::testing::{ScriptedOp, Stepper};
+
+type StateLatRepr = MapUnionRepr<tag::BTREE_MAP, String, MaxRepr<u32>>;
+type WritesLatRepr = MapUnionRepr<tag::VEC, String, MaxRepr<u32>>;
+type QueriesLatRepr = SetUnionRepr<tag::VEC, (usize, KeyRange<String>)>;
+
+#[test]
+pub fn test_hide_range() -> Result<(), String> {
+    let state: BTreeMap<String, u32> = vec![ ("apple", 1), ("apricot", 2), ("banana", 3), ("b", 4) ]
+        .into_iter()
+        .map(|(k, v)| (k.to_owned(), v))
+        .collect();
+    let state: Hide<Value, StateLatRepr> = Hide::new(state);
+
+    let range = state.range("apricot".to_owned()..="b".to_owned()).into_reveal();
+    assert_eq!(vec![ ("apricot".to_owned(), 2), ("b".to_owned(), 4) ], range);
+
+    let prefix = state.prefix("ap").into_reveal();
+    assert_eq!(vec![ ("apple".to_owned(), 1), ("apricot".to_owned(), 2) ], prefix);
+    assert_eq!(prefix, state.range(KeyRange::prefix("ap")).into_reveal());
+    assert_eq!(4, state.prefix("").into_reveal().len());
+
+    // Empty ranges which `BTreeMap::range` would panic on.
+    let reversed = KeyRange { start: Bound::Included("b".to_owned()), end: Bound::Excluded("a".to_owned()) };
+    assert!(state.range(reversed).into_reveal().is_empty());
+    let excluded = KeyRange { start: Bound::Excluded("b".to_owned()), end: Bound::Excluded("b".to_owned()) };
+    assert!(state.range(excluded).into_reveal().is_empty());
+    Ok(())
+}
+
+#[test]
+pub fn test_key_range_prefix() -> Result<(), String> {
+    let range = KeyRange::prefix("a\u{10FFFF}");
+    assert_eq!(std::ops::Bound::Excluded("b".to_owned()), range.end);
+    let range = KeyRange::prefix("\u{D7FF}");
+    assert_eq!(std::ops::Bound::Excluded("\u{E000}".to_owned()), range.end);
+    Ok(())
+}
+
+#[test]
+pub fn test_range_query_op() -> Result<(), String> {
+    let (writes, write_script) = ScriptedOp::<WritesLatRepr>::new();
+    let (queries, query_script) = ScriptedOp::<QueriesLatRepr>::new();
+    let state = LatticeOp::<_, StateLatRepr>::new_default(writes);
+    let stepper = Stepper::new(queries.range_query(state));
+
+    write_script.delta(vec![ ("a".to_owned(), 1), ("b".to_owned(), 2), ("c".to_owned(), 3) ]);
+    stepper.assert_pending();
+
+    query_script.delta(vec![ (0, KeyRange::new("b".to_owned()..)), (1, KeyRange::prefix("z")) ]);
+    write_script.delta(vec![ ("b".to_owned(), 5) ]);
+    let deltas = stepper.drain();
+    assert_eq!(1, deltas.len());
+    assert_eq!(
+        vec![
+            (0, vec![ ("b".to_owned(), 5), ("c".to_owned(), 3) ]),
+            (1, vec![]),
+        ],
+        deltas[0]);
+
+    // Queries are answered once, later writes aren't sent.
+    write_script.delta(vec![ ("d".to_owned(), 4) ]);
+    stepper.assert_pending();
+
+    // Reversed and empty ranges get empty results.
+    query_script.delta(vec![
+        (2, KeyRange { start: Bound::Included("c".to_owned()), end: Bound::Included("a".to_owned()) }),
+        (3, KeyRange { start: Bound::Excluded("b".to_owned()), end: Bound::Excluded("b".to_owned()) }),
+    ]);
+    assert_eq!(vec![ vec![ (2, vec![]), (3, vec![]) ] ], stepper.drain());
+
+    query_script.close();
+    stepper.assert_done();
+    Ok(())
+}
@@ -36,7 +36,7 @@ pub async fn test_shj_constructive() -> Result<(), String> {
     // let op = SymHashJoinOp::new(op_a, op_b);
     let comp = DebugComp::new(op, "output");
 
-    comp.run().await.unwrap_err();
+    comp.run().await.unwrap();
 
     Ok(())
 }
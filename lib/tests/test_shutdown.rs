@@ -0,0 +1,56 @@
+use std::collections::HashSet;
+
+use futures::future;
+use tokio::sync::mpsc;
+
+use spinach::comp::{CancellationToken, CompExt};
+use spinach::format::JsonLines;
+use spinach::hide::Hide;
+use spinach::lattice::set_union::SetUnionRepr;
+use spinach::op::{ChannelOp, FileOp, OpDelta, OpExt, OpValue};
+use spinach::tag;
+
+type MyLatRepr = SetUnionRepr<tag::HASH_SET, u64>;
+type MyDeltaRepr = SetUnionRepr<tag::VEC, u64>;
+
+#[tokio::test]
+pub async fn test_shutdown_drains() -> Result<(), String> {
+    let path = std::env::temp_dir().join(format!("spinach_test_shutdown_{}", std::process::id()));
+
+    let (send, recv) = mpsc::unbounded_channel();
+    let file = tokio::fs::File::create(&path).await.map_err(|e| e.to_string())?;
+    let comp = ChannelOp::<MyDeltaRepr>::new(recv)
+        .comp_file::<JsonLines>(file);
+
+    for i in 0..5 {
+        send.send(Hide::new(vec![ i ])).map_err(|e| e.to_string())?;
+    }
+
+    // The sender is still open, so only the shutdown ends the comp.
+    let token = CancellationToken::new();
+    token.cancel();
+    comp.run_until(token.cancelled()).await.map_err(|e| e.to_string())?;
+
+    let op = FileOp::<JsonLines, MyDeltaRepr>::open(&path).await.map_err(|e| e.to_string())?
+        .lattice_default::<MyLatRepr>();
+    while future::poll_fn(|ctx| op.poll_delta(ctx)).await.is_some() {}
+
+    let expected: HashSet<u64> = (0..5).collect();
+    assert_eq!(expected, op.get_value().into_reveal());
+
+    drop(send);
+    tokio::fs::remove_file(&path).await.map_err(|e| e.to_string())
+}
+
+#[tokio::test]
+pub async fn test_shutdown_complete() -> Result<(), String> {
+    let (send, recv) = mpsc::unbounded_channel::<Hide<_, MyDeltaRepr>>();
+    let comp = ChannelOp::<MyDeltaRepr>::new(recv)
+        .comp_null();
+
+    send.send(Hide::new(vec![ 1, 2, 3 ])).map_err(|e| e.to_string())?;
+    drop(send);
+
+    // Completes once the input ends, without a shutdown.
+    comp.run().await.map_err(|e| format!("{:?}", e))
+}
@@ -0,0 +1,109 @@
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::net::SocketAddr;
+use std::rc::Rc;
+
+use spinach::comp::{CancellationToken, Comp, CompExt};
+use spinach::lattice::set_union::SetUnionRepr;
+use spinach::op::{IterOp, MergeOp, OpExt, SimOp};
+use spinach::sim_net::{SimConfig, SimNetwork, SimStats};
+use spinach::tag;
+
+type MyLatRepr = SetUnionRepr<tag::BTREE_SET, u64>;
+
+/// A replica which floods new values to its peers, and records its state.
+fn replica(net: &SimNetwork, addr: SocketAddr, peers: Vec<SocketAddr>, writes: Vec<u64>)
+    -> Result<(impl Comp, Rc<RefCell<BTreeSet<u64>>>), String>
+{
+    let server = net.bind(addr).map_err(|e| e.to_string())?;
+    let writes = IterOp::<MyLatRepr, _>::new(writes.into_iter().map(|x| vec![ x ].into_iter().collect()));
+
+    let splitter = MergeOp::new(writes, SimOp::<MyLatRepr>::new(server.clone()))
+        .lattice_default::<MyLatRepr>()
+        .dyn_split();
+
+    let state: Rc<RefCell<BTreeSet<u64>>> = Default::default();
+    let state_ref = state.clone();
+    let comp = splitter.add_split().comp_sim(server, peers)
+        .join(splitter.add_split()
+            .morphism_closure::<MyLatRepr, _>(move |delta| {
+                state_ref.borrow_mut().extend(delta.reveal_ref().iter().copied());
+                delta
+            })
+            .comp_null());
+    Ok((comp, state))
+}
+
+/// Run REPLICAS with the given writes until the network is idle.
+async fn run_replicas(net: &SimNetwork, writes: Vec<Vec<u64>>) -> Result<Vec<BTreeSet<u64>>, String> {
+    let addrs: Vec<SocketAddr> = (0..writes.len())
+        .map(|i| format!("10.0.0.{}:80", i).parse().unwrap())
+        .collect();
+
+    let mut comps = Vec::new();
+    let mut states = Vec::new();
+    for (i, writes) in writes.into_iter().enumerate() {
+        let peers = addrs.iter().copied().filter(|&addr| addr != addrs[i]).collect();
+        let (comp, state) = replica(net, addrs[i], peers, writes)?;
+        comps.push(comp);
+        states.push(state);
+    }
+
+    let token = CancellationToken::new();
+    let (result, ()) = tokio::join!(
+        comps.run_until(token.cancelled()),
+        async {
+            net.run_until_idle().await;
+            token.cancel();
+        });
+    result.map_err(|e| format!("{:?}", e))?;
+
+    Ok(states.into_iter().map(|state| state.take()).collect())
+}
+
+#[tokio::test]
+pub async fn test_sim_net_converges() -> Result<(), String> {
+    let net = SimNetwork::new(1, SimConfig {
+        duplicate_rate: 0.3,
+        min_delay: 1,
+        max_delay: 5,
+        ..Default::default()
+    });
+    let states = run_replicas(&net, vec![ vec![ 1, 2 ], vec![ 3 ], vec![ 4, 5, 6 ] ]).await?;
+
+    let expected: BTreeSet<u64> = (1..=6).collect();
+    for state in states {
+        assert_eq!(expected, state);
+    }
+    assert!(0 < net.stats().duplicated);
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn test_sim_net_partition() -> Result<(), String> {
+    let net = SimNetwork::new(2, Default::default());
+    net.partition(&[ "10.0.0.2:80".parse().unwrap() ]);
+    let states = run_replicas(&net, vec![ vec![ 1 ], vec![ 2 ], vec![ 3 ] ]).await?;
+
+    assert_eq!(vec![ 1, 2 ], states[0].iter().copied().collect::<Vec<_>>());
+    assert_eq!(vec![ 1, 2 ], states[1].iter().copied().collect::<Vec<_>>());
+    assert_eq!(vec![ 3 ], states[2].iter().copied().collect::<Vec<_>>());
+    assert!(0 < net.stats().dropped);
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn test_sim_net_deterministic() -> Result<(), String> {
+    async fn run(seed: u64) -> Result<SimStats, String> {
+        let net = SimNetwork::new(seed, SimConfig {
+            drop_rate: 0.2,
+            duplicate_rate: 0.2,
+            min_delay: 1,
+            max_delay: 3,
+        });
+        run_replicas(&net, vec![ vec![ 1, 2, 3 ], vec![ 4, 5 ], vec![ 6 ] ]).await?;
+        Ok(net.stats())
+    }
+    assert_eq!(run(7).await?, run(7).await?);
+    Ok(())
+}
@@ -46,7 +46,7 @@ pub async fn test_split_merge() -> Result<(), String> {
 
     let comp = DebugComp::new(merge, "output");
 
-    comp.run().await.unwrap_err();
+    comp.run().await.unwrap();
 
     Ok(())
 }
@@ -39,7 +39,7 @@ pub async fn test_switch() -> Result<(), String> {
     let merge = MergeOp::new(comp_a, comp_b);
     let comp = NullComp::new(merge);
 
-    comp.run().await.unwrap_err();
+    comp.run().await.unwrap();
 
     Ok(())
 }
@@ -0,0 +1,192 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use spinach::collections::{Collection, MaskedArray, SmallVecMap, SmallVecSet, SortedVec, SortedVecMap};
+use spinach::lattice::{Compare, Convert, Merge, TryMerge};
+use spinach::lattice::map_union::MapUnionRepr;
+use spinach::lattice::set_union::SetUnionRepr;
+use spinach::op::{LatticeOp, OpValue};
+use spinach::tag;
+use spinach::testing::{ScriptedOp, Stepper};
+
+type SortedSet = SetUnionRepr<tag::SORTED_VEC, u32>;
+type SmallSet = SetUnionRepr<tag::SMALL_VEC<4>, u32>;
+type HashSetRepr = SetUnionRepr<tag::HASH_SET, u32>;
+type VecSet = SetUnionRepr<tag::VEC, u32>;
+
+#[test]
+pub fn test_sorted_vec_set() -> Result<(), String> {
+    let mut this: SortedVec<u32> = vec![ 5, 1, 3, 1 ].into_iter().collect();
+    assert_eq!(&[ 1, 3, 5 ], this.as_slice());
+
+    assert!(<SortedSet as Merge<SortedSet>>::merge(&mut this, vec![ 4, 2, 3 ].into_iter().collect()));
+    assert_eq!(&[ 1, 2, 3, 4, 5 ], this.as_slice());
+    assert!(!<SortedSet as Merge<HashSetRepr>>::merge(&mut this, vec![ 2, 5 ].into_iter().collect()));
+    assert!(<SortedSet as Merge<VecSet>>::merge(&mut this, vec![ 9, 7 ]));
+    assert_eq!(&[ 1, 2, 3, 4, 5, 7, 9 ], this.as_slice());
+
+    assert!(this.get(&4).is_some());
+    assert!(this.get(&6).is_none());
+
+    let hash_set: HashSet<u32> = <SortedSet as Convert<HashSetRepr>>::convert(this.clone());
+    assert_eq!(Some(std::cmp::Ordering::Equal), <SortedSet as Compare<HashSetRepr>>::compare(&this, &hash_set));
+
+    let bytes = bincode::serialize(&vec![ 3, 1, 2 ]).map_err(|e| e.to_string())?;
+    let decoded: SortedVec<u32> = bincode::deserialize(&bytes).map_err(|e| e.to_string())?;
+    assert_eq!(&[ 1, 2, 3 ], decoded.as_slice());
+    Ok(())
+}
+
+#[test]
+pub fn test_small_vec_set() -> Result<(), String> {
+    let mut this: SmallVecSet<u32, 4> = Default::default();
+    assert!(<SmallSet as Merge<SortedSet>>::merge(&mut this, vec![ 1, 2 ].into_iter().collect()));
+    assert!(!this.spilled());
+
+    // Existing elements are not added again.
+    assert!(!<SmallSet as Merge<VecSet>>::merge(&mut this, vec![ 2, 1, 2 ]));
+    assert_eq!(&[ 1, 2 ], this.as_slice());
+
+    assert!(<SmallSet as Merge<SmallSet>>::merge(&mut this, vec![ 3, 4, 5, 3 ].into_iter().collect()));
+    assert!(this.spilled());
+    assert_eq!(&[ 1, 2, 3, 4, 5 ], this.as_slice());
+
+    let btree: BTreeSet<u32> = <SmallSet as Convert<SetUnionRepr<tag::BTREE_SET, u32>>>::convert(this);
+    assert_eq!((1..=5).collect::<BTreeSet<u32>>(), btree);
+    Ok(())
+}
+
+#[test]
+pub fn test_sorted_vec_map() -> Result<(), String> {
+    type SortedMap = MapUnionRepr<tag::SORTED_VEC, &'static str, SortedSet>;
+
+    let mut this: SortedVecMap<&'static str, SortedVec<u32>> = vec![
+        ("b", vec![ 1 ].into_iter().collect()),
+        ("a", vec![ 2 ].into_iter().collect()),
+    ].into_iter().collect();
+
+    assert!(<SortedMap as Merge<SortedMap>>::merge(&mut this, vec![
+        ("c", vec![ 3 ].into_iter().collect()),
+        ("a", vec![ 4 ].into_iter().collect()),
+    ].into_iter().collect()));
+
+    let keys: Vec<_> = this.keys().copied().collect();
+    assert_eq!(vec![ "a", "b", "c" ], keys);
+    assert_eq!(Some(&[ 2, 4 ][..]), this.get(&"a").map(|set| set.as_slice()));
+    Ok(())
+}
+
+#[test]
+pub fn test_small_vec_map() -> Result<(), String> {
+    type SmallMap = MapUnionRepr<tag::SMALL_VEC<2>, &'static str, SmallSet>;
+    type VecMap = MapUnionRepr<tag::VEC, &'static str, VecSet>;
+
+    let mut this: SmallVecMap<&'static str, SmallVecSet<u32, 4>, 2> = Default::default();
+    // The delta repeats "a", both values are merged into one entry.
+    assert!(<SmallMap as Merge<VecMap>>::merge(&mut this, vec![ ("a", vec![ 1 ]), ("b", vec![ 2 ]), ("a", vec![ 3 ]) ]));
+    assert_eq!(2, this.len());
+    assert_eq!(Some(&[ 1, 3 ][..]), this.get(&"a").map(|set| set.as_slice()));
+
+    assert!(!<SmallMap as Merge<VecMap>>::merge(&mut this, vec![ ("b", vec![ 2 ]), ("a", vec![ 3, 1 ]) ]));
+    assert!(<SmallMap as Merge<VecMap>>::merge(&mut this, vec![ ("c", vec![ 4 ]), ("c", vec![ 5 ]) ]));
+    assert!(this.spilled());
+    assert_eq!(vec![ "a", "b", "c" ], this.keys().copied().collect::<Vec<_>>());
+    assert_eq!(Some(&[ 4, 5 ][..]), this.get(&"c").map(|set| set.as_slice()));
+    Ok(())
+}
+
+#[test]
+pub fn test_bitmap_set() -> Result<(), String> {
+    type BitmapSet = SetUnionRepr<tag::BITMAP, u32>;
+
+    let mut this = roaring::RoaringBitmap::new();
+    assert!(<BitmapSet as Merge<VecSet>>::merge(&mut this, vec![ 1, 2, 3 ]));
+    assert!(!<BitmapSet as Merge<HashSetRepr>>::merge(&mut this, vec![ 2, 3 ].into_iter().collect()));
+    assert!(<BitmapSet as Merge<BitmapSet>>::merge(&mut this, (100..200).collect()));
+    assert_eq!(103, this.len());
+
+    let subset: roaring::RoaringBitmap = (100..150).collect();
+    assert_eq!(Some(std::cmp::Ordering::Greater), <BitmapSet as Compare<BitmapSet>>::compare(&this, &subset));
+    assert_eq!(Some(std::cmp::Ordering::Less), <BitmapSet as Compare<BitmapSet>>::compare(&subset, &this));
+    assert_eq!(None, <BitmapSet as Compare<VecSet>>::compare(&subset, &vec![ 1, 2 ]));
+
+    let hash_set: HashSet<u32> = <BitmapSet as Convert<HashSetRepr>>::convert(this.clone());
+    assert_eq!(Some(std::cmp::Ordering::Equal), <HashSetRepr as Compare<BitmapSet>>::compare(&hash_set, &this));
+    let back: roaring::RoaringBitmap = <HashSetRepr as Convert<BitmapSet>>::convert(hash_set);
+    assert_eq!(this, back);
+    Ok(())
+}
+
+#[test]
+pub fn test_masked_array_try_merge() -> Result<(), String> {
+    type MaskedSet = SetUnionRepr<tag::MASKED_ARRAY<3>, u32>;
+
+    let mut this = MaskedArray { mask: [ false; 3 ], vals: [ 0; 3 ] };
+    assert_eq!((true, None), <MaskedSet as TryMerge<VecSet>>::try_merge(&mut this, vec![ 1, 2 ]));
+    assert_eq!((false, None), <MaskedSet as TryMerge<VecSet>>::try_merge(&mut this, vec![ 2, 1 ]));
+    // Part of the delta fits, THIS changes and the rest overflows.
+    assert_eq!((true, Some(vec![ 5 ])), <MaskedSet as TryMerge<VecSet>>::try_merge(&mut this, vec![ 4, 2, 5 ]));
+    assert_eq!(Some(std::cmp::Ordering::Equal), <MaskedSet as Compare<VecSet>>::compare(&this, &vec![ 1, 2, 4 ]));
+    // None of the delta fits, THIS is unchanged.
+    assert_eq!((false, Some(vec![ 6 ])), <MaskedSet as TryMerge<VecSet>>::try_merge(&mut this, vec![ 6, 1 ]));
+    Ok(())
+}
+
+#[test]
+pub fn test_masked_array_map_try_merge() -> Result<(), String> {
+    // Per-node flags, with room for two nodes.
+    type Flags = SetUnionRepr<tag::HASH_SET, &'static str>;
+    type MaskedMap = MapUnionRepr<tag::MASKED_ARRAY<2>, u32, Flags>;
+    type VecMap = MapUnionRepr<tag::VEC, u32, Flags>;
+    type HashMapRepr = MapUnionRepr<tag::HASH_MAP, u32, Flags>;
+
+    let mut this = MaskedArray { mask: [ false; 2 ], vals: [ (0, HashSet::new()), (0, HashSet::new()) ] };
+    let delta = vec![ (1, vec![ "up" ].into_iter().collect()) ];
+    assert_eq!((true, None), <MaskedMap as TryMerge<VecMap>>::try_merge(&mut this, delta));
+    let delta = vec![ (1, vec![ "up", "leader" ].into_iter().collect()), (2, vec![ "up" ].into_iter().collect()) ];
+    assert_eq!((true, None), <MaskedMap as TryMerge<VecMap>>::try_merge(&mut this, delta));
+    let delta = vec![ (2, vec![ "up" ].into_iter().collect()) ];
+    assert_eq!((false, None), <MaskedMap as TryMerge<VecMap>>::try_merge(&mut this, delta));
+
+    // No room for node 3, it overflows and can be spilled into a larger map.
+    // Node 2's update still fits.
+    let delta = vec![ (2, vec![ "leader" ].into_iter().collect()), (3, vec![ "up" ].into_iter().collect()) ];
+    let (changed, overflow) = <MaskedMap as TryMerge<VecMap>>::try_merge(&mut this, delta);
+    assert!(changed);
+    let overflow = overflow.expect("Node 3 should overflow.");
+    let mut spill: HashMap<u32, HashSet<&'static str>> = <MaskedMap as Convert<HashMapRepr>>::convert(this.clone());
+    assert!(<HashMapRepr as Merge<VecMap>>::merge(&mut spill, overflow));
+    assert_eq!(3, spill.len());
+    assert_eq!(2, spill[&1].len());
+    assert_eq!(2, spill[&2].len());
+    Ok(())
+}
+
+#[test]
+pub fn test_im_map() -> Result<(), String> {
+    type ImSet = SetUnionRepr<tag::IM_HASH_SET, u32>;
+    type ImMap = MapUnionRepr<tag::IM_HASH_MAP, &'static str, ImSet>;
+    type ImOrdMap = MapUnionRepr<tag::IM_BTREE_MAP, &'static str, ImSet>;
+    type HashMapSet = MapUnionRepr<tag::HASH_MAP, &'static str, HashSetRepr>;
+    type VecMap = MapUnionRepr<tag::VEC, &'static str, VecSet>;
+
+    let (op, script) = ScriptedOp::<VecMap>::new();
+    let stepper = Stepper::new(LatticeOp::<_, ImMap>::new_default(op));
+    script.delta(vec![ ("a", vec![ 1, 2 ]), ("b", vec![ 3 ]) ]);
+    script.delta(vec![ ("a", vec![ 4 ]) ]);
+    assert_eq!(2, stepper.drain().len());
+
+    // Reading the value shares structure with the op's state.
+    let value = stepper.op().get_value().into_reveal();
+    assert!(value.ptr_eq(&stepper.op().get_value().into_reveal()));
+    assert_eq!(3, value["a"].len());
+
+    let hash_map: HashMap<&'static str, HashSet<u32>> = <ImMap as Convert<HashMapSet>>::convert(value.clone());
+    assert_eq!(Some(std::cmp::Ordering::Equal), <HashMapSet as Compare<ImMap>>::compare(&hash_map, &value));
+
+    let mut ord_map: im::OrdMap<&'static str, im::HashSet<u32>> = <ImMap as Convert<ImOrdMap>>::convert(value.clone());
+    assert!(<ImOrdMap as Merge<HashMapSet>>::merge(&mut ord_map, vec![ ("c", vec![ 5 ].into_iter().collect()) ].into_iter().collect()));
+    assert!(!<ImOrdMap as Merge<ImMap>>::merge(&mut ord_map, value.clone()));
+    assert_eq!(Some(std::cmp::Ordering::Greater), <ImOrdMap as Compare<ImMap>>::compare(&ord_map, &value));
+    assert_eq!(vec![ "a", "b", "c" ], ord_map.keys().copied().collect::<Vec<_>>());
+    Ok(())
+}
@@ -0,0 +1,158 @@
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use futures::future;
+use tokio::sync::mpsc;
+
+use spinach::comp::{Comp, CompExt};
+use spinach::error::SpinachError;
+use spinach::lattice::set_union::SetUnionRepr;
+use spinach::op::{OpDelta, OpExt, TcpClientOp, TcpServerOp};
+use spinach::tag;
+use spinach::tcp_client::TcpClient;
+use spinach::tcp_server::TcpServer;
+use spinach::testing::ScriptedOp;
+
+type MyLatRepr = SetUnionRepr<tag::BTREE_SET, u32>;
+
+/// Receive deltas until one contains ITEM.
+async fn recv_until(op: &TcpServerOp<MyLatRepr>, item: u32) -> Vec<BTreeSet<u32>> {
+    let mut received = Vec::new();
+    while !received.last().map(|delta: &BTreeSet<u32>| delta.contains(&item)).unwrap_or(false) {
+        let delta = future::poll_fn(|ctx| op.poll_delta(ctx)).await
+            .expect("TcpServerOp does not end.");
+        received.push((delta.into_reveal().0).1);
+    }
+    received
+}
+
+/// Receive the first delta from the client, then kill the server and bring up
+/// a new one on the same address. Returns the deltas received by the new server.
+async fn restart_server(tcp_server: TcpServer, send: impl Fn(u32), tcp_client: &TcpClient) -> Result<Vec<BTreeSet<u32>>, String> {
+    let addr = tcp_server.local_addr().map_err(|e| e.to_string())?;
+
+    let op = TcpServerOp::<MyLatRepr>::new(tcp_server);
+    send(1);
+    recv_until(&op, 1).await;
+    assert_eq!(1, tcp_client.connected().await);
+    std::mem::drop(op);
+
+    let tcp_server = TcpServer::bind(addr).await.map_err(|e| e.to_string())?;
+    let op = TcpServerOp::<MyLatRepr>::new(tcp_server);
+    while tcp_client.connected().await < 2 {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    send(2);
+    Ok(recv_until(&op, 2).await)
+}
+
+async fn run_restart(comp: impl Comp<Error = SpinachError>, tcp_server: TcpServer, send: impl Fn(u32), tcp_client: TcpClient) -> Result<Vec<BTreeSet<u32>>, String> {
+    // The client notices the lost connection by reading.
+    let comp = comp.join(TcpClientOp::<MyLatRepr>::new(tcp_client.clone()).comp_null());
+
+    let test = tokio::time::timeout(Duration::from_secs(10), restart_server(tcp_server, send, &tcp_client));
+    tokio::select! {
+        result = comp.run() => Err(format!("Comp ended: {:?}", result.err())),
+        result = test => result.map_err(|_| "Timed out.".to_owned())?,
+    }
+}
+
+#[tokio::test]
+pub async fn test_tcp_client_resend() -> Result<(), String> {
+    let tcp_server = TcpServer::bind("127.0.0.1:0").await.map_err(|e| e.to_string())?;
+    let addr = tcp_server.local_addr().map_err(|e| e.to_string())?;
+    let tcp_client = TcpClient::new_with_backoff(addr, Duration::from_millis(10), Duration::from_millis(100));
+
+    let (op, script) = ScriptedOp::<MyLatRepr>::new();
+    let comp = op
+        .lattice_default::<MyLatRepr>()
+        .comp_tcp_client_resend(tcp_client.clone());
+
+    let send = |x| script.delta(vec![ x ].into_iter().collect());
+    let received = run_restart(comp, tcp_server, send, tcp_client).await?;
+
+    // The new server gets the full value, including the write sent before the restart.
+    let all: BTreeSet<u32> = received.into_iter().flatten().collect();
+    assert_eq!(vec![ 1, 2 ], all.into_iter().collect::<Vec<_>>());
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn test_tcp_client_reconnect() -> Result<(), String> {
+    let tcp_server = TcpServer::bind("127.0.0.1:0").await.map_err(|e| e.to_string())?;
+    let addr = tcp_server.local_addr().map_err(|e| e.to_string())?;
+    let tcp_client = TcpClient::new_with_backoff(addr, Duration::from_millis(10), Duration::from_millis(100));
+
+    let (op, script) = ScriptedOp::<MyLatRepr>::new();
+    let comp = op.comp_tcp_client(tcp_client.clone());
+
+    let send = |x| script.delta(vec![ x ].into_iter().collect());
+    let received = run_restart(comp, tcp_server, send, tcp_client).await?;
+
+    // Only the new delta is sent to the new server.
+    assert_eq!(vec![ vec![ 2 ].into_iter().collect::<BTreeSet<_>>() ], received);
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn test_tcp_client_split_tasks() -> Result<(), String> {
+    let tcp_server = TcpServer::bind("127.0.0.1:0").await.map_err(|e| e.to_string())?;
+    let addr = tcp_server.local_addr().map_err(|e| e.to_string())?;
+    let tcp_client = TcpClient::new_with_backoff(addr, Duration::from_millis(10), Duration::from_millis(100));
+
+    let local = tokio::task::LocalSet::new();
+    local.run_until(async {
+        // The writer and the reader share the client, but wait in separate tasks.
+        let (op, script) = ScriptedOp::<MyLatRepr>::new();
+        let writer = op.comp_tcp_client(tcp_client.clone());
+        tokio::task::spawn_local(async move { writer.run().await });
+
+        let (send_read, mut recv_read) = mpsc::unbounded_channel();
+        let reader = TcpClientOp::<MyLatRepr>::new(tcp_client.clone());
+        tokio::task::spawn_local(async move {
+            while let Some(delta) = future::poll_fn(|ctx| reader.poll_delta(ctx)).await {
+                if send_read.send(delta.into_reveal()).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let op = TcpServerOp::<MyLatRepr>::new(tcp_server);
+        script.delta(vec![ 1 ].into_iter().collect());
+        recv_until(&op, 1).await;
+        std::mem::drop(op);
+
+        // The reader notices the lost connection and starts reconnecting,
+        // then the writer waits on the same reconnect.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        script.delta(vec![ 2 ].into_iter().collect());
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        // Echo server, the reader must be woken to receive the echo.
+        let tcp_server = TcpServer::bind(addr).await.map_err(|e| e.to_string())?;
+        let echo = TcpServerOp::<MyLatRepr>::new(tcp_server.clone())
+            .comp_tcp_server(tcp_server);
+        tokio::task::spawn_local(async move { echo.run().await });
+
+        let received = tokio::time::timeout(Duration::from_secs(10), recv_read.recv()).await
+            .map_err(|_| "Timed out, reader not woken.".to_owned())?;
+        assert_eq!(Some(vec![ 2 ].into_iter().collect::<BTreeSet<_>>()), received);
+        Ok(())
+    }).await
+}
+
+#[tokio::test]
+pub async fn test_tcp_client_oversized() -> Result<(), String> {
+    let tcp_server = TcpServer::bind("127.0.0.1:0").await.map_err(|e| e.to_string())?;
+    let addr = tcp_server.local_addr().map_err(|e| e.to_string())?;
+    let tcp_client = TcpClient::new(addr);
+
+    // A delta too large for one frame fails the comp, instead of being retried.
+    let (op, script) = ScriptedOp::<MyLatRepr>::new();
+    script.delta((0..20_000).collect());
+    let comp = op.comp_tcp_client(tcp_client);
+    match tokio::time::timeout(Duration::from_secs(10), comp.run()).await {
+        Ok(Err(SpinachError::Io(_))) => Ok(()),
+        other => Err(format!("Expected an I/O error, got: {:?}", other)),
+    }
+}
@@ -0,0 +1,58 @@
+use spinach::lattice::set_union::SetUnionRepr;
+use spinach::op::{LatticeOp, Splitter};
+use spinach::tag;
+use spinach::testing::{ScriptedOp, Step, Stepper};
+
+type MyLatRepr = SetUnionRepr<tag::BTREE_SET, usize>;
+type MyDeltaRepr = SetUnionRepr<tag::VEC, usize>;
+
+#[test]
+pub fn test_stepper_lattice() -> Result<(), String> {
+    let (op, script) = ScriptedOp::<MyDeltaRepr>::new();
+    let op = LatticeOp::<_, MyLatRepr>::new(op, Default::default());
+    let stepper = Stepper::new(op);
+
+    stepper.assert_pending();
+    assert_eq!(0, stepper.wake_count());
+
+    script.delta(vec![ 1, 2 ]);
+    assert_eq!(1, stepper.wake_count());
+    stepper.assert_delta::<MyDeltaRepr>(vec![ 2, 1 ]);
+    stepper.assert_value::<MyDeltaRepr>(vec![ 1, 2 ]);
+
+    // Already-known values are suppressed.
+    script.delta(vec![ 2 ]);
+    script.pending();
+    script.delta(vec![ 3 ]);
+    stepper.assert_pending();
+    stepper.assert_delta::<MyDeltaRepr>(vec![ 3 ]);
+    stepper.assert_value::<MyDeltaRepr>(vec![ 1, 2, 3 ]);
+
+    drop(script);
+    stepper.assert_done();
+    Ok(())
+}
+
+#[test]
+pub fn test_stepper_split() -> Result<(), String> {
+    let op = ScriptedOp::<MyDeltaRepr>::from_steps(vec![
+        Step::Delta(vec![ 1 ]),
+        Step::Pending,
+        Step::Delta(vec![ 2, 3 ]),
+    ]);
+    let op = LatticeOp::<_, MyLatRepr>::new(op, Default::default());
+    let splitter = Splitter::new(op);
+    let split_a = Stepper::new(splitter.add_split());
+    let split_b = Stepper::new(splitter.add_split());
+
+    split_a.assert_delta::<MyDeltaRepr>(vec![ 1 ]);
+    // Backpressure: B has not taken its copy yet, so A waits.
+    split_a.assert_pending();
+    split_b.assert_delta::<MyDeltaRepr>(vec![ 1 ]);
+    split_b.assert_pending();
+    split_b.assert_delta::<MyDeltaRepr>(vec![ 3, 2 ]);
+    split_a.assert_delta::<MyDeltaRepr>(vec![ 2, 3 ]);
+    split_a.assert_done();
+    split_b.assert_done();
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use std::sync::{Arc, Mutex};
+
+use spinach::comp::CompExt;
+use spinach::lattice::set_union::SetUnionRepr;
+use spinach::op::{IterOp, OpExt};
+use spinach::tag;
+use spinach::tracing::{self, field, span, Event, Metadata, Subscriber};
+
+type MyLatRepr = SetUnionRepr<tag::HASH_SET, u64>;
+type MyDeltaRepr = SetUnionRepr<tag::VEC, u64>;
+
+/// Collects the `op` field of each `poll_delta` span, and any `repr_bytes`
+/// recorded.
+#[derive(Clone, Default)]
+struct OpNames(Arc<Mutex<Vec<String>>>, Arc<Mutex<Vec<u64>>>);
+
+impl field::Visit for OpNames {
+    fn record_u64(&mut self, field: &field::Field, value: u64) {
+        if "repr_bytes" == field.name() {
+            self.1.lock().unwrap().push(value);
+        }
+    }
+    fn record_debug(&mut self, field: &field::Field, value: &dyn std::fmt::Debug) {
+        if "op" == field.name() {
+            self.0.lock().unwrap().push(format!("{:?}", value).trim_matches('"').to_owned());
+        }
+    }
+}
+
+impl Subscriber for OpNames {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+    fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+        if "poll_delta" == span.metadata().name() {
+            span.record(&mut self.clone());
+        }
+        span::Id::from_u64(1)
+    }
+    fn record(&self, _span: &span::Id, values: &span::Record<'_>) {
+        values.record(&mut self.clone());
+    }
+    fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+    fn event(&self, _event: &Event<'_>) {}
+    fn enter(&self, _span: &span::Id) {}
+    fn exit(&self, _span: &span::Id) {}
+}
+
+#[tokio::test]
+pub async fn test_trace() -> Result<(), String> {
+    let names = OpNames::default();
+    let _guard = tracing::subscriber::set_default(names.clone());
+
+    let op = IterOp::<MyDeltaRepr, _>::new(vec![ vec![ 1 ], vec![ 2 ], vec![ 1 ] ])
+        .trace("input")
+        .lattice_default::<MyLatRepr>();
+    op.comp_null().run().await.map_err(|e| format!("{:?}", e))?;
+
+    let repr_bytes = names.1.lock().unwrap();
+    let names = names.0.lock().unwrap();
+    assert!(names.iter().any(|name| "input" == name));
+    assert!(names.iter().any(|name| "LatticeOp" == name));
+
+    // Only the traced input records sizes: a length prefix and one u64.
+    assert_eq!(vec![ 16, 16, 16 ], *repr_bytes);
+    Ok(())
+}
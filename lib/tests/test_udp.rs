@@ -0,0 +1,41 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::future;
+use tokio::net::UdpSocket;
+
+use spinach::comp::{CompExt, UdpComp};
+use spinach::lattice::set_union::SetUnionRepr;
+use spinach::op::{OnceOp, OpDelta, OpExt, OpValue, UdpOp};
+use spinach::tag;
+
+#[tokio::test]
+pub async fn test_udp_split() -> Result<(), String> {
+    type MyLatRepr = SetUnionRepr<tag::HASH_SET, u64>;
+
+    let socket_send = Arc::new(UdpSocket::bind("127.0.0.1:0").await.map_err(|e| e.to_string())?);
+    let socket_recv = Arc::new(UdpSocket::bind("127.0.0.1:0").await.map_err(|e| e.to_string())?);
+    let addr_recv = socket_recv.local_addr().map_err(|e| e.to_string())?;
+
+    let items: HashSet<u64> = (0..1000).collect();
+
+    // Each datagram fits ~60 items, so the delta must be split.
+    let comp = UdpComp::new_with_size(OnceOp::<MyLatRepr>::new(items.clone()), socket_send, vec![ addr_recv ], 512);
+    comp.run().await.unwrap();
+
+    let op = UdpOp::<MyLatRepr>::new(socket_recv)
+        .lattice_default::<MyLatRepr>();
+    let mut deltas = 0;
+    while op.get_value().into_reveal().len() < items.len() {
+        // Don't hang if a datagram is dropped.
+        tokio::time::timeout(Duration::from_secs(10), future::poll_fn(|ctx| op.poll_delta(ctx))).await
+            .map_err(|_| "Timed out waiting for datagrams.".to_owned())?;
+        deltas += 1;
+    }
+
+    assert_eq!(items, op.get_value().into_reveal());
+    assert!(deltas > 1);
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+#![cfg(unix)]
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use futures::future;
+use tokio::net::UnixStream;
+
+use spinach::comp::CompExt;
+use spinach::lattice::set_union::SetUnionRepr;
+use spinach::op::{OnceOp, OpDelta, OpExt, UnixOp, UnixServerOp};
+use spinach::tag;
+use spinach::unix_server::UnixServer;
+
+#[tokio::test]
+pub async fn test_unix_echo() -> Result<(), String> {
+    type MyLatRepr = SetUnionRepr<tag::HASH_SET, u64>;
+
+    let path = std::env::temp_dir().join(format!("spinach_test_unix_{}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let unix_server = UnixServer::bind(&path).map_err(|e| e.to_string())?;
+    let server_comp = UnixServerOp::<MyLatRepr>::new(unix_server.clone())
+        .comp_unix_server(unix_server);
+
+    let (read, write) = UnixStream::connect(&path).await.map_err(|e| e.to_string())?
+        .into_split();
+
+    let items: HashSet<u64> = (0..10).collect();
+    let client = async {
+        OnceOp::<MyLatRepr>::new(items.clone())
+            .comp_unix(write)
+            .run().await
+            .map_err(|e| e.to_string())?;
+
+        let op = UnixOp::<MyLatRepr>::new(read);
+        let echoed = tokio::time::timeout(Duration::from_secs(10), future::poll_fn(|ctx| op.poll_delta(ctx))).await
+            .map_err(|_| "Timed out.".to_owned())?;
+        Ok::<_, String>(echoed.map(|hide| hide.into_reveal()))
+    };
+
+    let echoed = tokio::select! {
+        result = server_comp.run() => return Err(format!("Server ended: {:?}", result.err())),
+        result = client => result?,
+    };
+    std::fs::remove_file(&path).map_err(|e| e.to_string())?;
+
+    assert_eq!(Some(items), echoed);
+    Ok(())
+}